@@ -0,0 +1,54 @@
+package io.github.memkit.test
+
+import androidx.test.ext.junit.rules.ActivityScenarioRule
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.github.memkit.RustBridge
+import java.util.concurrent.TimeUnit
+import org.junit.Assert.assertEquals
+import org.junit.Assert.assertTrue
+import org.junit.Before
+import org.junit.Rule
+import org.junit.Test
+import org.junit.runner.RunWith
+
+/**
+ * Runs the bridge end to end on a device: JNI_OnLoad, evalJs into the WebView, messages
+ * from JS through onMessageFromJava, and sends from Rust's bridge thread.
+ */
+@RunWith(AndroidJUnit4::class)
+class BridgeInstrumentedTest {
+    @get:Rule
+    val activity = ActivityScenarioRule(TestActivity::class.java)
+
+    @Before
+    fun waitForPage() {
+        lateinit var test: TestActivity
+        activity.scenario.onActivity { test = it }
+        assertTrue("page didn't load", test.pageLoaded.await(10, TimeUnit.SECONDS))
+        RustBridge.fromRust.clear()
+    }
+
+    @Test
+    fun evalReturnsValue() {
+        assertEquals("42", BridgeNatives.eval("return 40 + 2"))
+    }
+
+    @Test
+    fun evalReturnsUnicode() {
+        assertEquals("\"héllo 🦀\"", BridgeNatives.eval("return 'héllo 🦀'"))
+    }
+
+    @Test
+    fun evalReportsErrors() {
+        assertTrue(BridgeNatives.eval("throw new Error('boom')").startsWith("error:"))
+    }
+
+    @Test
+    fun valuesFromJsAreEchoedBack() {
+        BridgeNatives.startEcho("echo_test")
+        RustBridge.evalJs("window.__dioxus_bridge_echo_test({ n: 1, text: 'hi' })")
+        val echoed = RustBridge.fromRust.poll(10, TimeUnit.SECONDS)
+        assertTrue("no message from Rust", echoed != null)
+        assertTrue(echoed!!.contains("\"text\":\"hi\""))
+    }
+}
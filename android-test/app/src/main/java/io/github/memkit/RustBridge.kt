@@ -0,0 +1,65 @@
+package io.github.memkit
+
+import android.app.Activity
+import android.os.Handler
+import android.os.Looper
+import android.webkit.JavascriptInterface
+import android.webkit.WebView
+import androidx.webkit.WebViewCompat
+import androidx.webkit.WebViewFeature
+import java.util.concurrent.LinkedBlockingQueue
+
+/**
+ * The Kotlin half of the bridge, as an app would write it. Rust calls [evalJs] and
+ * [onMessageFromRust]; the page posts messages through
+ * `window.dxNative`, or `window.RustBridge.postMessage` where WebMessageListener isn't
+ * supported.
+ */
+object RustBridge {
+    private val mainHandler = Handler(Looper.getMainLooper())
+
+    @Volatile
+    var webView: WebView? = null
+
+    /** Messages Rust sent with `send_to_java`, for tests to inspect. */
+    val fromRust = LinkedBlockingQueue<String>()
+
+    fun attach(activity: Activity, view: WebView) {
+        webView = view
+        view.addJavascriptInterface(JsInterface, "RustBridge")
+        // Reports which frame sent each message, for bridges with an origin policy
+        if (WebViewFeature.isFeatureSupported(WebViewFeature.WEB_MESSAGE_LISTENER)) {
+            WebViewCompat.addWebMessageListener(view, "dxNative", setOf("*")) {
+                _, message, sourceOrigin, isMainFrame, _ ->
+                message.data?.let { onMessageFromFrame(it, sourceOrigin.toString(), isMainFrame) }
+            }
+        }
+        registerInstance(activity)
+    }
+
+    @JvmStatic
+    fun evalJs(script: String) {
+        mainHandler.post { webView?.evaluateJavascript(script, null) }
+    }
+
+    @JvmStatic
+    fun onMessageFromRust(message: String) {
+        fromRust.put(message)
+    }
+
+    @JvmStatic
+    external fun onMessageFromJava(callbackId: String, json: String)
+
+    @JvmStatic
+    external fun onMessageFromFrame(message: String, sourceOrigin: String, isMainFrame: Boolean)
+
+    @JvmStatic
+    external fun registerInstance(activity: Activity)
+
+    private object JsInterface {
+        @JavascriptInterface
+        fun postMessage(callbackId: String, json: String) {
+            onMessageFromJava(callbackId, json)
+        }
+    }
+}
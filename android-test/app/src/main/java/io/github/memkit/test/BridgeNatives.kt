@@ -0,0 +1,16 @@
+package io.github.memkit.test
+
+/** Test entry points implemented in the Rust crate at the root of `android-test/`. */
+object BridgeNatives {
+    init {
+        System.loadLibrary("dx_use_js_bridge_android_test")
+    }
+
+    /** Evaluates [script] through the bridge and returns its JSON result or `error: ...`. */
+    @JvmStatic
+    external fun eval(script: String): String
+
+    /** Registers a bridge under [id] that echoes every value back through `onMessageFromRust`. */
+    @JvmStatic
+    external fun startEcho(id: String)
+}
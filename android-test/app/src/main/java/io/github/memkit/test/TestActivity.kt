@@ -0,0 +1,29 @@
+package io.github.memkit.test
+
+import android.app.Activity
+import android.os.Bundle
+import android.webkit.WebView
+import android.webkit.WebViewClient
+import io.github.memkit.RustBridge
+import java.util.concurrent.CountDownLatch
+
+/** Hosts a blank WebView wired to the bridge. */
+class TestActivity : Activity() {
+    val pageLoaded = CountDownLatch(1)
+
+    override fun onCreate(savedInstanceState: Bundle?) {
+        super.onCreate(savedInstanceState)
+        // Loads the native library, which runs JNI_OnLoad.
+        BridgeNatives
+        val view = WebView(this)
+        view.settings.javaScriptEnabled = true
+        view.webViewClient = object : WebViewClient() {
+            override fun onPageFinished(view: WebView, url: String) {
+                pageLoaded.countDown()
+            }
+        }
+        setContentView(view)
+        RustBridge.attach(this, view)
+        view.loadDataWithBaseURL("https://appassets.test/", "<html><body></body></html>", "text/html", "utf-8", null)
+    }
+}
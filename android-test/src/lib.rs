@@ -0,0 +1,58 @@
+#![cfg(target_os = "android")]
+
+use dx_use_js_bridge::{eval_as, RawBridge};
+use futures_util::StreamExt;
+use jni::objects::{JClass, JString};
+use jni::sys::jstring;
+use jni::JNIEnv;
+
+fn rust_string(env: &mut JNIEnv, value: &JString) -> String {
+    env.get_string(value).map(Into::into).unwrap_or_default()
+}
+
+/// Evaluates `script` through the bridge and returns its JSON result, or `error: ...`.
+/// Goes through `evalJs` and back through `onMessageFromJava`.
+#[no_mangle]
+pub extern "system" fn Java_io_github_memkit_test_BridgeNatives_eval(
+    mut env: JNIEnv,
+    _class: JClass,
+    script: JString,
+) -> jstring {
+    let script = rust_string(&mut env, &script);
+    let result = match pollster::block_on(eval_as::<serde_json::Value>(&script)) {
+        Ok(value) => value.to_string(),
+        Err(e) => format!("error: {e}"),
+    };
+    env.new_string(result)
+        .map(|result| result.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Registers a bridge with callback ID `id` that sends every value it receives back to
+/// Kotlin through `onMessageFromRust`. Returns once the bridge is listening.
+#[no_mangle]
+pub extern "system" fn Java_io_github_memkit_test_BridgeNatives_startEcho(
+    mut env: JNIEnv,
+    _class: JClass,
+    id: JString,
+) {
+    let id = rust_string(&mut env, &id);
+    let (listening, registered) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let bridge = RawBridge::with_id(id);
+        let mut messages = bridge.listen();
+        let _ = listening.send(());
+        pollster::block_on(async move {
+            while let Some(message) = messages.next().await {
+                let echoed = match message {
+                    Ok(value) => bridge.send(&value).await,
+                    Err(e) => bridge.send(&serde_json::json!({ "error": e })).await,
+                };
+                if let Err(e) = echoed {
+                    eprintln!("Echo failed: {e}");
+                }
+            }
+        });
+    });
+    let _ = registered.recv();
+}
@@ -0,0 +1,159 @@
+use base64::Engine;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dx_use_js_bridge::{json_diff, Codec, FromJs, JsonCodec, PreparedMessage};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Entity {
+    id: u32,
+    kind: String,
+    position: [f64; 3],
+    health: u32,
+    tags: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Snapshot {
+    tick: u64,
+    entities: Vec<Entity>,
+}
+
+fn snapshot(entities: u32) -> Snapshot {
+    Snapshot {
+        tick: 42,
+        entities: (0..entities)
+            .map(|id| Entity {
+                id,
+                kind: "unit".to_string(),
+                position: [id as f64 * 1.5, id as f64 * -0.25, 0.0],
+                health: 100 - (id % 100),
+                tags: vec!["visible".to_string(), "selectable".to_string()],
+            })
+            .collect(),
+    }
+}
+
+/// MessagePack carried as a base64 string, the way the `Codec` docs suggest for binary
+/// formats.
+#[derive(Clone)]
+struct MessagePackCodec;
+
+impl Codec for MessagePackCodec {
+    const NAME: &'static str = "msgpack";
+
+    fn encode<S: Serialize>(value: &S) -> Result<String, String> {
+        let bytes = rmp_serde::to_vec_named(value).map_err(|e| e.to_string())?;
+        serde_json::to_string(&base64::engine::general_purpose::STANDARD.encode(bytes))
+            .map_err(|e| e.to_string())
+    }
+
+    fn decode<T: FromJs>(wire: serde_json::Value) -> Result<T, String> {
+        let text = wire.as_str().ok_or("expected a base64 string")?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .map_err(|e| e.to_string())?;
+        rmp_serde::from_slice(&bytes).map_err(|e| e.to_string())
+    }
+}
+
+fn codec<C: Codec>(c: &mut Criterion, name: &str) {
+    let mut group = c.benchmark_group(format!("codec/{name}"));
+    for entities in [1, 100, 10_000] {
+        let value = snapshot(entities);
+        let encoded = C::encode(&value).unwrap();
+        let wire: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+        group.throughput(Throughput::Bytes(encoded.len() as u64));
+        group.bench_with_input(BenchmarkId::new("encode", entities), &value, |b, value| {
+            b.iter(|| C::encode(value).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("decode", entities), &wire, |b, wire| {
+            b.iter(|| C::decode::<Snapshot>(wire.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn codecs(c: &mut Criterion) {
+    codec::<JsonCodec>(c, "json");
+    codec::<MessagePackCodec>(c, "msgpack");
+}
+
+/// Sending one value to many bridges: encoding per bridge against a `PreparedMessage`.
+fn broadcast(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadcast_to_16_bridges");
+    for entities in [1, 1_000] {
+        let value = snapshot(entities);
+        group.bench_with_input(
+            BenchmarkId::new("encode_each", entities),
+            &value,
+            |b, value| {
+                b.iter(|| {
+                    (0..16)
+                        .map(|_| JsonCodec::encode(value).unwrap().len())
+                        .sum::<usize>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("prepared", entities),
+            &value,
+            |b, value| {
+                b.iter(|| {
+                    let prepared = PreparedMessage::new(value).unwrap();
+                    (0..16)
+                        .map(|_| prepared.clone().as_json().len())
+                        .sum::<usize>()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Delta mode: diffing against the previous value and sending the patch, against sending
+/// the whole snapshot, when one entity moved.
+fn delta(c: &mut Criterion) {
+    let mut group = c.benchmark_group("delta");
+    for entities in [100, 10_000] {
+        let before = serde_json::to_value(snapshot(entities)).unwrap();
+        let mut moved = snapshot(entities);
+        moved.entities[0].position[0] += 1.0;
+        let after = serde_json::to_value(moved).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("snapshot", entities),
+            &after,
+            |b, after| b.iter(|| serde_json::to_string(after).unwrap()),
+        );
+        group.bench_with_input(BenchmarkId::new("patch", entities), &after, |b, after| {
+            b.iter(|| serde_json::to_string(&json_diff(&before, after)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// The Rust half of a web send: evaluating a script that embeds the JSON, against handing
+/// the JSON to the registered function for `JSON.parse`. What the page pays for each is
+/// measured by `node benches/send_path.js`.
+fn send_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("send_path");
+    for entities in [1, 100, 10_000] {
+        let json = JsonCodec::encode(&snapshot(entities)).unwrap();
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::new("eval", entities), &json, |b, json| {
+            b.iter(|| {
+                format!(
+                    "if (window.dxBridge) {{ window.dxBridge.send(\"{}\", {}); }}",
+                    "callback_1", json
+                )
+                .len()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("call1", entities), &json, |b, json| {
+            b.iter(|| std::hint::black_box(json.as_str()).len())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, codecs, broadcast, delta, send_path);
+criterion_main!(benches);
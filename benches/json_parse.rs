@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+
+/// A state snapshot like the ones games and maps send, with `entities` entries.
+fn snapshot(entities: usize) -> String {
+    let entities: Vec<Value> = (0..entities)
+        .map(|i| {
+            json!({
+                "id": i,
+                "kind": "unit",
+                "position": { "x": i as f64 * 1.5, "y": i as f64 * -0.25, "z": 0.0 },
+                "velocity": [0.5, -1.25, 0.0],
+                "health": 100 - (i % 100),
+                "tags": ["visible", "selectable"],
+            })
+        })
+        .collect();
+    json!({ "tick": 42, "entities": entities }).to_string()
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inbound_parse");
+    for entities in [10, 1_000, 20_000] {
+        let json = snapshot(entities);
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("serde_json", entities),
+            &json,
+            |b, json| b.iter(|| serde_json::from_str::<Value>(json).unwrap()),
+        );
+        group.bench_with_input(BenchmarkId::new("simd_json", entities), &json, |b, json| {
+            // The bridge owns the received string, so the copy here is only for reuse
+            b.iter(|| {
+                let mut bytes = json.clone().into_bytes();
+                simd_json::serde::from_slice::<Value>(&mut bytes).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
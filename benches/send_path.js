@@ -0,0 +1,34 @@
+// The JS half of the `send_path` benchmark in codecs.rs: what the page pays per message on
+// the web when Rust evals a script embedding the JSON, against calling the registered
+// function with `JSON.parse` of it. Run with `node benches/send_path.js`.
+const snapshot = (entities, tick) => ({
+    tick,
+    entities: Array.from({ length: entities }, (_, id) => ({
+        id,
+        kind: "unit",
+        position: [id * 1.5, id * -0.25, 0],
+        health: 100 - (id % 100),
+        tags: ["visible", "selectable"],
+    })),
+});
+
+let sink = 0;
+globalThis.window = globalThis;
+window.dxBridge = { send: (_id, value) => { sink += value.tick; } };
+const callback = (value) => { sink += value.tick; };
+
+for (const entities of [1, 100, 10000]) {
+    const iterations = entities === 10000 ? 200 : entities === 100 ? 5000 : 50000;
+    const messages = Array.from({ length: 64 }, (_, i) => JSON.stringify(snapshot(entities, i)));
+    // Real payloads differ from message to message, and V8 caches evals of identical text
+    const message = (i) => messages[i % 64].replace('"tick":', `"tick":${i},"previous":`);
+    const run = (label, send) => {
+        for (let i = 0; i < iterations / 10; i++) send(message(i));
+        const start = process.hrtime.bigint();
+        for (let i = 0; i < iterations; i++) send(message(i));
+        const micros = Number(process.hrtime.bigint() - start) / iterations / 1000;
+        console.log(`send_path/${label}/${entities}: ${micros.toFixed(2)} us (${messages[0].length} bytes)`);
+    };
+    run("eval", (json) => (0, eval)(`if (window.dxBridge) { window.dxBridge.send("cb", ${json}); }`));
+    run("call1", (json) => callback(JSON.parse(json)));
+}
@@ -0,0 +1,22 @@
+package dev.dioxus.main
+
+import android.webkit.WebView
+import io.github.memkit.RustBridge
+
+/**
+ * Replaces the activity `dx` generates, to hand the WebView to [RustBridge] and to pause
+ * the game on the back button.
+ */
+class MainActivity : WryActivity() {
+    private var webView: WebView? = null
+
+    override fun onWebViewCreate(webView: WebView) {
+        this.webView = webView
+        RustBridge.attach(this, webView)
+    }
+
+    @Deprecated("Kept for API levels without OnBackPressedDispatcher")
+    override fun onBackPressed() {
+        webView?.evaluateJavascript("window.game && window.game.pause()", null)
+    }
+}
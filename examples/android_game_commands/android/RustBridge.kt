@@ -0,0 +1,68 @@
+package io.github.memkit
+
+import android.app.Activity
+import android.os.Handler
+import android.os.Looper
+import android.webkit.JavascriptInterface
+import android.webkit.WebView
+import androidx.webkit.WebViewCompat
+import androidx.webkit.WebViewFeature
+
+/**
+ * The Kotlin half of the bridge. Rust calls [evalJs] to run scripts in the WebView and
+ * [onMessageFromRust] to deliver values; the page posts to Rust through
+ * `window.dxNative`, a WebMessageListener (add `androidx.webkit:webkit` to the app), or
+ * `window.RustBridge.postMessage(callbackId, json)` where that isn't supported. Copy it
+ * into the generated Android project, keeping the package: the crate's JNI functions are
+ * exported for this class.
+ */
+object RustBridge {
+    private val mainHandler = Handler(Looper.getMainLooper())
+
+    @Volatile
+    private var webView: WebView? = null
+
+    fun attach(activity: Activity, view: WebView) {
+        webView = view
+        view.addJavascriptInterface(JsInterface, "RustBridge")
+        // Reports which frame sent each message, for bridges with an origin policy
+        if (WebViewFeature.isFeatureSupported(WebViewFeature.WEB_MESSAGE_LISTENER)) {
+            WebViewCompat.addWebMessageListener(view, "dxNative", setOf("*")) {
+                _, message, sourceOrigin, isMainFrame, _ ->
+                message.data?.let { onMessageFromFrame(it, sourceOrigin.toString(), isMainFrame) }
+            }
+        }
+        registerInstance(activity)
+    }
+
+    @JvmStatic
+    fun evalJs(script: String) {
+        mainHandler.post { webView?.evaluateJavascript(script, null) }
+    }
+
+    /** Values Rust sends as `{"callback_id": id, "data": value}`; hand them to the page. */
+    @JvmStatic
+    fun onMessageFromRust(message: String) {
+        evalJs(
+            "(() => { const message = $message; " +
+                "const callback = window['__dioxus_bridge_' + message.callback_id]; " +
+                "if (callback) callback(message.data); })();"
+        )
+    }
+
+    @JvmStatic
+    external fun onMessageFromJava(callbackId: String, json: String)
+
+    @JvmStatic
+    external fun onMessageFromFrame(message: String, sourceOrigin: String, isMainFrame: Boolean)
+
+    @JvmStatic
+    external fun registerInstance(activity: Activity)
+
+    private object JsInterface {
+        @JavascriptInterface
+        fun postMessage(callbackId: String, json: String) {
+            onMessageFromJava(callbackId, json)
+        }
+    }
+}
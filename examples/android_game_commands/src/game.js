@@ -0,0 +1,40 @@
+// Page side of the game, installed once by Rust with install_script: touch controls that
+// send commands, a HUD fed by the game store, and save slots in localStorage.
+window.game = {
+  // `sendCommand` delivers a command to Rust's bridge
+  connect(sendCommand) {
+    const pad = document.getElementById("pad");
+    const hud = document.getElementById("hud");
+    pad.replaceChildren();
+    const button = (label, command) => {
+      const element = document.createElement("button");
+      element.textContent = label;
+      element.ontouchstart = element.onclick = (event) => {
+        event.preventDefault();
+        sendCommand(command);
+      };
+      pad.append(element);
+    };
+    button("◀", { Move: { dx: -1, dy: 0 } });
+    button("▲", { Move: { dx: 0, dy: -1 } });
+    button("▼", { Move: { dx: 0, dy: 1 } });
+    button("▶", { Move: { dx: 1, dy: 0 } });
+    button("Fire", "Fire");
+    this.pause = () => sendCommand("Pause");
+
+    window.dxStore("game").subscribe((state) => {
+      hud.textContent = `score ${state.score} at (${state.x}, ${state.y})`
+        + (state.paused ? " (paused)" : "");
+    });
+  },
+
+  // Called from Rust through bound JsFunctions; saves are base64 encoded bytes
+  save: (slot, base64) => {
+    localStorage.setItem(`save-${slot}`, base64);
+    return base64.length;
+  },
+  load: (slot) => localStorage.getItem(`save-${slot}`),
+
+  // Replaced by `connect`; Kotlin calls it on the back button
+  pause: () => {},
+};
@@ -0,0 +1,158 @@
+// A small game driven by commands from touch controls in page JS and from Kotlin. It
+// shows a store synced to JS, commands limited by topic, JS functions called from Rust, and
+// bytes round-tripped through JS as base64 save slots.
+//
+// Build it with `dx serve --platform android` from this directory, then add the Kotlin glue
+// in `android/` to the generated project: `RustBridge.kt` forwards WebView messages to Rust
+// and evals from Rust to the WebView, and `MainActivity.kt` hands it the WebView.
+use base64::Engine;
+use dioxus::prelude::*;
+use dx_use_js_bridge::{
+    use_bridge_store, use_js_bridge_with_options, BridgeOptions, JsFunction, Permissions,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Command {
+    Move { dx: i32, dy: i32 },
+    Fire,
+    Pause,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Game {
+    x: i32,
+    y: i32,
+    score: u32,
+    paused: bool,
+}
+
+fn apply(game: &mut Game, command: Command) {
+    match command {
+        Command::Pause => game.paused = !game.paused,
+        _ if game.paused => {}
+        Command::Move { dx, dy } => {
+            game.x = (game.x + dx).clamp(0, 9);
+            game.y = (game.y + dy).clamp(0, 9);
+        }
+        Command::Fire => game.score += 10,
+    }
+}
+
+/// The JS save slot functions, bound once the page glue is installed.
+#[derive(Clone)]
+struct Slots {
+    save: JsFunction<(u32, String), usize>,
+    load: JsFunction<(u32,), Option<String>>,
+}
+
+fn main() {
+    dioxus::launch(App);
+}
+
+#[component]
+fn App() -> Element {
+    let mut game = use_bridge_store("game", Game::default, apply);
+    let commands = use_js_bridge_with_options::<Command>(BridgeOptions {
+        permissions: Some(
+            Permissions::new()
+                .allow("Move")
+                .allow("Fire")
+                .allow("Pause"),
+        ),
+        ..Default::default()
+    });
+    let mut slots = use_signal(|| None::<Slots>);
+    let mut status = use_signal(String::new);
+
+    let setup = commands.clone();
+    use_future(move || {
+        let mut commands = setup.clone();
+        async move {
+            let connected = async {
+                commands.install_script(include_str!("game.js")).await?;
+                commands
+                    .eval(&format!(
+                        "window.game.connect((command) => {{ const callback = {}; if (callback) callback(command); }});",
+                        commands.js_callback()
+                    ))
+                    .await?;
+                Ok::<_, String>(Slots {
+                    save: commands.bind_function("window.game.save").await?,
+                    load: commands.bind_function("window.game.load").await?,
+                })
+            };
+            match connected.await {
+                Ok(bound) => slots.set(Some(bound)),
+                Err(e) => commands.set_error(Some(e)),
+            }
+        }
+    });
+
+    let listener = commands.clone();
+    use_future(move || {
+        let mut received = listener.stream();
+        async move {
+            while let Some(command) = received.next().await {
+                game.update(|game| apply(game, command));
+            }
+        }
+    });
+
+    // Saves are bytes; they cross the bridge base64 encoded
+    let save = move |_| async move {
+        let Some(slots) = slots() else {
+            return;
+        };
+        let saved = async {
+            let bytes = serde_json::to_vec(&game.get()).map_err(|e| e.to_string())?;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+            slots.save.call(&(1, encoded)).await
+        };
+        status.set(match saved.await {
+            Ok(size) => format!("Saved {size} characters"),
+            Err(e) => format!("Save failed: {e}"),
+        });
+    };
+    let load = move |_| async move {
+        let Some(slots) = slots() else {
+            return;
+        };
+        let loaded = async {
+            let encoded = slots.load.call(&(1,)).await?.ok_or("Nothing saved yet")?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| e.to_string())?;
+            serde_json::from_slice::<Game>(&bytes).map_err(|e| e.to_string())
+        };
+        match loaded.await {
+            Ok(saved) => {
+                game.update(|game| *game = saved);
+                status.set("Loaded".to_string());
+            }
+            Err(e) => status.set(format!("Load failed: {e}")),
+        }
+    };
+
+    let state = game.get();
+    rsx! {
+        div { id: "board",
+            for y in 0..10 {
+                div {
+                    for x in 0..10 {
+                        span { if (x, y) == (state.x, state.y) { "■" } else { "·" } }
+                    }
+                }
+            }
+        }
+        div { id: "pad" }
+        p { id: "hud" }
+        button { onclick: save, "Save" }
+        button { onclick: load, "Load" }
+        p { "{status}" }
+        if let Some(error) = commands.get_error() {
+            p { "Bridge error: {error}" }
+        }
+    }
+}
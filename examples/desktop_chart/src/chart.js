@@ -0,0 +1,56 @@
+// Page side of the desktop chart, installed once by Rust with install_script. It draws
+// the synced "series" signal on a canvas and reports pointer events to Rust.
+window.chart = {
+  // `sendEvent` delivers an event to Rust's bridge
+  connect(sendEvent) {
+    const canvas = document.getElementById("chart");
+    const context = canvas.getContext("2d");
+    let series = window.dxSignals.get("series") || [];
+
+    const draw = () => {
+      context.clearRect(0, 0, canvas.width, canvas.height);
+      if (series.length < 2) return;
+      const min = Math.min(...series);
+      const max = Math.max(...series);
+      const y = (value) => canvas.height - ((value - min) / (max - min || 1)) * canvas.height;
+      context.beginPath();
+      series.forEach((value, index) => {
+        const x = (index / (series.length - 1)) * canvas.width;
+        index === 0 ? context.moveTo(x, y(value)) : context.lineTo(x, y(value));
+      });
+      context.strokeStyle = "#3b82f6";
+      context.lineWidth = 2;
+      context.stroke();
+    };
+    const indexAt = (event) =>
+      Math.round((event.offsetX / canvas.width) * (series.length - 1));
+
+    // Rust's writes arrive here; so do JS's own
+    window.dxSignals.subscribe("series", ({ value }) => {
+      series = value || [];
+      draw();
+    });
+    draw();
+
+    // Events carry their topic, which the bridge checks against its permissions
+    canvas.onmousemove = (event) => {
+      const index = indexAt(event);
+      if (index >= 0 && index < series.length) {
+        sendEvent({ topic: "hover", index, value: series[index] });
+      }
+    };
+    let start = null;
+    canvas.onmousedown = (event) => (start = indexAt(event));
+    canvas.onmouseup = (event) => {
+      if (start === null) return;
+      const end = indexAt(event);
+      sendEvent({ topic: "select", from: Math.min(start, end), to: Math.max(start, end) });
+      start = null;
+    };
+    // JS may write the signal too; Rust sees the cleared series
+    canvas.ondblclick = () => window.dxSignals.set("series", []);
+  },
+
+  // Called from Rust through a bound JsFunction
+  exportPng: () => document.getElementById("chart").toDataURL("image/png"),
+};
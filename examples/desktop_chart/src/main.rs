@@ -0,0 +1,119 @@
+// A live chart drawn by page JS from a signal Rust keeps filling. It shows a signal synced
+// to JS, chart events limited by topic, a JS function called from Rust, and bytes from JS
+// saved to disk. Run it from this directory with `cargo run`.
+use base64::Engine;
+use dioxus::prelude::*;
+use dx_use_js_bridge::{
+    use_js_bridge_with_options, use_synced_signal, BridgeOptions, JsFunction, Permissions,
+};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Points kept on the chart.
+const POINTS: usize = 120;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "topic", rename_all = "snake_case")]
+enum ChartEvent {
+    Hover { index: usize, value: f64 },
+    Select { from: usize, to: usize },
+}
+
+fn main() {
+    dioxus::launch(App);
+}
+
+#[component]
+fn App() -> Element {
+    // Mirrored to `dxSignals.get("series")`; the chart redraws on every write
+    let mut series = use_synced_signal::<Vec<f64>>("series");
+    let events = use_js_bridge_with_options::<ChartEvent>(BridgeOptions {
+        permissions: Some(Permissions::new().allow("hover").allow("select")),
+        ..Default::default()
+    });
+    let mut export = use_signal(|| None::<JsFunction<(), String>>);
+    let mut status = use_signal(String::new);
+
+    // Installs the chart, hands it the event callback and binds the exporter
+    let setup = events.clone();
+    use_future(move || {
+        let mut events = setup.clone();
+        async move {
+            let connected = async {
+                events.install_script(include_str!("chart.js")).await?;
+                events
+                    .eval(&format!(
+                        "window.chart.connect((event) => {{ const callback = {}; if (callback) callback(event); }});",
+                        events.js_callback()
+                    ))
+                    .await?;
+                events.bind_function("window.chart.exportPng").await
+            };
+            match connected.await {
+                Ok(function) => export.set(Some(function)),
+                Err(e) => events.set_error(Some(e)),
+            }
+        }
+    });
+
+    // A new point every 100 ms
+    use_future(move || async move {
+        for tick in 0u64.. {
+            futures_timer::Delay::new(Duration::from_millis(100)).await;
+            let t = tick as f64 / 10.0;
+            series.with_mut(|series| {
+                let points = series.get_or_insert_with(Vec::new);
+                points.push(t.sin() + (t * 0.37).cos() * 0.5);
+                if points.len() > POINTS {
+                    points.remove(0);
+                }
+            });
+        }
+    });
+
+    let listener = events.clone();
+    use_future(move || {
+        let mut received = listener.stream();
+        async move {
+            while let Some(event) = received.next().await {
+                status.set(match event {
+                    ChartEvent::Hover { index, value } => format!("Point {index}: {value:.3}"),
+                    ChartEvent::Select { from, to } => format!("Selected points {from} to {to}"),
+                });
+            }
+        }
+    });
+
+    // The PNG comes back as a data URL and is written next to the working directory
+    let save_png = move |_| async move {
+        let Some(export) = export() else {
+            return;
+        };
+        let saved = async {
+            let url = export.call(&()).await?;
+            let encoded = url
+                .strip_prefix("data:image/png;base64,")
+                .ok_or("Unexpected data URL")?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| e.to_string())?;
+            std::fs::write("chart.png", &bytes).map_err(|e| e.to_string())?;
+            Ok::<_, String>(bytes.len())
+        };
+        status.set(match saved.await {
+            Ok(size) => format!("Saved chart.png ({size} bytes)"),
+            Err(e) => format!("Export failed: {e}"),
+        });
+    };
+
+    rsx! {
+        canvas { id: "chart", width: "640", height: "240" }
+        p { "{status}" }
+        button { onclick: save_png, "Save as PNG" }
+        button { onclick: move |_| series.set(Some(Vec::new())), "Clear" }
+        if let Some(error) = events.get_error() {
+            p { "Bridge error: {error}" }
+        }
+    }
+}
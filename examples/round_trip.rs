@@ -0,0 +1,72 @@
+// Measures round trips through each transport of the current platform: eval, and a value
+// sent to the bridge's JS callback and back. Run it on web (postMessage-free direct calls
+// into `window.dxBridge`), desktop (IPC) or Android to compare, e.g.
+// `dx serve --example round_trip --platform web`.
+use dioxus::prelude::*;
+use dx_use_js_bridge::{eval_as, use_js_bridge};
+use futures_util::StreamExt;
+
+const ROUNDS: u32 = 200;
+
+fn main() {
+    dioxus::launch(App);
+}
+
+fn now_us() -> i64 {
+    chrono::Utc::now().timestamp_micros()
+}
+
+/// Formats the mean of `total_us` over [`ROUNDS`].
+fn mean(label: &str, total_us: i64) -> String {
+    format!(
+        "{label}: {:.3} ms",
+        total_us as f64 / ROUNDS as f64 / 1000.0
+    )
+}
+
+#[component]
+fn App() -> Element {
+    let bridge = use_js_bridge::<Vec<u32>>();
+    let mut report = use_signal(Vec::<String>::new);
+
+    let run = move |_| {
+        let mut bridge = bridge.clone();
+        spawn(async move {
+            report.set(Vec::new());
+
+            let started = now_us();
+            for round in 0..ROUNDS {
+                if let Err(e) = eval_as::<u32>(&format!("return {round};")).await {
+                    report.push(format!("eval failed: {e}"));
+                    return;
+                }
+            }
+            report.push(mean("eval round trip", now_us() - started));
+
+            // Values sent to the bridge's own JS callback come straight back to Rust
+            let mut echoes = bridge.stream();
+            for size in [1, 10_000] {
+                let payload: Vec<u32> = (0..size).collect();
+                let started = now_us();
+                for _ in 0..ROUNDS {
+                    if let Err(e) = bridge.send_to_js(&payload).await {
+                        report.push(format!("send failed: {e}"));
+                        return;
+                    }
+                    echoes.next().await;
+                }
+                report.push(mean(
+                    &format!("callback round trip, {size} numbers"),
+                    now_us() - started,
+                ));
+            }
+        });
+    };
+
+    rsx! {
+        button { onclick: run, "Run {ROUNDS} rounds" }
+        for line in report.read().iter() {
+            div { "{line}" }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use dioxus::prelude::*;
+use dx_use_js_bridge::{
+    use_bridge_store, use_js_bridge_with_options, use_js_port, BridgeOptions, JsFunction,
+    Permissions,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Command {
+    Increment(i64),
+    Reset,
+    /// Not permitted from JS, to show a rejected topic.
+    SetTo(i64),
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct Counter {
+    count: i64,
+    history: Vec<i64>,
+}
+
+fn apply(counter: &mut Counter, command: Command) {
+    match command {
+        Command::Increment(by) => counter.count += by,
+        Command::Reset => counter.count = 0,
+        Command::SetTo(count) => counter.count = count,
+    }
+    counter.history.push(counter.count);
+}
+
+#[component]
+pub fn App() -> Element {
+    // Mirrored to JS as `dxStore("counter")`; JS may also dispatch commands to it
+    let mut store = use_bridge_store("counter", Counter::default, apply);
+    let commands = use_js_bridge_with_options::<Command>(BridgeOptions {
+        permissions: Some(Permissions::new().allow("Increment").allow("Reset")),
+        ..Default::default()
+    });
+    let port = use_js_port::<serde_json::Value>();
+    let mut format = use_signal(|| None::<JsFunction<(i64,), String>>);
+    let mut bytes_from_js = use_signal(|| None::<String>);
+
+    // Installs the page glue, hands it the command callback and the port, and binds the
+    // formatter
+    let setup = (commands.clone(), port.clone());
+    use_future(move || {
+        let (mut commands, port) = setup.clone();
+        async move {
+            let connected = async {
+                commands.install_script(include_str!("counter.js")).await?;
+                commands
+                    .eval(&format!(
+                        "window.counterJs.connect((command) => {{ const callback = {}; if (callback) callback(command); }}, {});",
+                        commands.js_callback(),
+                        port.js_port()
+                    ))
+                    .await?;
+                commands.bind_function("window.counterJs.format").await
+            };
+            match connected.await {
+                Ok(function) => format.set(Some(function)),
+                Err(e) => commands.set_error(Some(e)),
+            }
+        }
+    });
+
+    let listener = commands.clone();
+    use_future(move || {
+        let mut received = listener.stream();
+        async move {
+            while let Some(command) = received.next().await {
+                store.update(|counter| apply(counter, command));
+            }
+        }
+    });
+
+    let binary = port.clone();
+    use_future(move || {
+        let mut received = binary.binary();
+        async move {
+            while let Some(bytes) = received.next().await {
+                bytes_from_js.set(Some(String::from_utf8_lossy(&bytes).into_owned()));
+            }
+        }
+    });
+
+    let count = store.get().count;
+    let formatted = use_resource(move || async move {
+        let Some(format) = format() else {
+            return String::new();
+        };
+        format.call(&(count,)).await.unwrap_or_else(|e| e)
+    });
+
+    let send_bytes = move |_| {
+        let text = format!("count is {count}");
+        if let Err(e) = port.send_bytes(text.as_bytes()) {
+            eprintln!("{e}");
+        }
+    };
+
+    rsx! {
+        h1 { "Count: {count}" }
+        p { "Formatted in JS: {formatted.read().clone().unwrap_or_default()}" }
+        button { onclick: move |_| store.update(|counter| apply(counter, Command::Increment(1))), "Rust +1" }
+        button { onclick: send_bytes, "Rust sends bytes" }
+        div { id: "js-toolbar" }
+        p { id: "js-mirror" }
+        if let Some(error) = commands.get_error() {
+            p { "Bridge error: {error}" }
+        }
+        if let Some(text) = bytes_from_js() {
+            p { "Bytes from JS: {text}" }
+        }
+    }
+}
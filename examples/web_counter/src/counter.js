@@ -0,0 +1,41 @@
+// Page side of the web counter, installed once by Rust with install_script.
+window.counterJs = {
+  // Called from Rust through a bound JsFunction
+  format: (count) =>
+    new Intl.NumberFormat(undefined, { signDisplay: "exceptZero" }).format(count),
+
+  // `sendCommand` delivers a command to Rust's bridge; `port` is the JsPort's end
+  connect(sendCommand, port) {
+    const toolbar = document.getElementById("js-toolbar");
+    const mirror = document.getElementById("js-mirror");
+    toolbar.replaceChildren();
+    const button = (label, onclick) => {
+      const element = document.createElement("button");
+      element.textContent = label;
+      element.onclick = onclick;
+      toolbar.append(element);
+    };
+
+    // Commands are externally tagged enums; their variant name is the topic
+    button("JS +10", () => sendCommand({ Increment: 10 }));
+    button("JS reset", () => sendCommand("Reset"));
+    button("JS set to 100 (not permitted)", () => sendCommand({ SetTo: 100 }));
+
+    // Bytes go to Rust as transferred ArrayBuffers
+    button("JS sends bytes", () => {
+      const bytes = new TextEncoder().encode(`hello from JS at ${new Date().toISOString()}`);
+      port.postMessage(bytes.buffer, [bytes.buffer]);
+    });
+    port.onmessage = (event) => {
+      if (event.data instanceof ArrayBuffer) {
+        mirror.dataset.bytes = new TextDecoder().decode(event.data);
+      }
+    };
+
+    // The store is pushed to JS as patches; subscribers get the patched state
+    window.dxStore("counter").subscribe((state) => {
+      mirror.textContent = `JS sees ${state.count} (history: ${state.history.join(", ")})`
+        + (mirror.dataset.bytes ? `, last bytes from Rust: "${mirror.dataset.bytes}"` : "");
+    });
+  },
+};
@@ -0,0 +1,14 @@
+// A counter whose state lives in Rust and is mirrored to page JS. It shows a store synced
+// to JS, commands from page JS limited by topic, a JS function called from Rust, and bytes
+// both ways over a MessagePort. Run it from this directory with `dx serve --platform web`.
+
+// JsPort is web only, so the app is too.
+#[cfg(target_arch = "wasm32")]
+mod app;
+
+fn main() {
+    #[cfg(target_arch = "wasm32")]
+    dioxus::launch(app::App);
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("web_counter runs in the browser: dx serve --platform web");
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use dx_use_js_bridge::__fuzz;
+use libfuzzer_sys::fuzz_target;
+
+// Bytes from JS arrive base64 encoded.
+fuzz_target!(|data: &str| {
+    let _ = __fuzz::base64(data);
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use dx_use_js_bridge::{Security, __fuzz};
+use libfuzzer_sys::fuzz_target;
+
+// Envelopes as bridges with schema versions, message IDs, compression or security
+// unwrap them.
+fuzz_target!(|data: &[u8]| {
+    let Ok(wire) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return;
+    };
+    let _ = __fuzz::envelope(wire.clone());
+    let _ = __fuzz::envelope(serde_json::Value::String(wire.to_string()));
+    let _ = __fuzz::unseal(Security::Sign, wire.clone());
+    let _ = __fuzz::unseal(Security::Encrypt, wire);
+});
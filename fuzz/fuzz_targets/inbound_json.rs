@@ -0,0 +1,24 @@
+#![no_main]
+
+use dx_use_js_bridge::__fuzz;
+use dx_use_js_bridge::{Capabilities, Codec, FileDropEvent, JsonCodec, PatchOp};
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+// Raw text from JS, through the parser and the typed decoding a bridge does next.
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data).into_owned();
+    // Values that aren't JSON still reach the codec as strings
+    let _ = JsonCodec::decode::<Value>(Value::String(text.clone()));
+    let Ok(value) = __fuzz::parse_json(text) else {
+        return;
+    };
+    let _ = JsonCodec::decode::<Value>(value.clone());
+    let _ = JsonCodec::decode::<FileDropEvent>(value.clone());
+    let _ = JsonCodec::decode::<Capabilities>(value.clone());
+    let _ = JsonCodec::decode::<Vec<PatchOp>>(value.clone());
+    let _ = JsonCodec::decode::<(String, Vec<u8>, Option<f64>)>(value.clone());
+    if let Ok(data) = __fuzz::origin(value) {
+        let _ = JsonCodec::decode::<Value>(data);
+    }
+});
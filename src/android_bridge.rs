@@ -1,36 +1,168 @@
 use jni::sys;
 use jni::JavaVM;
-use jni::objects::{JClass, JObject, JString, JValue};
+use jni::objects::{GlobalRef, JClass, JObject, JString, JValue};
 use jni::JNIEnv;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
-use std::sync::{Mutex, Once};
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex, Once};
 
 // Global static to hold callback functions.
 static CALLBACKS: Lazy<Mutex<HashMap<String, Box<dyn Fn(String) + Send + Sync>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+// Bridges with an origin policy, whose messages must say which frame sent them.
+static CHECKED_ORIGINS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+// Marks messages wrapped here with their sender, so origin checks can tell them from
+// wrappers page scripts make up.
+static FRAME_NONCE: Lazy<String> = Lazy::new(crate::ids::random_id);
+
+pub(crate) fn frame_nonce() -> &'static str {
+    &FRAME_NONCE
+}
+
+/// Makes messages for the bridge carry their sender: frames are tagged with the origin
+/// Kotlin reports through `onMessageFromFrame`, and messages through `onMessageFromJava`,
+/// which can come from any frame, are tagged as unverified so the policy rejects them.
+pub(crate) fn check_origins(id: &str) {
+    let mut checked = CHECKED_ORIGINS.lock().unwrap_or_else(|e| e.into_inner());
+    checked.insert(id.to_string());
+}
+
+fn checks_origins(id: &str) -> bool {
+    let checked = CHECKED_ORIGINS.lock().unwrap_or_else(|e| e.into_inner());
+    checked.contains(id)
+}
+
+/// Wraps `data` like the origin listener does, with `source` as the sender.
+fn with_source(data: serde_json::Value, source: serde_json::Value) -> String {
+    serde_json::json!({
+        "__dx_source": source,
+        "__dx_nonce": frame_nonce(),
+        "data": data,
+    })
+    .to_string()
+}
+
+// Counter used to build unique callback IDs for `eval_js_with_result`.
+static NEXT_RESULT_ID: AtomicUsize = AtomicUsize::new(0);
+
 // Global static to hold the JavaVM pointer using atomic for better thread safety.
 static GLOBAL_JAVA_VM: AtomicPtr<sys::JavaVM> = AtomicPtr::new(ptr::null_mut());
 
+// The Kotlin bridge class, in JNI form. Apps in another package set it through
+// `android_bridge_exports!`.
+static BRIDGE_CLASS: Mutex<&'static str> = Mutex::new("io/github/memkit/RustBridge");
+
+fn bridge_class() -> &'static str {
+    *BRIDGE_CLASS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Used by `android_bridge_exports!` to make calls into Kotlin go to the app's class.
+pub fn set_bridge_class(class: &'static str) {
+    let mut current = BRIDGE_CLASS.lock().unwrap_or_else(|e| e.into_inner());
+    if *current != class {
+        *current = class;
+        *BRIDGE_CLASS_REF.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+// The bridge class as found from a Java thread. Threads attached from native code, like
+// the bridge thread, look classes up in the system class loader, which can't see the app's.
+static BRIDGE_CLASS_REF: Mutex<Option<GlobalRef>> = Mutex::new(None);
+
+/// Finds the bridge class with the current thread's class loader and keeps it for threads
+/// attached from Rust. Called from `JNI_OnLoad` and `registerInstance`, which run on Java
+/// threads.
+pub fn cache_bridge_class(env: &mut JNIEnv) {
+    let class = env
+        .find_class(bridge_class())
+        .and_then(|class| env.new_global_ref(class));
+    match class {
+        Ok(class) => *BRIDGE_CLASS_REF.lock().unwrap_or_else(|e| e.into_inner()) = Some(class),
+        Err(_) => {
+            let _ = env.exception_clear();
+        }
+    }
+}
+
+fn bridge_class_ref(env: &mut JNIEnv) -> Result<GlobalRef, String> {
+    if let Some(class) = BRIDGE_CLASS_REF.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+        return Ok(class);
+    }
+    let class_name = bridge_class();
+    let class = env
+        .find_class(class_name)
+        .and_then(|class| env.new_global_ref(class))
+        .map_err(|e| format!("Failed to find class {}: {:?}", class_name, e))?;
+    Ok(class)
+}
+
+// Code to run once the JavaVM is known, registered through `when_vm_loaded`.
+type LoadHook = Box<dyn for<'local> FnOnce(&mut JNIEnv<'local>) + Send>;
+static LOAD_HOOKS: Mutex<Vec<LoadHook>> = Mutex::new(Vec::new());
+
 /// This function is called when the native library is loaded.
 /// It stores the JavaVM pointer for later use.
+#[cfg(not(feature = "custom-jni-onload"))]
 #[no_mangle]
 pub unsafe extern "C" fn JNI_OnLoad(
     vm: *mut sys::JavaVM,
     _reserved: *mut std::ffi::c_void,
 ) -> sys::jint {
+    on_jni_load(vm)
+}
+
+/// Stores the JavaVM pointer for later use and runs the hooks from [`when_vm_loaded`].
+///
+/// With the `custom-jni-onload` feature the crate doesn't define `JNI_OnLoad`; call this
+/// from the app's own and return its result.
+///
+/// # Safety
+///
+/// `vm` must be the pointer `JNI_OnLoad` received.
+pub unsafe fn on_jni_load(vm: *mut sys::JavaVM) -> sys::jint {
     // Store the JavaVM pointer atomically
     GLOBAL_JAVA_VM.store(vm, Ordering::SeqCst);
     
     // Print debug info
     eprintln!("JNI_OnLoad called, stored JavaVM pointer: {:?}", vm);
     
+    let _ = with_env(|env| {
+        cache_bridge_class(env);
+        Ok(())
+    });
+    run_load_hooks();
     sys::JNI_VERSION_1_6
 }
 
+/// Runs `hook` with a JNI environment once the JavaVM is loaded, e.g. to cache classes
+/// or register natives. Runs it right away if the VM is already loaded.
+pub fn when_vm_loaded(hook: impl for<'local> FnOnce(&mut JNIEnv<'local>) + Send + 'static) {
+    LOAD_HOOKS.lock().unwrap_or_else(|e| e.into_inner()).push(Box::new(hook));
+    if !GLOBAL_JAVA_VM.load(Ordering::SeqCst).is_null() {
+        run_load_hooks();
+    }
+}
+
+fn run_load_hooks() {
+    let hooks = std::mem::take(&mut *LOAD_HOOKS.lock().unwrap_or_else(|e| e.into_inner()));
+    if hooks.is_empty() {
+        return;
+    }
+    let ran = with_env(|env| {
+        for hook in hooks {
+            hook(env);
+        }
+        Ok(())
+    });
+    if let Err(e) = ran {
+        eprintln!("Failed to run JavaVM load hooks: {}", e);
+    }
+}
+
 /// On Android, retrieve the JavaVM from our stored global variable.
 #[cfg(target_os = "android")]
 fn get_java_vm() -> Option<JavaVM> {
@@ -57,6 +189,193 @@ fn get_java_vm() -> Option<JavaVM> {
     }
 }
 
+/// Runs `f` with a JNI environment attached to the current thread. A Java exception left
+/// pending by `f` is described, cleared and turned into an error.
+fn with_env<R>(f: impl for<'local> FnOnce(&mut JNIEnv<'local>) -> Result<R, String>) -> Result<R, String> {
+    let vm = get_java_vm().ok_or("Failed to get JavaVM")?;
+    let mut env = vm
+        .attach_current_thread()
+        .map_err(|e| format!("Failed to attach to JVM: {:?}", e))?;
+
+    let result = f(&mut env);
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_describe();
+        let _ = env.exception_clear();
+        return Err(match result {
+            Err(e) => e,
+            Ok(_) => "Java call threw an exception".to_string(),
+        });
+    }
+    result
+}
+
+/// Returns `android.webkit.CookieManager.getInstance()`.
+fn cookie_manager<'local>(env: &mut JNIEnv<'local>) -> Result<JObject<'local>, String> {
+    env.call_static_method(
+        "android/webkit/CookieManager",
+        "getInstance",
+        "()Landroid/webkit/CookieManager;",
+        &[],
+    )
+    .and_then(|v| v.l())
+    .map_err(|e| format!("Failed to get CookieManager: {:?}", e))
+}
+
+/// Reads the `Cookie` header the WebView would send to `url`.
+pub fn get_cookies(url: &str) -> Result<Option<String>, String> {
+    with_env(|env| {
+        let manager = cookie_manager(env)?;
+        let url_obj = JObject::from(
+            env.new_string(url)
+                .map_err(|e| format!("Failed to create Java string: {:?}", e))?,
+        );
+        let cookies = env
+            .call_method(
+                &manager,
+                "getCookie",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[JValue::Object(&url_obj)],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to call getCookie: {:?}", e))?;
+        if cookies.is_null() {
+            return Ok(None);
+        }
+        let cookies = JString::from(cookies);
+        let cookies = env
+            .get_string(&cookies)
+            .map_err(|e| format!("Failed to read cookie string: {:?}", e))?;
+        Ok(Some(cookies.into()))
+    })
+}
+
+/// Stores a `Set-Cookie` style string for `url` and flushes it to disk.
+pub fn set_cookie(url: &str, cookie: &str) -> Result<(), String> {
+    with_env(|env| {
+        let manager = cookie_manager(env)?;
+        let url_obj = JObject::from(
+            env.new_string(url)
+                .map_err(|e| format!("Failed to create Java string: {:?}", e))?,
+        );
+        let cookie_obj = JObject::from(
+            env.new_string(cookie)
+                .map_err(|e| format!("Failed to create Java string: {:?}", e))?,
+        );
+        env.call_method(
+            &manager,
+            "setCookie",
+            "(Ljava/lang/String;Ljava/lang/String;)V",
+            &[JValue::Object(&url_obj), JValue::Object(&cookie_obj)],
+        )
+        .map_err(|e| format!("Failed to call setCookie: {:?}", e))?;
+        env.call_method(&manager, "flush", "()V", &[])
+            .map_err(|e| format!("Failed to call flush: {:?}", e))?;
+        Ok(())
+    })
+}
+
+/// Returns the language tag of `java.util.Locale.getDefault()` and the ID of
+/// `java.util.TimeZone.getDefault()`, which follow the system settings.
+pub fn system_locale() -> Result<(String, String), String> {
+    with_env(|env| {
+        let locale = env
+            .call_static_method("java/util/Locale", "getDefault", "()Ljava/util/Locale;", &[])
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to get the default Locale: {:?}", e))?;
+        let tag = env
+            .call_method(&locale, "toLanguageTag", "()Ljava/lang/String;", &[])
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to call toLanguageTag: {:?}", e))?;
+        let zone = env
+            .call_static_method("java/util/TimeZone", "getDefault", "()Ljava/util/TimeZone;", &[])
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to get the default TimeZone: {:?}", e))?;
+        let zone_id = env
+            .call_method(&zone, "getID", "()Ljava/lang/String;", &[])
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to call getID: {:?}", e))?;
+        let tag: String = env
+            .get_string(&JString::from(tag))
+            .map_err(|e| format!("Failed to read language tag: {:?}", e))?
+            .into();
+        let zone_id: String = env
+            .get_string(&JString::from(zone_id))
+            .map_err(|e| format!("Failed to read time zone ID: {:?}", e))?
+            .into();
+        Ok((tag, zone_id))
+    })
+}
+
+/// Whether the system is in night mode, from the `uiMode` of
+/// `Resources.getSystem().getConfiguration()`.
+pub fn system_night_mode() -> Result<bool, String> {
+    // Configuration.UI_MODE_NIGHT_MASK and UI_MODE_NIGHT_YES
+    const NIGHT_MASK: i32 = 0x30;
+    const NIGHT_YES: i32 = 0x20;
+    with_env(|env| {
+        let resources = env
+            .call_static_method(
+                "android/content/res/Resources",
+                "getSystem",
+                "()Landroid/content/res/Resources;",
+                &[],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to get system Resources: {:?}", e))?;
+        let configuration = env
+            .call_method(
+                &resources,
+                "getConfiguration",
+                "()Landroid/content/res/Configuration;",
+                &[],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to get Configuration: {:?}", e))?;
+        let ui_mode = env
+            .get_field(&configuration, "uiMode", "I")
+            .and_then(|v| v.i())
+            .map_err(|e| format!("Failed to read uiMode: {:?}", e))?;
+        Ok(ui_mode & NIGHT_MASK == NIGHT_YES)
+    })
+}
+
+/// The width and height in pixels and the density of the default display, from
+/// `Resources.getSystem().getDisplayMetrics()`.
+pub fn display_metrics() -> Result<(i32, i32, f32), String> {
+    with_env(|env| {
+        let resources = env
+            .call_static_method(
+                "android/content/res/Resources",
+                "getSystem",
+                "()Landroid/content/res/Resources;",
+                &[],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to get system Resources: {:?}", e))?;
+        let metrics = env
+            .call_method(
+                &resources,
+                "getDisplayMetrics",
+                "()Landroid/util/DisplayMetrics;",
+                &[],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to get DisplayMetrics: {:?}", e))?;
+        let mut int_field = |name: &str| {
+            env.get_field(&metrics, name, "I")
+                .and_then(|v| v.i())
+                .map_err(|e| format!("Failed to read {}: {:?}", name, e))
+        };
+        let width = int_field("widthPixels")?;
+        let height = int_field("heightPixels")?;
+        let density = env
+            .get_field(&metrics, "density", "F")
+            .and_then(|v| v.f())
+            .map_err(|e| format!("Failed to read density: {:?}", e))?;
+        Ok((width, height, density))
+    })
+}
+
 /// Registers a callback function under the provided identifier.
 pub fn register_callback<F>(id: String, callback: F)
 where
@@ -70,29 +389,134 @@ where
 pub fn unregister_callback(id: &str) {
     let mut callbacks = CALLBACKS.lock().unwrap();
     callbacks.remove(id);
+    let mut checked = CHECKED_ORIGINS.lock().unwrap_or_else(|e| e.into_inner());
+    checked.remove(id);
+}
+
+/// Work for the bridge thread, which owns the JNI attachment for sends and evals, so
+/// they reach Kotlin one at a time and in the order they were made.
+enum Command {
+    /// Calls `onMessageFromRust`.
+    Send(String, Reply),
+    /// Calls `evalJs`. Without a reply, failures are logged.
+    Eval(String, Option<Reply>),
+    /// Answers once every earlier command has run.
+    Flush(futures_channel::oneshot::Sender<()>),
+    /// Stops the thread. The next command starts a new one.
+    Shutdown,
+}
+
+type Reply = futures_channel::oneshot::Sender<Result<(), String>>;
+
+static MAILBOX: Mutex<Option<mpsc::Sender<Command>>> = Mutex::new(None);
+
+/// Queues `command` for the bridge thread, starting it if needed.
+fn post(command: Command) -> Result<(), String> {
+    let mut mailbox = MAILBOX.lock().unwrap_or_else(|e| e.into_inner());
+    let sender = match &*mailbox {
+        Some(sender) => sender.clone(),
+        None => {
+            let (sender, commands) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("dx-js-bridge".to_string())
+                .spawn(move || bridge_thread(commands))
+                .map_err(|e| format!("Failed to start bridge thread: {}", e))?;
+            mailbox.insert(sender).clone()
+        }
+    };
+    if let Command::Shutdown = command {
+        *mailbox = None;
+    }
+    sender
+        .send(command)
+        .map_err(|_| "Bridge thread stopped".to_string())
+}
+
+fn bridge_thread(commands: mpsc::Receiver<Command>) {
+    let mut vm = None;
+    for command in commands {
+        match command {
+            Command::Send(message, reply) => {
+                let result = with_thread_env(&mut vm, |env| call_on_message_from_rust(env, &message));
+                let _ = reply.send(result);
+            }
+            Command::Eval(js_code, reply) => {
+                let result = with_thread_env(&mut vm, |env| call_eval_js(env, &js_code));
+                match reply {
+                    Some(reply) => {
+                        let _ = reply.send(result);
+                    }
+                    None => {
+                        if let Err(e) = result {
+                            eprintln!("Failed to evaluate JS: {}", e);
+                        }
+                    }
+                }
+            }
+            Command::Flush(done) => {
+                let _ = done.send(());
+            }
+            Command::Shutdown => break,
+        }
+    }
+    if let Some(vm) = vm {
+        // SAFETY: the thread holds no JNI references past this point
+        unsafe { vm.detach_current_thread() };
+    }
+}
+
+/// Runs `f` on the bridge thread's JNI environment, attaching the thread on first use.
+fn with_thread_env(
+    vm: &mut Option<JavaVM>,
+    f: impl for<'local> FnOnce(&mut JNIEnv<'local>) -> Result<(), String>,
+) -> Result<(), String> {
+    if vm.is_none() {
+        *vm = Some(get_java_vm().ok_or("Failed to get JavaVM")?);
+    }
+    let vm = vm.as_ref().ok_or("Failed to get JavaVM")?;
+    let mut env = vm
+        .attach_current_thread_permanently()
+        .map_err(|e| format!("Failed to attach to JVM: {:?}", e))?;
+    f(&mut env)
 }
 
 /// Evaluates JavaScript on Android by calling the static method `evalJs` on
-/// the Kotlin class "io.github.memkit.RustBridge".
+/// the Kotlin class "io.github.memkit.RustBridge", from the bridge thread.
 pub async fn eval_js(js_code: &str) -> Result<(), String> {
+    let (reply, result) = futures_channel::oneshot::channel();
+    post(Command::Eval(js_code.to_string(), Some(reply)))?;
+    result.await.map_err(|_| "Bridge thread stopped".to_string())?
+}
+
+/// Queues JavaScript for the bridge thread without waiting for it. Failures are logged.
+pub fn eval_js_detached(js_code: &str) {
+    if let Err(e) = post(Command::Eval(js_code.to_string(), None)) {
+        eprintln!("Failed to evaluate JS: {}", e);
+    }
+}
+
+/// Waits until every send and eval queued so far has reached Kotlin.
+pub async fn flush() {
+    let (done, flushed) = futures_channel::oneshot::channel();
+    if post(Command::Flush(done)).is_ok() {
+        let _ = flushed.await;
+    }
+}
+
+/// Stops the bridge thread once the commands queued so far have run.
+pub fn shutdown_thread() {
+    let running = MAILBOX.lock().unwrap_or_else(|e| e.into_inner()).is_some();
+    if running {
+        let _ = post(Command::Shutdown);
+    }
+}
+
+fn call_eval_js(env: &mut JNIEnv, js_code: &str) -> Result<(), String> {
     eprintln!("Attempting to evaluate JS: {}", js_code);
     
-    // Retrieve the JavaVM.
-    let vm = get_java_vm().ok_or("Failed to get JavaVM")?;
-    eprintln!("Successfully got JavaVM for eval_js");
-    
-    // Attach the current thread to the JVM.
-    let mut env = vm
-        .attach_current_thread()
-        .map_err(|e| format!("Failed to attach to JVM: {:?}", e))?;
-    eprintln!("Successfully attached to JVM");
-    
-    // Find the class "io/github/memkit/RustBridge".
-    let class_name = "io/github/memkit/RustBridge";
-    let class = env
-        .find_class(class_name)
-        .map_err(|e| format!("Failed to find class {}: {:?}", class_name, e))?;
-    eprintln!("Successfully found class: {}", class_name);
+    // Find the bridge class, "io/github/memkit/RustBridge" by default.
+    let class = bridge_class_ref(env)?;
+    eprintln!("Successfully found class: {}", bridge_class());
     
     // Create a Java string from js_code.
     let js_string = env
@@ -107,7 +531,7 @@ pub async fn eval_js(js_code: &str) -> Result<(), String> {
     let args = [JValue::Object(&js_obj)];
     
     // Call the static method "evalJs".
-    env.call_static_method(class, "evalJs", "(Ljava/lang/String;)V", &args)
+    env.call_static_method(<&JClass>::from(class.as_obj()), "evalJs", "(Ljava/lang/String;)V", &args)
         .map_err(|e| format!("Failed to call evalJs: {:?}", e))?;
     eprintln!("Successfully called evalJs method");
     
@@ -127,35 +551,188 @@ pub async fn eval_js(js_code: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// A one-shot callback registered under a fresh ID, resolved by the first message
+/// posted to that ID through `onMessageFromJava`.
+struct OneShot {
+    id: String,
+    rx: futures_channel::oneshot::Receiver<String>,
+}
+
+impl OneShot {
+    fn new() -> Self {
+        let id = format!(
+            "__dioxus_result_{}",
+            NEXT_RESULT_ID.fetch_add(1, Ordering::SeqCst)
+        );
+        let (tx, rx) = futures_channel::oneshot::channel::<String>();
+        let tx = Mutex::new(Some(tx));
+        register_callback(id.clone(), move |json: String| {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(json);
+            }
+        });
+        Self { id, rx }
+    }
+
+    /// Waits for the `{"ok": value}` or `{"err": message}` reply.
+    async fn wait(self) -> Result<serde_json::Value, String> {
+        let json = self.rx.await.map_err(|_| "Result channel closed".to_string());
+        unregister_callback(&self.id);
+        let mut result: serde_json::Value =
+            serde_json::from_str(&json?).map_err(|e| format!("Invalid result: {}", e))?;
+        match result.get("err").and_then(|e| e.as_str()) {
+            Some(err) => Err(err.to_string()),
+            None => Ok(result
+                .get_mut("ok")
+                .map(serde_json::Value::take)
+                .unwrap_or_default()),
+        }
+    }
+
+    fn cancel(self) {
+        unregister_callback(&self.id);
+    }
+}
+
+/// Evaluates JavaScript on Android and waits for the value it returns.
+/// `evalJs` has no return channel, so the script is wrapped to post its result back
+/// through `window.RustBridge.postMessage` under a one-shot callback ID.
+pub async fn eval_js_with_result(js_code: &str) -> Result<serde_json::Value, String> {
+    let one_shot = OneShot::new();
+    let wrapped = format!(
+        r#"(async function() {{
+            let result;
+            try {{
+                const value = await (async function() {{
+                    {js_code}
+                }})();
+                result = {{ ok: value === undefined ? null : value }};
+            }} catch (e) {{
+                result = {{ err: String(e) }};
+            }}
+            window.RustBridge.postMessage("{id}", JSON.stringify(result));
+        }})();"#,
+        id = one_shot.id
+    );
+    if let Err(e) = eval_js(&wrapped).await {
+        one_shot.cancel();
+        return Err(e);
+    }
+    one_shot
+        .wait()
+        .await
+        .map_err(|e| format!("JS eval error: {}", e))
+}
+
+/// Calls the static method `method` on the Kotlin class "io.github.memkit.RustBridge"
+/// with the signature `(String) -> String?`, passing `args` as JSON.
+///
+/// This is how platform features without a WebView API reach Android services; the app's
+/// `RustBridge` class implements the methods it supports. Calls to methods it doesn't
+/// define fail with [`not_supported`]'s error.
+pub fn call_native(method: &str, args: &serde_json::Value) -> Result<Option<String>, String> {
+    const SIGNATURE: &str = "(Ljava/lang/String;)Ljava/lang/String;";
+    with_env(|env| {
+        let class = bridge_class_ref(env)?;
+        let found = env.get_static_method_id(<&JClass>::from(class.as_obj()), method, SIGNATURE);
+        if let Err(e) = found {
+            return Err(match take_missing_method(env) {
+                true => not_supported(method),
+                false => format!("Failed to call {}: {:?}", method, e),
+            });
+        }
+        let args_obj = JObject::from(
+            env.new_string(args.to_string())
+                .map_err(|e| format!("Failed to create Java string: {:?}", e))?,
+        );
+        let result = env
+            .call_static_method(
+                <&JClass>::from(class.as_obj()),
+                method,
+                SIGNATURE,
+                &[JValue::Object(&args_obj)],
+            )
+            .and_then(|v| v.l())
+            .map_err(|e| format!("Failed to call {}: {:?}", method, e))?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        let result = JString::from(result);
+        let result = env
+            .get_string(&result)
+            .map_err(|e| format!("Failed to read result of {}: {:?}", method, e))?;
+        Ok(Some(result.into()))
+    })
+}
+
+/// The error for a feature whose Kotlin method the app's `RustBridge` class doesn't
+/// implement.
+pub(crate) fn not_supported(method: &str) -> String {
+    format!("Not supported by this app: its RustBridge class doesn't implement {}", method)
+}
+
+/// Clears a pending `NoSuchMethodError`, returning whether there was one. Other
+/// exceptions are left for [`with_env`] to report.
+fn take_missing_method(env: &mut JNIEnv) -> bool {
+    let exception = match env.exception_occurred() {
+        Ok(exception) if !exception.is_null() => exception,
+        _ => return false,
+    };
+    let _ = env.exception_clear();
+    let missing = env
+        .is_instance_of(&exception, "java/lang/NoSuchMethodError")
+        .unwrap_or(false);
+    if !missing {
+        let _ = env.throw(exception);
+    }
+    missing
+}
+
+/// Like [`call_native`] for methods that answer later. A `callback_id` field is added to
+/// `args`; Kotlin replies by calling `onMessageFromJava(callbackId, json)` with
+/// `{"ok": value}` or `{"err": message}`.
+pub async fn call_native_async(
+    method: &str,
+    mut args: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let one_shot = OneShot::new();
+    if let Some(args) = args.as_object_mut() {
+        args.insert(
+            "callback_id".to_string(),
+            serde_json::Value::String(one_shot.id.clone()),
+        );
+    }
+    if let Err(e) = call_native(method, &args) {
+        one_shot.cancel();
+        return Err(e);
+    }
+    one_shot.wait().await
+}
+
 /// Sends data to Kotlin by calling the static method `onMessageFromRust` on
-/// the Kotlin class "io.github.memkit.RustBridge".
+/// the Kotlin class "io.github.memkit.RustBridge", from the bridge thread.
 pub async fn send_to_java(message: String) -> Result<(), String> {
+    let (reply, result) = futures_channel::oneshot::channel();
+    post(Command::Send(message, reply))?;
+    result.await.map_err(|_| "Bridge thread stopped".to_string())?
+}
+
+fn call_on_message_from_rust(env: &mut JNIEnv, message: &str) -> Result<(), String> {
     eprintln!("Attempting to send message to Kotlin: {}", message);
     
-    let vm = get_java_vm().ok_or("Failed to get JavaVM")?;
-    eprintln!("Successfully got JavaVM for send_to_java");
-    
-    let mut env = vm
-        .attach_current_thread()
-        .map_err(|e| format!("Failed to attach to JVM: {:?}", e))?;
-    eprintln!("Successfully attached to JVM");
-    
-    let class_name = "io/github/memkit/RustBridge";
-    let class = env
-        .find_class(class_name)
-        .map_err(|e| format!("Failed to find class {}: {:?}", class_name, e))?;
-    eprintln!("Successfully found class: {}", class_name);
+    let class = bridge_class_ref(env)?;
+    eprintln!("Successfully found class: {}", bridge_class());
     
     let msg_string = env
-        .new_string(&message)
+        .new_string(message)
         .map_err(|e| format!("Failed to create Java string: {:?}", e))?;
     eprintln!("Successfully created Java string");
-    
+
     let msg_obj: JObject = JObject::from(msg_string);
     let args = [JValue::Object(&msg_obj)];
     
     env.call_static_method(
-        class,
+        <&JClass>::from(class.as_obj()),
         "onMessageFromRust",
         "(Ljava/lang/String;)V",
         &args,
@@ -188,11 +765,16 @@ pub extern "system" fn Java_io_github_memkit_RustBridge_onMessageFromJava(
     callback_id: JString,
     json_data: JString,
 ) {
+    on_message_from_java(&mut env, &callback_id, &json_data);
+}
+
+/// The body of `onMessageFromJava`, shared with the exports from `android_bridge_exports!`.
+pub fn on_message_from_java(env: &mut JNIEnv, callback_id: &JString, json_data: &JString) {
     eprintln!("Received message from Kotlin - callback_id length: {}, json_data length: {}", 
-              env.get_string(&callback_id).map(|s| s.to_string_lossy().len()).unwrap_or(0),
-              env.get_string(&json_data).map(|s| s.to_string_lossy().len()).unwrap_or(0));
+              env.get_string(callback_id).map(|s| s.to_string_lossy().len()).unwrap_or(0),
+              env.get_string(json_data).map(|s| s.to_string_lossy().len()).unwrap_or(0));
     
-    let callback_id_rust = match env.get_string(&callback_id) {
+    let callback_id_rust = match env.get_string(callback_id) {
         Ok(s) => s,
         Err(_) => {
             eprintln!("Failed to get callback_id string");
@@ -207,7 +789,7 @@ pub extern "system" fn Java_io_github_memkit_RustBridge_onMessageFromJava(
         }
     };
     
-    let json_data_rust = match env.get_string(&json_data) {
+    let json_data_rust = match env.get_string(json_data) {
         Ok(s) => s,
         Err(_) => {
             eprintln!("Failed to get json_data string");
@@ -223,24 +805,100 @@ pub extern "system" fn Java_io_github_memkit_RustBridge_onMessageFromJava(
     };
     
     eprintln!("Processing message - callback_id: {}, json_data length: {}", callback_id_str, json_data_str.len());
-    
+
+    let json_data_str = if checks_origins(&callback_id_str) {
+        match serde_json::from_str(&json_data_str) {
+            Ok(data) => with_source(data, serde_json::json!({ "unverified": true })),
+            Err(e) => {
+                eprintln!("Dropped invalid message for {}: {}", callback_id_str, e);
+                return;
+            }
+        }
+    } else {
+        json_data_str
+    };
+    dispatch(&callback_id_str, json_data_str);
+}
+
+/// Hands a message from the page to the callback registered for `callback_id`.
+fn dispatch(callback_id: &str, json: String) {
     let callbacks = CALLBACKS.lock().unwrap();
-    if let Some(callback) = callbacks.get(&callback_id_str) {
-        callback(json_data_str);
-        eprintln!("Successfully called callback for: {}", callback_id_str);
+    if let Some(callback) = callbacks.get(callback_id) {
+        callback(json);
+        eprintln!("Successfully called callback for: {}", callback_id);
     } else {
-        eprintln!("No callback found for: {}", callback_id_str);
+        eprintln!("No callback found for: {}", callback_id);
+    }
+}
+
+/// Called from Kotlin's WebMessageListener with `{"callback_id": id, "data": value}` as
+/// posted to `window.dxNative`, and the sender WebView reports.
+#[no_mangle]
+pub extern "system" fn Java_io_github_memkit_RustBridge_onMessageFromFrame(
+    mut env: JNIEnv,
+    _class: JClass,
+    message: JString,
+    source_origin: JString,
+    is_main_frame: sys::jboolean,
+) {
+    on_message_from_frame(&mut env, &message, &source_origin, is_main_frame != 0);
+}
+
+/// The body of `onMessageFromFrame`, shared with the exports from `android_bridge_exports!`.
+/// Messages from the main frame are the bridge's own document's; messages from other
+/// frames only reach bridges with an origin policy, tagged with their origin.
+pub fn on_message_from_frame(
+    env: &mut JNIEnv,
+    message: &JString,
+    source_origin: &JString,
+    main_frame: bool,
+) {
+    let read = |env: &mut JNIEnv, text: &JString| -> Option<String> {
+        env.get_string(text).ok().map(|text| text.to_string_lossy().into_owned())
+    };
+    let (Some(message), Some(origin)) = (read(env, message), read(env, source_origin)) else {
+        eprintln!("Failed to read message from frame");
+        return;
+    };
+    #[derive(serde::Deserialize)]
+    struct FrameMessage {
+        callback_id: String,
+        #[serde(default)]
+        data: serde_json::Value,
     }
+    let message: FrameMessage = match serde_json::from_str(&message) {
+        Ok(message) => message,
+        Err(e) => {
+            eprintln!("Dropped invalid message from {}: {}", origin, e);
+            return;
+        }
+    };
+    let json = if main_frame {
+        message.data.to_string()
+    } else if checks_origins(&message.callback_id) {
+        with_source(
+            message.data,
+            serde_json::json!({ "origin": origin, "top_frame": false }),
+        )
+    } else {
+        eprintln!(
+            "Dropped message from frame {}: bridge {} has no origin policy",
+            origin, message.callback_id
+        );
+        return;
+    };
+    dispatch(&message.callback_id, json);
 }
 
 /// JNI function to register the main activity instance
 #[no_mangle]
 pub extern "system" fn Java_io_github_memkit_RustBridge_registerInstance(
-    _env: JNIEnv,
+    mut env: JNIEnv,
     _class: JClass,
     _activity: JObject,
 ) {
     eprintln!("registerInstance called - activity registered");
-    // This function is called when MainActivity registers itself
-    // We don't need to do anything here as the JavaVM is already stored in JNI_OnLoad
+    // This function is called when MainActivity registers itself. The JavaVM is already
+    // stored in JNI_OnLoad; cache the bridge class while on a Java thread.
+    cache_bridge_class(&mut env);
 }
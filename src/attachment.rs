@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The URL path attachments are served under by [`use_attachments`] on desktop.
+pub const ATTACHMENT_PROTOCOL: &str = "dx-attachments";
+
+/// What JS receives for an [`Attachment`]: a URL it can load into `<video>`, `<img>` or
+/// `fetch`, without the bytes crossing the bridge.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub url: String,
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+}
+
+/// A file handed between Rust and JS by reference, for payloads too large to send even in
+/// chunks, such as videos. Desktop and Android only.
+///
+/// Rust-made attachments are written to a temporary file that is deleted when the last
+/// clone is dropped; attachments of existing files leave them in place. Either way the
+/// page loads them from [`Attachment::url`]: served by [`use_attachments`] on desktop, and
+/// on Android by the app, whose `RustBridge.serveAttachment({ id, path, mime_type })`
+/// returns the URL until `revokeAttachment({ id })`. Attachments serialize as
+/// [`AttachmentInfo`], so they can be sent in messages.
+#[derive(Clone, Debug)]
+pub struct Attachment {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    id: String,
+    path: PathBuf,
+    info: AttachmentInfo,
+    temporary: bool,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        registry().remove(&self.id);
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "id": self.id });
+            let _ = crate::android_bridge::call_native("revokeAttachment", &args);
+        }
+        if self.temporary {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl PartialEq for Attachment {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Serialize for Attachment {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.info.serialize(serializer)
+    }
+}
+
+/// Served attachments by ID, with their path and MIME type.
+static REGISTRY: Mutex<BTreeMap<String, (PathBuf, String)>> = Mutex::new(BTreeMap::new());
+
+fn registry() -> std::sync::MutexGuard<'static, BTreeMap<String, (PathBuf, String)>> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Attachment {
+    /// Writes `bytes` to a temporary file.
+    pub fn from_bytes(bytes: &[u8], name: &str, mime_type: &str) -> Result<Self, String> {
+        let dir = std::env::temp_dir().join("dx_attachments");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let id = crate::ids::random_id();
+        let path = dir.join(format!("{}-{}", id, sanitize(name)));
+        std::fs::write(&path, bytes)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Self::serve(id, path, name, mime_type, true)
+    }
+
+    /// Refers to an existing file, e.g. one the app recorded. The MIME type is guessed from
+    /// the extension when `None`.
+    pub fn from_path(path: impl AsRef<Path>, mime_type: Option<&str>) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mime_type = mime_type.map_or_else(|| guess_mime(&path), str::to_string);
+        Self::serve(crate::ids::random_id(), path, &name, &mime_type, false)
+    }
+
+    /// The files chosen in an `<input type="file">`, which desktop reports by path.
+    pub fn from_form(event: &dioxus::prelude::FormEvent) -> Result<Vec<Self>, String> {
+        if cfg!(any(target_arch = "wasm32", target_os = "android")) {
+            return Err("File inputs don't report paths here; use Attachment::pick".to_string());
+        }
+        let Some(files) = event.files() else {
+            return Ok(Vec::new());
+        };
+        files
+            .files()
+            .iter()
+            .map(|path| Self::from_path(path, None))
+            .collect()
+    }
+
+    /// Lets the user choose a file with the system picker, resolving to `None` when they
+    /// cancel. On Android, `RustBridge.pickAttachment({ accept })` answers with
+    /// `{ path, mime_type }` of a copy of the chosen file, or `null`. Elsewhere, use a file
+    /// input with [`Attachment::from_form`].
+    pub async fn pick(accept: &str) -> Result<Option<Self>, String> {
+        #[cfg(target_os = "android")]
+        {
+            #[derive(Deserialize)]
+            struct Picked {
+                path: String,
+                mime_type: Option<String>,
+            }
+
+            let args = serde_json::json!({ "accept": accept });
+            let picked = crate::android_bridge::call_native_async("pickAttachment", args).await?;
+            match serde_json::from_value::<Option<Picked>>(picked)
+                .map_err(|e| format!("Deserialization error: {}", e))?
+            {
+                Some(picked) => Self::from_path(picked.path, picked.mime_type.as_deref()).map(Some),
+                None => Ok(None),
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = accept;
+            Err("Attachment::pick is only available on Android".to_string())
+        }
+    }
+
+    fn serve(
+        id: String,
+        path: PathBuf,
+        name: &str,
+        mime_type: &str,
+        temporary: bool,
+    ) -> Result<Self, String> {
+        if cfg!(target_arch = "wasm32") {
+            return Err(crate::BridgeError::Unsupported.into());
+        }
+        let size = std::fs::metadata(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .len();
+        // Dropping it on failure cleans up like a dropped attachment
+        let mut inner = Inner {
+            id,
+            path,
+            info: AttachmentInfo {
+                url: String::new(),
+                name: name.to_string(),
+                mime_type: mime_type.to_string(),
+                size,
+            },
+            temporary,
+        };
+        registry().insert(
+            inner.id.clone(),
+            (inner.path.clone(), mime_type.to_string()),
+        );
+        inner.info.url = url_for(&inner.id, &inner.path, mime_type)?;
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// The URL the page loads the file from.
+    pub fn url(&self) -> &str {
+        &self.inner.info.url
+    }
+
+    pub fn info(&self) -> &AttachmentInfo {
+        &self.inner.info
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Reads the whole file, e.g. for a picked file small enough to process in memory.
+    pub fn read(&self) -> Result<Vec<u8>, String> {
+        std::fs::read(&self.inner.path)
+            .map_err(|e| format!("Failed to read {}: {}", self.inner.path.display(), e))
+    }
+
+    /// Copies the file to `to` and refers to the copy, e.g. to keep a temporary attachment
+    /// in the app's storage after it is dropped.
+    pub fn persist(self, to: impl AsRef<Path>) -> Result<Self, String> {
+        let to = to.as_ref();
+        std::fs::copy(&self.inner.path, to)
+            .map_err(|e| format!("Failed to copy to {}: {}", to.display(), e))?;
+        Self::from_path(to, Some(&self.inner.info.mime_type))
+    }
+}
+
+#[cfg(target_os = "android")]
+fn url_for(id: &str, path: &Path, mime_type: &str) -> Result<String, String> {
+    let args = serde_json::json!({
+        "id": id,
+        "path": path.to_string_lossy(),
+        "mime_type": mime_type,
+    });
+    crate::android_bridge::call_native("serveAttachment", &args)?
+        .ok_or_else(|| "RustBridge.serveAttachment returned no URL".to_string())
+}
+
+#[cfg(not(target_os = "android"))]
+fn url_for(id: &str, _path: &Path, _mime_type: &str) -> Result<String, String> {
+    Ok(format!("/{}/{}", ATTACHMENT_PROTOCOL, id))
+}
+
+/// Keeps characters of a file name that are safe in a path and a URL.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn guess_mime(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("mp4" | "m4v") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("mov") => "video/quicktime",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("ogg") => "audio/ogg",
+        Some("m4a") => "audio/mp4",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// Serves attachments to the page on desktop, with range requests so videos can seek.
+/// Call it in a component that stays mounted while attachments are in use; it does
+/// nothing on other platforms.
+pub fn use_attachments() {
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    dioxus_desktop::use_asset_handler(ATTACHMENT_PROTOCOL, |request, responder| {
+        // Files are read off the UI thread
+        std::thread::spawn(move || responder.respond(desktop::respond(&request)));
+    });
+}
+
+#[cfg(all(
+    feature = "desktop",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+mod desktop {
+    use super::registry;
+    use dioxus_desktop::wry::http::{header, Request, Response, StatusCode};
+    use std::io::{Read, Seek, SeekFrom};
+
+    /// Most bytes served for one range request, so seeking in a video doesn't read the rest
+    /// of the file into memory.
+    const MAX_RANGE: u64 = 4 * 1024 * 1024;
+
+    pub(super) fn respond(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+        let id = request.uri().path().rsplit('/').next().unwrap_or_default();
+        let entry = registry().get(id).cloned();
+        let Some((path, mime_type)) = entry else {
+            return status(StatusCode::NOT_FOUND);
+        };
+        let range = request
+            .headers()
+            .get(header::RANGE)
+            .and_then(|range| range.to_str().ok())
+            .map(str::to_string);
+        let (bytes, content_range) = match read(&path, range.as_deref()) {
+            Ok(read) => read,
+            Err(code) => return status(code),
+        };
+        let mut response = Response::builder()
+            .header(header::CONTENT_TYPE, mime_type)
+            .header(header::ACCEPT_RANGES, "bytes");
+        if let Some(content_range) = content_range {
+            response = response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, content_range);
+        }
+        response
+            .body(bytes)
+            .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    /// Reads the whole file, or the requested range with its `Content-Range`.
+    fn read(
+        path: &std::path::Path,
+        range: Option<&str>,
+    ) -> Result<(Vec<u8>, Option<String>), StatusCode> {
+        let mut file = std::fs::File::open(path).map_err(|_| StatusCode::NOT_FOUND)?;
+        let size = file
+            .metadata()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .len();
+        let Some(range) = range else {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            return Ok((bytes, None));
+        };
+        let (start, end) = parse_range(range, size).ok_or(StatusCode::RANGE_NOT_SATISFIABLE)?;
+        let end = end.min(start + MAX_RANGE - 1);
+        let mut bytes = vec![0; (end - start + 1) as usize];
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        Ok((bytes, Some(format!("bytes {}-{}/{}", start, end, size))))
+    }
+
+    /// The first range of a `bytes=` header, as inclusive offsets within the file.
+    fn parse_range(range: &str, size: u64) -> Option<(u64, u64)> {
+        let spec = range.strip_prefix("bytes=")?.split(',').next()?.trim();
+        let (start, end) = spec.split_once('-')?;
+        let (start, end) = match (start.parse::<u64>().ok(), end.parse::<u64>().ok()) {
+            (Some(start), Some(end)) => (start, end.min(size.checked_sub(1)?)),
+            (Some(start), None) => (start, size.checked_sub(1)?),
+            // The last `suffix` bytes
+            (None, Some(suffix)) => (size.saturating_sub(suffix), size.checked_sub(1)?),
+            (None, None) => return None,
+        };
+        (start <= end).then_some((start, end))
+    }
+
+    fn status(status: StatusCode) -> Response<Vec<u8>> {
+        let mut response = Response::new(Vec::new());
+        *response.status_mut() = status;
+        response
+    }
+}
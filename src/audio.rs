@@ -0,0 +1,401 @@
+use crate::binary::JS_BASE64_HELPERS;
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// A command for the page's `AudioContext`, queued with [`AudioEngine::send`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AudioCommand {
+    /// Fetches and decodes `url` into the buffer named `sound`.
+    Load {
+        sound: String,
+        url: String,
+    },
+    /// Decodes encoded audio bytes into the buffer named `sound`.
+    LoadBytes {
+        sound: String,
+        #[serde(with = "crate::binary::base64_bytes")]
+        data: Vec<u8>,
+    },
+    /// Starts a new voice playing `sound`.
+    Play {
+        sound: String,
+        voice: String,
+        looped: bool,
+        gain: f32,
+        /// Places the voice in 3D space relative to the listener.
+        position: Option<[f32; 3]>,
+    },
+    Stop {
+        voice: String,
+    },
+    /// Sets the gain of a voice, or the master gain when `voice` is `None`.
+    SetGain {
+        voice: Option<String>,
+        gain: f32,
+    },
+    /// Moves a voice that was played with a position.
+    SetPosition {
+        voice: String,
+        position: [f32; 3],
+    },
+    SetListenerPosition {
+        position: [f32; 3],
+    },
+    /// Resumes a context suspended by the browser's autoplay policy; send it from a user
+    /// gesture.
+    Resume,
+}
+
+/// An event from the `AudioContext`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AudioEvent {
+    /// `sound` was decoded and can be played.
+    Loaded {
+        sound: String,
+        duration: f64,
+    },
+    LoadError {
+        sound: String,
+        message: String,
+    },
+    /// A voice finished playing or was stopped.
+    Ended {
+        voice: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Options for [`AudioEngine::play`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayOptions {
+    pub looped: bool,
+    pub gain: f32,
+    pub position: Option<[f32; 3]>,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self {
+            looped: false,
+            gain: 1.0,
+            position: None,
+        }
+    }
+}
+
+/// Drives a Web Audio `AudioContext` living in the page, created by [`use_audio`].
+///
+/// Commands are queued and sent to JS in one batch per tick, so triggering many sounds
+/// from one handler costs a single eval.
+#[derive(Clone)]
+pub struct AudioEngine {
+    /// Names of the decoded sounds.
+    pub loaded: Signal<Vec<String>>,
+    /// IDs of the voices currently playing.
+    pub playing: Signal<Vec<String>>,
+    pub error: Signal<Option<String>>,
+    pub bridge: JsBridge<AudioEvent>,
+    queue: CopyValue<Vec<AudioCommand>>,
+    next_voice: CopyValue<u64>,
+}
+
+impl AudioEngine {
+    /// Returns a stream of every audio event received after this call.
+    pub fn events(&self) -> UnboundedReceiver<AudioEvent> {
+        self.bridge.stream()
+    }
+
+    pub fn is_loaded(&self, sound: &str) -> bool {
+        self.loaded.read().iter().any(|s| s == sound)
+    }
+    pub fn get_error(&self) -> Option<String> {
+        self.error.read().clone()
+    }
+
+    pub fn load(&self, sound: &str, url: &str) {
+        self.send(AudioCommand::Load {
+            sound: sound.to_string(),
+            url: url.to_string(),
+        });
+    }
+
+    pub fn load_bytes(&self, sound: &str, data: Vec<u8>) {
+        self.send(AudioCommand::LoadBytes {
+            sound: sound.to_string(),
+            data,
+        });
+    }
+
+    /// Plays a loaded sound and returns the ID of the new voice.
+    pub fn play(&self, sound: &str, options: &PlayOptions) -> String {
+        let mut next_voice = self.next_voice;
+        let voice = format!("{}#{}", sound, next_voice());
+        *next_voice.write() += 1;
+        let mut playing = self.playing;
+        playing.write().push(voice.clone());
+        self.send(AudioCommand::Play {
+            sound: sound.to_string(),
+            voice: voice.clone(),
+            looped: options.looped,
+            gain: options.gain,
+            position: options.position,
+        });
+        voice
+    }
+
+    pub fn stop(&self, voice: &str) {
+        self.send(AudioCommand::Stop {
+            voice: voice.to_string(),
+        });
+    }
+
+    pub fn set_gain(&self, voice: &str, gain: f32) {
+        self.send(AudioCommand::SetGain {
+            voice: Some(voice.to_string()),
+            gain,
+        });
+    }
+
+    pub fn set_master_gain(&self, gain: f32) {
+        self.send(AudioCommand::SetGain { voice: None, gain });
+    }
+
+    pub fn set_position(&self, voice: &str, position: [f32; 3]) {
+        self.send(AudioCommand::SetPosition {
+            voice: voice.to_string(),
+            position,
+        });
+    }
+
+    pub fn set_listener_position(&self, position: [f32; 3]) {
+        self.send(AudioCommand::SetListenerPosition { position });
+    }
+
+    pub fn resume(&self) {
+        self.send(AudioCommand::Resume);
+    }
+
+    /// Queues a command. The queue is flushed to JS once the current handler returns.
+    pub fn send(&self, command: AudioCommand) {
+        let mut queue = self.queue;
+        let flush_pending = !queue.read().is_empty();
+        queue.write().push(command);
+        if flush_pending {
+            return;
+        }
+
+        let id = self.bridge.callback_id();
+        let mut error = self.error;
+        spawn(async move {
+            let commands = std::mem::take(&mut *queue.write());
+            let commands = match serde_json::to_string(&commands) {
+                Ok(commands) => commands,
+                Err(e) => {
+                    error.set(Some(format!("Serialization error: {}", e)));
+                    return;
+                }
+            };
+            let js_code = format!(
+                r#"
+                const audio = window.__dioxus_audio && window.__dioxus_audio[{id}];
+                if (!audio) {{
+                    throw new Error("Audio engine is not installed");
+                }}
+                audio.run({commands});
+                "#,
+                id = js_string(&id),
+            );
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                error.set(Some(e));
+            }
+        });
+    }
+}
+
+fn close_script(id: &str) -> String {
+    format!(
+        r#"
+        const audio = window.__dioxus_audio && window.__dioxus_audio[{id}];
+        if (audio) {{
+            if (audio.ctx) audio.ctx.close();
+            delete window.__dioxus_audio[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Creates an [`AudioEngine`]. The `AudioContext` is created on the first command and
+/// closed when the component unmounts.
+pub fn use_audio() -> AudioEngine {
+    let mut loaded = use_signal(Vec::<String>::new);
+    let mut playing = use_signal(Vec::<String>::new);
+    let mut error = use_signal(|| None::<String>);
+    let queue = use_hook(|| CopyValue::new(Vec::new()));
+    let next_voice = use_hook(|| CopyValue::new(0));
+
+    let bridge = use_js_listener::<AudioEvent>(move |event| match event {
+        AudioEvent::Loaded { sound, .. } => loaded.with_mut(|loaded| {
+            if !loaded.contains(&sound) {
+                loaded.push(sound);
+            }
+        }),
+        AudioEvent::LoadError { sound, message } => {
+            error.set(Some(format!("Failed to load {}: {}", sound, message)))
+        }
+        AudioEvent::Ended { voice } => playing.with_mut(|playing| playing.retain(|v| *v != voice)),
+        AudioEvent::Error { message } => error.set(Some(message)),
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            {JS_BASE64_HELPERS}
+            window.__dioxus_audio = window.__dioxus_audio || {{}};
+            const id = {id};
+            if (!window.__dioxus_audio[id]) {{
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const audio = {{ ctx: null, master: null, buffers: {{}}, voices: {{}} }};
+                const context = () => {{
+                    if (!audio.ctx) {{
+                        audio.ctx = new (window.AudioContext || window.webkitAudioContext)();
+                        audio.master = audio.ctx.createGain();
+                        audio.master.connect(audio.ctx.destination);
+                    }}
+                    return audio.ctx;
+                }};
+                const voice = (id) => {{
+                    const v = audio.voices[id];
+                    if (!v) throw new Error("No such voice: " + id);
+                    return v;
+                }};
+                const setPosition = (param, [x, y, z]) => {{
+                    if (param.positionX) {{
+                        param.positionX.value = x;
+                        param.positionY.value = y;
+                        param.positionZ.value = z;
+                    }} else {{
+                        param.setPosition(x, y, z);
+                    }}
+                }};
+                const decode = async (sound, bytes) => {{
+                    try {{
+                        const buffer = await context().decodeAudioData(bytes);
+                        audio.buffers[sound] = buffer;
+                        emit({{ event: "loaded", sound, duration: buffer.duration }});
+                    }} catch (e) {{
+                        emit({{ event: "load_error", sound, message: String(e) }});
+                    }}
+                }};
+                const handlers = {{
+                    load: async (c) => {{
+                        try {{
+                            const response = await fetch(c.url);
+                            if (!response.ok) throw new Error("HTTP " + response.status);
+                            await decode(c.sound, await response.arrayBuffer());
+                        }} catch (e) {{
+                            emit({{ event: "load_error", sound: c.sound, message: String(e) }});
+                        }}
+                    }},
+                    load_bytes: (c) => decode(c.sound, __dxFromBase64(c.data).buffer),
+                    play: (c) => {{
+                        const ctx = context();
+                        const buffer = audio.buffers[c.sound];
+                        if (!buffer) {{
+                            emit({{ event: "ended", voice: c.voice }});
+                            throw new Error("Sound not loaded: " + c.sound);
+                        }}
+                        const source = ctx.createBufferSource();
+                        source.buffer = buffer;
+                        source.loop = c.looped;
+                        const gain = ctx.createGain();
+                        gain.gain.value = c.gain;
+                        source.connect(gain);
+                        let panner = null;
+                        if (c.position) {{
+                            panner = ctx.createPanner();
+                            panner.panningModel = "HRTF";
+                            setPosition(panner, c.position);
+                            gain.connect(panner);
+                            panner.connect(audio.master);
+                        }} else {{
+                            gain.connect(audio.master);
+                        }}
+                        source.onended = () => {{
+                            delete audio.voices[c.voice];
+                            emit({{ event: "ended", voice: c.voice }});
+                        }};
+                        audio.voices[c.voice] = {{ source, gain, panner }};
+                        source.start();
+                    }},
+                    stop: (c) => {{
+                        const v = audio.voices[c.voice];
+                        if (v) v.source.stop();
+                    }},
+                    set_gain: (c) => {{
+                        if (c.voice === null) {{
+                            context();
+                            audio.master.gain.value = c.gain;
+                        }} else {{
+                            voice(c.voice).gain.gain.value = c.gain;
+                        }}
+                    }},
+                    set_position: (c) => {{
+                        const v = voice(c.voice);
+                        if (!v.panner) throw new Error("Voice was played without a position: " + c.voice);
+                        setPosition(v.panner, c.position);
+                    }},
+                    set_listener_position: (c) => setPosition(context().listener, c.position),
+                    resume: () => context().resume(),
+                }};
+                audio.run = (commands) => {{
+                    for (const c of commands) {{
+                        try {{
+                            const result = handlers[c.command](c);
+                            if (result && result.catch) {{
+                                result.catch((e) => emit({{ event: "error", message: String(e) }}));
+                            }}
+                        }} catch (e) {{
+                            emit({{ event: "error", message: String(e) }});
+                        }}
+                    }}
+                }};
+                window.__dioxus_audio[id] = audio;
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+        );
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                error.set(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&close_script(&callback_id));
+    });
+
+    AudioEngine {
+        loaded,
+        playing,
+        error,
+        bridge,
+        queue,
+        next_voice,
+    }
+}
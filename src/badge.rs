@@ -0,0 +1,126 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, js_string};
+
+/// Sets the document title, which is also the title of the window on desktop and of the
+/// app's task in Android's recents screen, through `RustBridge.setTitle({ title })`.
+pub async fn set_title(title: &str) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::json!({ "title": title });
+        crate::android_bridge::call_native("setTitle", &args).map(|_| ())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        #[cfg(all(
+            feature = "desktop",
+            not(any(target_arch = "wasm32", target_os = "android"))
+        ))]
+        if let Ok(window) = crate::desktop_window::desktop_context() {
+            window.set_title(title);
+        }
+
+        eval_as::<serde_json::Value>(&format!(
+            "document.title = {}; return null;",
+            js_string(title)
+        ))
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Shows a count on the app's icon, e.g. unread notifications. Zero clears it.
+///
+/// Web uses `navigator.setAppBadge` for installed apps, and draws the count on the
+/// favicon where it isn't available. Desktop badges the dock icon on macOS and the
+/// launcher entry on Linux; Windows has no badge and ignores it. Android sets the
+/// launcher's shortcut badge with `RustBridge.setBadge({ count })`.
+pub async fn set_badge(count: u32) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::json!({ "count": count });
+        crate::android_bridge::call_native("setBadge", &args).map(|_| ())
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        let window = crate::desktop_window::desktop_context()?;
+        #[cfg(target_os = "macos")]
+        {
+            use dioxus_desktop::tao::platform::macos::WindowExtMacOS;
+            window.set_badge_label((count > 0).then(|| count.to_string()));
+        }
+        #[cfg(target_os = "linux")]
+        {
+            use dioxus_desktop::tao::platform::unix::WindowExtUnix;
+            window.set_badge_count((count > 0).then_some(count as i64), None);
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        let _ = window;
+        Ok(())
+    }
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        not(any(target_os = "android", feature = "desktop"))
+    ))]
+    {
+        eval_as::<serde_json::Value>(&format!(
+            r##"
+            const count = {count};
+            if (navigator.setAppBadge) {{
+                await (count > 0 ? navigator.setAppBadge(count) : navigator.clearAppBadge());
+                return null;
+            }}
+            let link = document.querySelector("link[rel~='icon']");
+            if (!link) {{
+                link = document.createElement("link");
+                link.rel = "icon";
+                document.head.appendChild(link);
+            }}
+            if (link.dataset.dxOriginal === undefined) {{
+                link.dataset.dxOriginal = link.getAttribute("href") || "";
+            }}
+            const original = link.dataset.dxOriginal;
+            if (count === 0) {{
+                if (original) {{
+                    link.href = original;
+                }} else {{
+                    link.removeAttribute("href");
+                }}
+                return null;
+            }}
+            const size = 64;
+            const canvas = document.createElement("canvas");
+            canvas.width = canvas.height = size;
+            const context = canvas.getContext("2d");
+            if (original) {{
+                const icon = new Image();
+                icon.src = original;
+                try {{
+                    await icon.decode();
+                    context.drawImage(icon, 0, 0, size, size);
+                }} catch (e) {{
+                    // Badge a blank icon if the favicon can't be drawn
+                }}
+            }}
+            context.fillStyle = "#e53935";
+            context.beginPath();
+            context.arc(size * 0.68, size * 0.32, size * 0.32, 0, 2 * Math.PI);
+            context.fill();
+            context.fillStyle = "#fff";
+            context.font = "bold " + size * 0.4 + "px sans-serif";
+            context.textAlign = "center";
+            context.textBaseline = "middle";
+            context.fillText(count > 99 ? "99+" : String(count), size * 0.68, size * 0.34);
+            link.href = canvas.toDataURL("image/png");
+            return null;
+            "##
+        ))
+        .await
+        .map(|_| ())
+    }
+}
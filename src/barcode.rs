@@ -0,0 +1,133 @@
+#[cfg(not(target_os = "android"))]
+use crate::eval_as;
+use crate::FacingMode;
+use serde::{Deserialize, Serialize};
+
+/// A code read by [`scan_barcode`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Barcode {
+    /// The decoded text.
+    pub value: String,
+    /// The `BarcodeDetector` format name, such as `"qr_code"` or `"ean_13"`.
+    pub format: String,
+}
+
+/// What [`scan_barcode_with_options`] looks for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScanOptions {
+    /// `BarcodeDetector` format names to accept; empty accepts every supported format.
+    pub formats: Vec<String>,
+    pub facing_mode: FacingMode,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            formats: Vec::new(),
+            facing_mode: FacingMode::Environment,
+        }
+    }
+}
+
+/// Scans a QR code or barcode with the back camera. `None` if the user cancels.
+pub async fn scan_barcode() -> Result<Option<Barcode>, String> {
+    scan_barcode_with_options(&ScanOptions::default()).await
+}
+
+/// Scans a QR code or barcode. `None` if the user cancels.
+///
+/// On web and desktop a full-screen camera preview is shown, through `getUserMedia`, until
+/// `BarcodeDetector` finds a code or the user closes it. On Android the app scans with ML
+/// Kit: `RustBridge.scanBarcode({ formats, facing_mode })` answers with a [`Barcode`] as
+/// `{ value, format }`, or `null` when the user cancels.
+pub async fn scan_barcode_with_options(options: &ScanOptions) -> Result<Option<Barcode>, String> {
+    #[cfg(target_os = "android")]
+    {
+        let args =
+            serde_json::to_value(options).map_err(|e| format!("Serialization error: {}", e))?;
+        let barcode = crate::android_bridge::call_native_async("scanBarcode", args).await?;
+        serde_json::from_value(barcode).map_err(|e| format!("Deserialization error: {}", e))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let options =
+            serde_json::to_string(options).map_err(|e| format!("Serialization error: {}", e))?;
+        eval_as(&format!(
+            r#"
+            const options = {options};
+            if (!("BarcodeDetector" in window)) {{
+                throw new Error("BarcodeDetector is not supported");
+            }}
+            if (!navigator.mediaDevices || !navigator.mediaDevices.getUserMedia) {{
+                throw new Error("Camera capture is not supported");
+            }}
+            const supported = await BarcodeDetector.getSupportedFormats();
+            const formats = options.formats.length
+                ? options.formats.filter((f) => supported.includes(f))
+                : supported;
+            if (!formats.length) {{
+                throw new Error("None of the requested barcode formats are supported");
+            }}
+            const detector = new BarcodeDetector({{ formats }});
+            const stream = await navigator.mediaDevices.getUserMedia({{
+                video: {{ facingMode: options.facing_mode }},
+                audio: false,
+            }});
+
+            const overlay = document.createElement("div");
+            overlay.style.cssText = "position:fixed;inset:0;z-index:2147483647;background:#000;" +
+                "display:flex;align-items:center;justify-content:center";
+            const video = document.createElement("video");
+            video.muted = true;
+            video.playsInline = true;
+            video.srcObject = stream;
+            video.style.cssText = "max-width:100%;max-height:100%";
+            const close = document.createElement("button");
+            close.textContent = "×";
+            close.setAttribute("aria-label", "Cancel scanning");
+            close.style.cssText = "position:absolute;top:16px;right:16px;font-size:32px;" +
+                "background:none;border:0;color:#fff;cursor:pointer";
+            overlay.append(video, close);
+            document.body.appendChild(overlay);
+
+            let done = false;
+            try {{
+                await video.play();
+                return await new Promise((resolve, reject) => {{
+                    const finish = (result) => {{
+                        done = true;
+                        resolve(result);
+                    }};
+                    close.onclick = () => finish(null);
+                    overlay.onkeydown = (e) => {{
+                        if (e.key === "Escape") finish(null);
+                    }};
+                    close.focus();
+                    const detect = async () => {{
+                        if (done) return;
+                        try {{
+                            const codes = await detector.detect(video);
+                            if (codes.length) {{
+                                finish({{ value: codes[0].rawValue, format: codes[0].format }});
+                                return;
+                            }}
+                        }} catch (e) {{
+                            done = true;
+                            reject(e);
+                            return;
+                        }}
+                        requestAnimationFrame(detect);
+                    }};
+                    detect();
+                }});
+            }} finally {{
+                done = true;
+                stream.getTracks().forEach((track) => track.stop());
+                overlay.remove();
+            }}
+            "#
+        ))
+        .await
+    }
+}
@@ -0,0 +1,70 @@
+/// JS functions converting between `Uint8Array`s and base64, inlined into scripts that
+/// exchange bytes with Rust.
+pub(crate) const JS_BASE64_HELPERS: &str = r#"
+const __dxToBase64 = (bytes) => {
+    let binary = "";
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+        binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+    }
+    return btoa(binary);
+};
+const __dxFromBase64 = (text) => Uint8Array.from(atob(text), (c) => c.charCodeAt(0));
+"#;
+
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+pub(crate) fn decode(text: &str) -> Result<Vec<u8>, String> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| format!("Invalid base64 data: {}", e))
+}
+
+/// Serde adapter for `Vec<u8>` fields carried as base64 strings,
+/// used as `#[serde(with = "crate::binary::base64_bytes")]`.
+pub(crate) mod base64_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        super::decode(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Frame {
+        #[serde(with = "base64_bytes")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn bytes_survive_base64() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)), Ok(bytes));
+        assert_eq!(encode(b""), "");
+        assert!(decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn byte_fields_serialize_as_base64() {
+        let frame = Frame {
+            data: b"foo".to_vec(),
+        };
+        let json = serde_json::to_value(&frame).unwrap();
+        assert_eq!(json, serde_json::json!({ "data": "Zm9v" }));
+        assert_eq!(serde_json::from_value::<Frame>(json).unwrap(), frame);
+        assert!(serde_json::from_str::<Frame>(r#"{"data":"%%%"}"#).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use crate::eval_detached;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// Keys of the bootstrap scripts injected into the current document. Cleared when a new
+/// document generation starts.
+static INJECTED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+fn injected() -> std::sync::MutexGuard<'static, BTreeSet<String>> {
+    INJECTED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Evaluates `script` unless a script with the same `key` was already injected into this
+/// document, so bridges mounting together don't each send the shared page helpers.
+pub(crate) fn inject_once(key: &str, script: &str) {
+    let fresh = injected().insert(key.to_string());
+    if fresh {
+        eval_detached(script);
+    }
+}
+
+/// Lets `key` be injected again, e.g. after its callback was unregistered.
+#[cfg(target_os = "android")]
+pub(crate) fn forget(key: &str) {
+    injected().remove(key);
+}
+
+/// Starts a new document generation after JS lost its state, such as an Android renderer
+/// restart, so every bootstrap script is injected again.
+#[cfg(target_os = "android")]
+pub(crate) fn new_document() {
+    injected().clear();
+}
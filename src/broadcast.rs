@@ -0,0 +1,196 @@
+#[cfg(any(target_arch = "wasm32", target_os = "android"))]
+use crate::{eval_as, eval_detached, js_string};
+use crate::{use_js_bridge, FromJs, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Desktop windows each have their own webview, so messages are routed between them in
+/// process instead of through JS. Subscribers are keyed by channel name, then bridge ID.
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+mod desktop {
+    use futures_channel::mpsc::UnboundedSender;
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    type Subscribers = HashMap<String, Vec<(String, UnboundedSender<serde_json::Value>)>>;
+
+    static CHANNELS: Lazy<Mutex<Subscribers>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+    pub fn subscribe(name: &str, id: &str, tx: UnboundedSender<serde_json::Value>) {
+        if let Ok(mut channels) = CHANNELS.lock() {
+            channels
+                .entry(name.to_string())
+                .or_default()
+                .push((id.to_string(), tx));
+        }
+    }
+
+    pub fn unsubscribe(name: &str, id: &str) {
+        if let Ok(mut channels) = CHANNELS.lock() {
+            if let Some(subscribers) = channels.get_mut(name) {
+                subscribers.retain(|(sub_id, _)| sub_id != id);
+                if subscribers.is_empty() {
+                    channels.remove(name);
+                }
+            }
+        }
+    }
+
+    /// Sends `message` to every subscriber of `name` except `sender`.
+    pub fn post(name: &str, sender: &str, message: serde_json::Value) {
+        if let Ok(mut channels) = CHANNELS.lock() {
+            if let Some(subscribers) = channels.get_mut(name) {
+                subscribers
+                    .retain(|(id, tx)| id == sender || tx.unbounded_send(message.clone()).is_ok());
+            }
+        }
+    }
+}
+
+/// A named channel shared by every tab and window of the app, created by
+/// [`use_broadcast_channel`]. Like `BroadcastChannel`, a sender doesn't receive its own
+/// messages.
+#[derive(Clone)]
+pub struct BroadcastChannel<T: FromJs + Clone + 'static> {
+    pub bridge: JsBridge<T>,
+    name: String,
+}
+
+impl<T> BroadcastChannel<T>
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn last_message(&self) -> Option<T> {
+        self.bridge.get_data()
+    }
+
+    /// Returns a stream of every message received after this call.
+    pub fn messages(&self) -> UnboundedReceiver<T> {
+        self.bridge.stream()
+    }
+
+    /// Sends `message` to every other subscriber of the channel.
+    pub async fn post(&self, message: &T) -> Result<(), String> {
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        {
+            let message =
+                serde_json::to_value(message).map_err(|e| format!("Serialization error: {}", e))?;
+            desktop::post(&self.name, &self.bridge.callback_id(), message);
+            Ok(())
+        }
+
+        #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+        {
+            let message = serde_json::to_string(message)
+                .map_err(|e| format!("Serialization error: {}", e))?;
+            let js_code = format!(
+                r#"
+                const channel = window.__dioxus_broadcast && window.__dioxus_broadcast[{id}];
+                if (!channel) {{
+                    throw new Error("Broadcast channel is closed");
+                }}
+                channel.postMessage({message});
+                "#,
+                id = js_string(&self.bridge.callback_id()),
+            );
+            eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+        }
+    }
+}
+
+/// Joins the broadcast channel `name` until the component unmounts.
+///
+/// Uses `BroadcastChannel` on web and Android. On desktop, messages are routed between
+/// the app's windows in process.
+pub fn use_broadcast_channel<T>(name: &str) -> BroadcastChannel<T>
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    let bridge = use_js_bridge::<T>();
+    let name = use_hook(|| name.to_string());
+
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    {
+        use futures_util::StreamExt;
+
+        let bridge_for_task = bridge.clone();
+        let name_for_task = name.clone();
+        use_hook(move || {
+            let (tx, mut rx) = futures_channel::mpsc::unbounded();
+            desktop::subscribe(&name_for_task, &bridge_for_task.callback_id(), tx);
+            let mut bridge = bridge_for_task;
+            spawn(async move {
+                while let Some(message) = rx.next().await {
+                    bridge.receive_json_value(message);
+                }
+            });
+        });
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+    {
+        let bridge_for_effect = bridge.clone();
+        let name_for_effect = name.clone();
+        use_effect(move || {
+            let js_code = format!(
+                r#"
+                if (!("BroadcastChannel" in window)) {{
+                    throw new Error("BroadcastChannel is not supported");
+                }}
+                window.__dioxus_broadcast = window.__dioxus_broadcast || {{}};
+                const id = {id};
+                if (!window.__dioxus_broadcast[id]) {{
+                    const channel = new BroadcastChannel({name});
+                    channel.onmessage = (e) => {{
+                        if ({callback}) {{
+                            {callback}(e.data);
+                        }}
+                    }};
+                    window.__dioxus_broadcast[id] = channel;
+                }}
+                "#,
+                id = js_string(&bridge_for_effect.callback_id()),
+                name = js_string(&name_for_effect),
+                callback = bridge_for_effect.js_callback(),
+            );
+            let mut bridge = bridge_for_effect.clone();
+            spawn(async move {
+                if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                    bridge.set_error(Some(e));
+                }
+            });
+        });
+    }
+
+    let callback_id = bridge.callback_id();
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    let name_for_drop = name.clone();
+    use_drop(move || {
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        desktop::unsubscribe(&name_for_drop, &callback_id);
+
+        #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+        {
+            eval_detached(&format!(
+                r#"
+                const channel = window.__dioxus_broadcast && window.__dioxus_broadcast[{id}];
+                if (channel) {{
+                    channel.close();
+                    delete window.__dioxus_broadcast[{id}];
+                }}
+                "#,
+                id = js_string(&callback_id)
+            ));
+        }
+    });
+
+    BroadcastChannel { bridge, name }
+}
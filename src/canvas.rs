@@ -0,0 +1,434 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Which rendering context the canvas is driven through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanvasContextKind {
+    #[serde(rename = "2d")]
+    TwoD,
+    Webgl,
+    Webgl2,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CanvasConfig {
+    pub context: CanvasContextKind,
+    /// For 2D contexts, draws each frame into an offscreen back buffer and copies it to the
+    /// canvas once complete, so partially drawn frames are never shown. WebGL contexts are
+    /// always double-buffered by the browser.
+    pub double_buffered: bool,
+    /// Frames submitted but not yet acknowledged before [`CanvasBridge::submit_frame`]
+    /// starts dropping frames.
+    pub max_frames_in_flight: u32,
+}
+
+impl Default for CanvasConfig {
+    fn default() -> Self {
+        Self {
+            context: CanvasContextKind::TwoD,
+            double_buffered: true,
+            max_frames_in_flight: 2,
+        }
+    }
+}
+
+/// A draw command applied by the JS interpreter.
+///
+/// The typed commands map to `CanvasRenderingContext2D`. [`DrawCommand::Call`] and
+/// [`DrawCommand::Set`] reach any context method or property, including WebGL; their
+/// arguments may be `{"$handle": name}` to pass an object stored by an earlier `Call`,
+/// or `{"$f32": [...]}`, `{"$u16": [...]}` and `{"$u8": [...]}` for typed arrays.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DrawCommand {
+    /// Clears the whole canvas regardless of the current transform.
+    Clear,
+    SetFillStyle {
+        style: String,
+    },
+    SetStrokeStyle {
+        style: String,
+    },
+    SetLineWidth {
+        width: f64,
+    },
+    SetFont {
+        font: String,
+    },
+    SetGlobalAlpha {
+        alpha: f64,
+    },
+    FillRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    StrokeRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    ClearRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    BeginPath,
+    MoveTo {
+        x: f64,
+        y: f64,
+    },
+    LineTo {
+        x: f64,
+        y: f64,
+    },
+    Arc {
+        x: f64,
+        y: f64,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+    },
+    ClosePath,
+    Fill,
+    Stroke,
+    FillText {
+        text: String,
+        x: f64,
+        y: f64,
+    },
+    /// Loads an image from `url` for later [`DrawCommand::DrawImage`] calls.
+    LoadImage {
+        key: String,
+        url: String,
+    },
+    /// Draws an image loaded with [`DrawCommand::LoadImage`]; skipped until it has loaded.
+    DrawImage {
+        key: String,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    Save,
+    Restore,
+    Translate {
+        x: f64,
+        y: f64,
+    },
+    Rotate {
+        angle: f64,
+    },
+    Scale {
+        x: f64,
+        y: f64,
+    },
+    /// Calls `ctx[method](...args)`, storing the result as a handle named `store` if set.
+    Call {
+        method: String,
+        args: Vec<serde_json::Value>,
+        store: Option<String>,
+    },
+    /// Sets `ctx[property] = value`.
+    Set {
+        property: String,
+        value: serde_json::Value,
+    },
+}
+
+/// An event from the JS interpreter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CanvasEvent {
+    /// `frame` was drawn and presented.
+    FrameAck {
+        frame: u64,
+        apply_ms: f64,
+    },
+    ImageLoaded {
+        key: String,
+    },
+    Error {
+        frame: Option<u64>,
+        message: String,
+    },
+}
+
+/// A canvas driven by batches of [`DrawCommand`]s, created by [`use_canvas`].
+///
+/// Push commands for a frame, then [`CanvasBridge::submit_frame`] sends them as one
+/// message. JS applies each frame on the next animation frame and acknowledges it.
+#[derive(Clone)]
+pub struct CanvasBridge {
+    /// Frames submitted but not yet acknowledged.
+    pub frames_in_flight: Signal<u32>,
+    pub last_ack: Signal<Option<u64>>,
+    pub error: Signal<Option<String>>,
+    pub bridge: JsBridge<CanvasEvent>,
+    batch: CopyValue<Vec<DrawCommand>>,
+    next_frame: CopyValue<u64>,
+    max_frames_in_flight: u32,
+}
+
+impl CanvasBridge {
+    /// Returns a stream of every canvas event received after this call.
+    pub fn events(&self) -> UnboundedReceiver<CanvasEvent> {
+        self.bridge.stream()
+    }
+
+    pub fn get_error(&self) -> Option<String> {
+        self.error.read().clone()
+    }
+
+    /// Whether a frame submitted now would be sent rather than dropped.
+    pub fn can_submit(&self) -> bool {
+        *self.frames_in_flight.peek() < self.max_frames_in_flight
+    }
+
+    /// Adds a command to the current frame.
+    pub fn push(&self, command: DrawCommand) {
+        let mut batch = self.batch;
+        batch.write().push(command);
+    }
+
+    pub fn extend(&self, commands: impl IntoIterator<Item = DrawCommand>) {
+        let mut batch = self.batch;
+        batch.write().extend(commands);
+    }
+
+    /// Sends the current frame and returns its number, or `None` if too many frames are
+    /// in flight, in which case the frame is dropped. The batch is empty afterwards either
+    /// way.
+    pub fn submit_frame(&self) -> Option<u64> {
+        let mut batch = self.batch;
+        let commands = std::mem::take(&mut *batch.write());
+        if !self.can_submit() {
+            return None;
+        }
+
+        let mut next_frame = self.next_frame;
+        let frame = next_frame();
+        *next_frame.write() += 1;
+        let mut frames_in_flight = self.frames_in_flight;
+        frames_in_flight += 1;
+
+        let id = self.bridge.callback_id();
+        let mut error = self.error;
+        spawn(async move {
+            let commands = match serde_json::to_string(&commands) {
+                Ok(commands) => commands,
+                Err(e) => {
+                    error.set(Some(format!("Serialization error: {}", e)));
+                    frames_in_flight -= 1;
+                    return;
+                }
+            };
+            let js_code = format!(
+                r#"
+                const canvas = window.__dioxus_canvas && window.__dioxus_canvas[{id}];
+                if (!canvas) {{
+                    throw new Error("Canvas bridge is not installed");
+                }}
+                canvas.submit({frame}, {commands});
+                "#,
+                id = js_string(&id),
+            );
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                error.set(Some(e));
+                frames_in_flight -= 1;
+            }
+        });
+        Some(frame)
+    }
+}
+
+fn install_script(selector: &str, config: &CanvasConfig, id: &str, callback: &str) -> String {
+    format!(
+        r#"
+        window.__dioxus_canvas = window.__dioxus_canvas || {{}};
+        const id = {id};
+        if (!window.__dioxus_canvas[id]) {{
+            const config = {config};
+            const target = document.querySelector({selector});
+            if (!target) {{
+                throw new Error("use_canvas: no element matches " + {selector});
+            }}
+            const emit = (event) => {{
+                if ({callback}) {{
+                    {callback}(event);
+                }}
+            }};
+            const visibleCtx = target.getContext(config.context);
+            if (!visibleCtx) {{
+                throw new Error("Failed to get a " + config.context + " context");
+            }}
+            const buffered = config.double_buffered && config.context === "2d";
+            const back = buffered ? document.createElement("canvas") : null;
+            const ctx = buffered ? back.getContext("2d") : visibleCtx;
+            const images = {{}};
+            const handles = {{}};
+            const arg = (a) => {{
+                if (a && typeof a === "object" && !Array.isArray(a)) {{
+                    if ("$handle" in a) return handles[a.$handle];
+                    if ("$f32" in a) return new Float32Array(a.$f32);
+                    if ("$u16" in a) return new Uint16Array(a.$u16);
+                    if ("$u8" in a) return new Uint8Array(a.$u8);
+                }}
+                return a;
+            }};
+            const ops = {{
+                clear: () => {{
+                    ctx.save();
+                    ctx.setTransform(1, 0, 0, 1, 0, 0);
+                    ctx.clearRect(0, 0, ctx.canvas.width, ctx.canvas.height);
+                    ctx.restore();
+                }},
+                set_fill_style: (c) => {{ ctx.fillStyle = c.style; }},
+                set_stroke_style: (c) => {{ ctx.strokeStyle = c.style; }},
+                set_line_width: (c) => {{ ctx.lineWidth = c.width; }},
+                set_font: (c) => {{ ctx.font = c.font; }},
+                set_global_alpha: (c) => {{ ctx.globalAlpha = c.alpha; }},
+                fill_rect: (c) => ctx.fillRect(c.x, c.y, c.width, c.height),
+                stroke_rect: (c) => ctx.strokeRect(c.x, c.y, c.width, c.height),
+                clear_rect: (c) => ctx.clearRect(c.x, c.y, c.width, c.height),
+                begin_path: () => ctx.beginPath(),
+                move_to: (c) => ctx.moveTo(c.x, c.y),
+                line_to: (c) => ctx.lineTo(c.x, c.y),
+                arc: (c) => ctx.arc(c.x, c.y, c.radius, c.start_angle, c.end_angle),
+                close_path: () => ctx.closePath(),
+                fill: () => ctx.fill(),
+                stroke: () => ctx.stroke(),
+                fill_text: (c) => ctx.fillText(c.text, c.x, c.y),
+                load_image: (c) => {{
+                    if (images[c.key]) return;
+                    const image = new Image();
+                    image.onload = () => emit({{ event: "image_loaded", key: c.key }});
+                    image.onerror = () => emit({{
+                        event: "error",
+                        frame: null,
+                        message: "Failed to load image " + c.url,
+                    }});
+                    image.src = c.url;
+                    images[c.key] = image;
+                }},
+                draw_image: (c) => {{
+                    const image = images[c.key];
+                    if (image && image.complete && image.naturalWidth > 0) {{
+                        ctx.drawImage(image, c.x, c.y, c.width, c.height);
+                    }}
+                }},
+                save: () => ctx.save(),
+                restore: () => ctx.restore(),
+                translate: (c) => ctx.translate(c.x, c.y),
+                rotate: (c) => ctx.rotate(c.angle),
+                scale: (c) => ctx.scale(c.x, c.y),
+                call: (c) => {{
+                    const result = ctx[c.method](...c.args.map(arg));
+                    if (c.store !== null) handles[c.store] = result;
+                }},
+                set: (c) => {{ ctx[c.property] = arg(c.value); }},
+            }};
+            const queue = [];
+            let scheduled = false;
+            const present = () => {{
+                scheduled = false;
+                const frames = queue.splice(0);
+                for (const {{ frame, commands }} of frames) {{
+                    const start = performance.now();
+                    try {{
+                        if (buffered && (back.width !== target.width || back.height !== target.height)) {{
+                            back.width = target.width;
+                            back.height = target.height;
+                        }}
+                        for (const c of commands) {{
+                            ops[c.op](c);
+                        }}
+                        if (buffered) {{
+                            visibleCtx.clearRect(0, 0, target.width, target.height);
+                            visibleCtx.drawImage(back, 0, 0);
+                        }}
+                    }} catch (e) {{
+                        emit({{ event: "error", frame, message: String(e) }});
+                    }}
+                    emit({{ event: "frame_ack", frame, apply_ms: performance.now() - start }});
+                }}
+            }};
+            window.__dioxus_canvas[id] = {{
+                submit: (frame, commands) => {{
+                    queue.push({{ frame, commands }});
+                    if (!scheduled) {{
+                        scheduled = true;
+                        requestAnimationFrame(present);
+                    }}
+                }},
+            }};
+        }}
+        "#,
+        id = js_string(id),
+        selector = js_string(selector),
+        config = serde_json::to_string(config).unwrap_or_else(|_| "{}".to_string()),
+    )
+}
+
+/// Drives the `<canvas>` matching `selector` from Rust. Frame pacing follows the acks:
+/// check [`CanvasBridge::can_submit`] before building a frame.
+pub fn use_canvas(selector: &str, config: CanvasConfig) -> CanvasBridge {
+    let mut frames_in_flight = use_signal(|| 0u32);
+    let mut last_ack = use_signal(|| None::<u64>);
+    let mut error = use_signal(|| None::<String>);
+    let batch = use_hook(|| CopyValue::new(Vec::new()));
+    let next_frame = use_hook(|| CopyValue::new(0));
+
+    let bridge = use_js_listener::<CanvasEvent>(move |event| match event {
+        CanvasEvent::FrameAck { frame, .. } => {
+            let in_flight = *frames_in_flight.peek();
+            frames_in_flight.set(in_flight.saturating_sub(1));
+            last_ack.set(Some(frame));
+        }
+        CanvasEvent::ImageLoaded { .. } => {}
+        CanvasEvent::Error { message, .. } => error.set(Some(message)),
+    });
+
+    let selector = use_hook(|| selector.to_string());
+    let config = use_hook(move || config);
+    let max_frames_in_flight = config.max_frames_in_flight;
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = install_script(
+            &selector,
+            &config,
+            &bridge_for_effect.callback_id(),
+            &bridge_for_effect.js_callback(),
+        );
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                error.set(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            "if (window.__dioxus_canvas) delete window.__dioxus_canvas[{}];",
+            js_string(&callback_id)
+        ));
+    });
+
+    CanvasBridge {
+        frames_in_flight,
+        last_ack,
+        error,
+        bridge,
+        batch,
+        next_frame,
+        max_frames_in_flight,
+    }
+}
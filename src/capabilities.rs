@@ -0,0 +1,49 @@
+use crate::BridgeOptions;
+use serde::{Deserialize, Serialize};
+
+/// The version of the bridge's own wire protocol, independent of the app's schema version.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// What one side of a bridge supports, exchanged in the handshake as
+/// `{ "handshake": true, "capabilities": { ... } }`. Missing fields default to empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Capabilities {
+    /// The bridge protocol version, see [`PROTOCOL_VERSION`].
+    pub protocol: u32,
+    /// Codec names, such as `"json"`.
+    pub codecs: Vec<String>,
+    /// Compression formats the side can decode, such as `"gzip"`.
+    pub compression: Vec<String>,
+    /// The largest message, in bytes of JSON, the side accepts.
+    pub max_message_size: Option<usize>,
+    /// Topics or functions the side accepts messages for.
+    pub topics: Vec<String>,
+}
+
+impl Capabilities {
+    /// What the Rust side of a bridge with these options supports.
+    pub(crate) fn local(options: &BridgeOptions, codec: &str) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            codecs: vec![codec.to_string()],
+            compression: if cfg!(feature = "compression") {
+                vec!["gzip".to_string()]
+            } else {
+                Vec::new()
+            },
+            max_message_size: options.max_message_size,
+            topics: options
+                .permissions
+                .as_ref()
+                .map(|permissions| permissions.topics())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether the side decodes `format`; sides that sent no capabilities are assumed to.
+    #[cfg(feature = "compression")]
+    pub(crate) fn decodes(capabilities: Option<&Self>, format: &str) -> bool {
+        capabilities.is_none_or(|capabilities| capabilities.compression.iter().any(|c| c == format))
+    }
+}
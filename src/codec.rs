@@ -0,0 +1,79 @@
+use crate::FromJs;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// How a [`JsBridge`](crate::JsBridge) turns values into what crosses the bridge and back.
+///
+/// Every transport carries JSON, so the wire representation is a JSON value. Binary
+/// formats such as MessagePack or CBOR can encode to a base64 string and decode it again
+/// on the way back.
+pub trait Codec: Clone + 'static {
+    /// The name announced in the handshake's capabilities.
+    const NAME: &'static str = "custom";
+
+    /// Encodes `value` as JSON text.
+    fn encode<S: Serialize>(value: &S) -> Result<String, String>;
+
+    /// Decodes a JSON value received from JS.
+    fn decode<T: FromJs>(wire: serde_json::Value) -> Result<T, String>;
+}
+
+/// The default codec: values cross the bridge as plain JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    const NAME: &'static str = "json";
+
+    fn encode<S: Serialize>(value: &S) -> Result<String, String> {
+        serde_json::to_string(value).map_err(|e| format!("Serialization error: {}", e))
+    }
+
+    /// Strings are parsed as JSON when the value itself doesn't match `T`, so JS may send
+    /// either objects or `JSON.stringify` output.
+    fn decode<T: FromJs>(wire: serde_json::Value) -> Result<T, String> {
+        let parsed = match serde_json::from_value::<T>(wire.clone()) {
+            Ok(parsed) => Ok(parsed),
+            Err(e) => match wire.as_str() {
+                Some(text) => serde_json::from_str::<T>(text),
+                None => Err(e),
+            },
+        };
+        parsed.map_err(|e| format!("Deserialization error: {e}"))
+    }
+}
+
+/// A payload encoded once, to send to many bridges without serializing it again. Clones
+/// share the encoded string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedMessage {
+    json: Arc<str>,
+}
+
+impl PreparedMessage {
+    /// Encodes `data` as plain JSON.
+    pub fn new<S: Serialize>(data: &S) -> Result<Self, String> {
+        Self::with_codec::<JsonCodec, S>(data)
+    }
+
+    pub fn with_codec<C: Codec, S: Serialize>(data: &S) -> Result<Self, String> {
+        Ok(Self {
+            json: C::encode(data)?.into(),
+        })
+    }
+
+    /// Wraps JSON text that is already encoded. It isn't validated.
+    pub fn from_json(json: impl Into<Arc<str>>) -> Self {
+        Self { json: json.into() }
+    }
+
+    pub fn as_json(&self) -> &str {
+        &self.json
+    }
+}
+
+impl From<Box<serde_json::value::RawValue>> for PreparedMessage {
+    fn from(json: Box<serde_json::value::RawValue>) -> Self {
+        Self::from_json(Box::<str>::from(json))
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, eval_detached, js_string};
+use crate::{use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// The system's light or dark preference, created by [`use_color_scheme`].
+#[derive(Clone)]
+pub struct SystemTheme {
+    pub scheme: Signal<ColorScheme>,
+    pub bridge: JsBridge<ColorScheme>,
+}
+
+impl SystemTheme {
+    pub fn get_scheme(&self) -> ColorScheme {
+        *self.scheme.read()
+    }
+
+    pub fn is_dark(&self) -> bool {
+        self.get_scheme() == ColorScheme::Dark
+    }
+}
+
+/// Follows the system's dark mode until the component unmounts, for theming.
+///
+/// Listens to the `(prefers-color-scheme: dark)` media query on web and desktop. On
+/// Android the night mode of the system configuration is read through JNI, since the
+/// WebView's media query follows the app theme instead. The app reports changes from
+/// `onConfigurationChanged`: `RustBridge.watchColorScheme({ callback_id })` posts `"light"`
+/// or `"dark"` to `callback_id` until `unwatchColorScheme({ callback_id })`.
+pub fn use_color_scheme() -> SystemTheme {
+    let mut scheme = use_signal(ColorScheme::default);
+    let bridge = use_js_listener::<ColorScheme>(move |update| {
+        if *scheme.peek() != update {
+            scheme.set(update);
+        }
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let callback_id = bridge_for_effect.callback_id();
+
+        #[cfg(target_os = "android")]
+        {
+            let mut bridge = bridge_for_effect.clone();
+            match crate::android_bridge::system_night_mode() {
+                Ok(true) => scheme.set(ColorScheme::Dark),
+                Ok(false) => scheme.set(ColorScheme::Light),
+                Err(e) => bridge.set_error(Some(e)),
+            }
+            // The app posts "light" or "dark" to this bridge's callback ID on changes
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("watchColorScheme", &args) {
+                bridge.set_error(Some(e));
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let js_code = format!(
+                r#"
+                window.__dioxus_color_scheme = window.__dioxus_color_scheme || {{}};
+                const id = {id};
+                if (!window.__dioxus_color_scheme[id]) {{
+                    const query = matchMedia("(prefers-color-scheme: dark)");
+                    const report = () => {{
+                        if ({callback}) {{
+                            {callback}(query.matches ? "dark" : "light");
+                        }}
+                    }};
+                    query.addEventListener("change", report);
+                    window.__dioxus_color_scheme[id] = {{ query, report }};
+                    report();
+                }}
+                "#,
+                id = js_string(&callback_id),
+                callback = bridge_for_effect.js_callback(),
+            );
+            let mut bridge = bridge_for_effect.clone();
+            spawn(async move {
+                if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                    bridge.set_error(Some(e));
+                }
+            });
+        }
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("unwatchColorScheme", &args) {
+                eprintln!("Failed to stop color scheme watch: {}", e);
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        eval_detached(&format!(
+            r#"
+            const watch = window.__dioxus_color_scheme && window.__dioxus_color_scheme[{id}];
+            if (watch) {{
+                watch.query.removeEventListener("change", watch.report);
+                delete window.__dioxus_color_scheme[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    SystemTheme { scheme, bridge }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// What `encodeURIComponent` escapes: everything but letters, digits and `-_.!~*'()`.
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+/// Attributes used by [`set_cookie`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CookieOptions {
+    pub path: Option<String>,
+    pub domain: Option<String>,
+    pub max_age: Option<i64>,
+    pub expires: Option<DateTime<Utc>>,
+    pub secure: bool,
+    /// Only honored on Android; page JS cannot create `HttpOnly` cookies.
+    pub http_only: bool,
+    pub same_site: Option<SameSite>,
+}
+
+impl CookieOptions {
+    /// Formats the cookie as a `Set-Cookie` style string, percent-encoding the value like
+    /// `encodeURIComponent`. Fails for names that aren't cookie tokens.
+    fn to_cookie_string(&self, name: &str, value: &str) -> Result<String, String> {
+        check_name(name)?;
+        let mut cookie = format!("{}={}", name, utf8_percent_encode(value, COMPONENT));
+        if let Some(path) = &self.path {
+            cookie.push_str(&format!("; Path={}", path));
+        }
+        if let Some(domain) = &self.domain {
+            cookie.push_str(&format!("; Domain={}", domain));
+        }
+        if let Some(max_age) = self.max_age {
+            cookie.push_str(&format!("; Max-Age={}", max_age));
+        }
+        if let Some(expires) = self.expires {
+            cookie.push_str(&format!(
+                "; Expires={}",
+                expires.format("%a, %d %b %Y %H:%M:%S GMT")
+            ));
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        if self.http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        match self.same_site {
+            Some(SameSite::Strict) => cookie.push_str("; SameSite=Strict"),
+            Some(SameSite::Lax) => cookie.push_str("; SameSite=Lax"),
+            Some(SameSite::None) => cookie.push_str("; SameSite=None"),
+            None => {}
+        }
+        Ok(cookie)
+    }
+}
+
+/// Rejects empty names and names with whitespace, control characters or separators such
+/// as `;`, `,` and `=`, which would change what the cookie string means.
+fn check_name(name: &str) -> Result<(), String> {
+    let separator = |c: char| "()<>@,;:\\\"/[]?={}".contains(c);
+    if name.is_empty()
+        || name.chars().any(|c| {
+            !c.is_ascii() || c.is_ascii_control() || c.is_ascii_whitespace() || separator(c)
+        })
+    {
+        return Err(format!("Invalid cookie name: {:?}", name));
+    }
+    Ok(())
+}
+
+/// Finds `name` in a `Cookie` header such as `document.cookie`, percent-decoding its value.
+/// Values that don't decode, e.g. ones set by a server, are returned as they are.
+fn find_cookie(header: &str, name: &str) -> Option<String> {
+    header.split(';').find_map(|pair| {
+        let (key, value) = pair.trim().split_once('=')?;
+        (key == name).then(|| match percent_decode_str(value).decode_utf8() {
+            Ok(decoded) => decoded.into_owned(),
+            Err(_) => value.to_string(),
+        })
+    })
+}
+
+/// Reads a cookie visible to the page, including ones set by the server.
+///
+/// On Android this goes through `CookieManager`, which also sees `HttpOnly` cookies.
+pub async fn get_cookie(name: &str) -> Result<Option<String>, String> {
+    #[cfg(target_os = "android")]
+    {
+        let url = crate::eval_as::<String>("return window.location.href;").await?;
+        let header = crate::android_bridge::get_cookies(&url)?;
+        Ok(header.and_then(|header| find_cookie(&header, name)))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let header = crate::eval_as::<String>("return document.cookie;").await?;
+        Ok(find_cookie(&header, name))
+    }
+}
+
+/// Sets a cookie for the current page.
+pub async fn set_cookie(name: &str, value: &str, options: &CookieOptions) -> Result<(), String> {
+    let cookie = options.to_cookie_string(name, value)?;
+
+    #[cfg(target_os = "android")]
+    {
+        let url = crate::eval_as::<String>("return window.location.href;").await?;
+        crate::android_bridge::set_cookie(&url, &cookie)
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        crate::eval_as::<serde_json::Value>(&format!(
+            "document.cookie = {};",
+            crate::js_string(&cookie)
+        ))
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn values_are_percent_encoded() {
+        let cookie = CookieOptions::default().to_cookie_string("session", "a b;c=d/é");
+        assert_eq!(cookie.as_deref(), Ok("session=a%20b%3Bc%3Dd%2F%C3%A9"));
+        let cookie = CookieOptions::default().to_cookie_string("token", "-_.!~*'()");
+        assert_eq!(cookie.as_deref(), Ok("token=-_.!~*'()"));
+    }
+
+    #[test]
+    fn attributes_follow_the_value() {
+        let options = CookieOptions {
+            path: Some("/".to_string()),
+            domain: Some("example.com".to_string()),
+            max_age: Some(3600),
+            expires: Some(Utc.with_ymd_and_hms(2030, 1, 2, 3, 4, 5).unwrap()),
+            secure: true,
+            http_only: true,
+            same_site: Some(SameSite::Lax),
+        };
+        assert_eq!(
+            options.to_cookie_string("id", "1").as_deref(),
+            Ok("id=1; Path=/; Domain=example.com; Max-Age=3600; \
+                Expires=Wed, 02 Jan 2030 03:04:05 GMT; Secure; HttpOnly; SameSite=Lax")
+        );
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        for name in ["", "a b", "a;b", "a=b", "a,b", "é", "a\nb", "\"a\""] {
+            assert!(check_name(name).is_err(), "{name:?}");
+            assert!(CookieOptions::default()
+                .to_cookie_string(name, "1")
+                .is_err());
+        }
+        for name in ["session", "__Host-id", "a.b_c!"] {
+            assert_eq!(check_name(name), Ok(()), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn cookies_are_found_and_decoded() {
+        let header = "theme=dark; session=a%20b%3Bc;empty=; raw=100%";
+        assert_eq!(find_cookie(header, "session").as_deref(), Some("a b;c"));
+        assert_eq!(find_cookie(header, "theme").as_deref(), Some("dark"));
+        assert_eq!(find_cookie(header, "empty").as_deref(), Some(""));
+        assert_eq!(find_cookie(header, "raw").as_deref(), Some("100%"));
+        assert_eq!(find_cookie(header, "sess"), None);
+        assert_eq!(find_cookie("", "theme"), None);
+    }
+}
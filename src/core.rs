@@ -0,0 +1,521 @@
+use crate::{ids, lifecycle, IdStrategy, PreparedMessage};
+use futures_util::stream::{LocalBoxStream, StreamExt};
+#[cfg(target_arch = "wasm32")]
+use gloo_utils::format::JsValueSerdeExt;
+use serde::Serialize;
+use std::time::Duration;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{prelude::Closure, JsValue};
+
+/// The platform transport behind [`JsBridge`](crate::JsBridge), without any Dioxus state,
+/// for background services, tests and other UI frameworks embedding a webview.
+///
+/// On web it only needs `window`, and on Android only the JNI bridge. Desktop and liveview
+/// evaluate JS through `dioxus::document`, so there it must be used inside a Dioxus runtime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawBridge {
+    callback_id: String,
+}
+
+impl Default for RawBridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RawBridge {
+    /// Creates a bridge with a freshly generated callback ID, unique in this process.
+    pub fn new() -> Self {
+        Self::with_strategy(&IdStrategy::default())
+    }
+
+    /// Creates a bridge with a callback ID from `strategy`, unique in this process.
+    pub fn with_strategy(strategy: &IdStrategy) -> Self {
+        Self::with_id(ids::allocate(strategy))
+    }
+
+    /// Creates a bridge with a known callback ID, e.g. one agreed on with a JS bundle.
+    /// The ID isn't checked against other bridges; see [`RawBridge::try_with_id`].
+    pub fn with_id(callback_id: impl Into<String>) -> Self {
+        Self {
+            callback_id: callback_id.into(),
+        }
+    }
+
+    /// Like [`RawBridge::with_id`], but reserves the ID and fails if another bridge in this
+    /// process holds it. [`RawBridge::close`] frees it again.
+    pub fn try_with_id(callback_id: impl Into<String>) -> Result<Self, String> {
+        let bridge = Self::with_id(callback_id);
+        ids::reserve(&bridge.callback_id)?;
+        Ok(bridge)
+    }
+
+    pub fn callback_id(&self) -> &str {
+        &self.callback_id
+    }
+
+    /// The JS expression that delivers a value to this bridge, e.g. `window.__dioxus_bridge_<id>`.
+    /// On web it looks the callback up in the `window.dxBridge` registry instead, and is
+    /// `undefined` until the bridge is registered.
+    pub fn js_callback(&self) -> String {
+        #[cfg(target_arch = "wasm32")]
+        {
+            format!(
+                "(window.dxBridge && window.dxBridge.get(\"{}\"))",
+                self.callback_id
+            )
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            format!("window.__dioxus_bridge_{}", self.callback_id)
+        }
+    }
+
+    /// Rust → JS: Evaluate JS code (cross-platform via dioxus::html::document().eval)
+    pub async fn eval(&self, js_code: &str) -> Result<(), String> {
+        eval_js(js_code).await
+    }
+
+    pub async fn send<S: Serialize>(&self, data: &S) -> Result<(), String> {
+        let json_data =
+            serde_json::to_string(data).map_err(|e| format!("Serialization error: {}", e))?;
+        self.send_json(&json_data).await
+    }
+
+    /// Sends an already serialized JSON value.
+    pub async fn send_json(&self, json_data: &str) -> Result<(), String> {
+        let _in_flight = lifecycle::InFlight::start(&self.callback_id)?;
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Calling the registered function directly skips compiling an eval script per
+            // message; `JSON.parse` of the payload is also cheaper than parsing it as a JS
+            // literal, so there is no message size where eval wins. `send_path` in
+            // benches/codecs.rs and benches/send_path.js (V8, Node 20) measured the page's
+            // cost per message as 18.5 vs 1.9 us at 110 bytes, 332 vs 113 us at 9 KB and
+            // 35 vs 13.6 ms at 1 MB for eval vs `call1`, so no threshold is used. Eval
+            // remains the fallback while no callback is registered.
+            if let Some(function) = web_shim::cached_callback(&self.callback_id) {
+                let value = js_sys::JSON::parse(json_data)
+                    .map_err(|e| format!("JSON parse error: {:?}", e))?;
+                return function
+                    .call1(&JsValue::NULL, &value)
+                    .map(|_| ())
+                    .map_err(|e| format!("JS call error: {:?}", e));
+            }
+            let js_code = format!(
+                "if (window.dxBridge) {{ window.dxBridge.send(\"{}\", {}); }}",
+                self.callback_id, json_data
+            );
+            self.eval(&js_code).await
+        }
+
+        #[cfg(target_os = "android")]
+        {
+            // Send the message to Java/Kotlin via the JNI bridge, with the callback ID
+            let message = format!(
+                "{{\"callback_id\":\"{}\",\"data\":{}}}",
+                self.callback_id, json_data
+            );
+            crate::android_bridge::send_to_java(message).await
+        }
+
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        {
+            // For Desktop and liveview
+            if cfg!(feature = "ssr") {
+                return Ok(());
+            }
+            if crate::STUB {
+                return Err(crate::BridgeError::Unsupported.into());
+            }
+            let js_code = format!(
+                "if (window.__dioxus_bridge_{}) {{ window.__dioxus_bridge_{}({}); }}",
+                self.callback_id, self.callback_id, json_data
+            );
+            self.eval(&js_code).await
+        }
+    }
+
+    pub async fn send_prepared(&self, message: &PreparedMessage) -> Result<(), String> {
+        self.send_json(message.as_json()).await
+    }
+
+    /// Sends JSON that was validated when it was parsed into a `RawValue`.
+    pub async fn send_raw_json(&self, json: &serde_json::value::RawValue) -> Result<(), String> {
+        self.send_json(json.get()).await
+    }
+
+    /// Sends one payload to every bridge in `targets`, which must live in the document of
+    /// the calling component. Web and desktop deliver it with a single eval; Android
+    /// hands each copy to the JNI bridge. Envelopes of [`JsBridge`](crate::JsBridge)
+    /// options aren't applied.
+    pub async fn broadcast(
+        targets: &[&RawBridge],
+        message: &PreparedMessage,
+    ) -> Result<(), String> {
+        let ids: Vec<&str> = targets
+            .iter()
+            .map(|target| target.callback_id())
+            .filter(|id| !lifecycle::is_shut_down(id))
+            .collect();
+        let json_data = message.as_json();
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Bridges with a resolved callback are called directly with one parsed value
+            let mut functions = Vec::new();
+            let mut remaining = Vec::new();
+            for id in ids {
+                match web_shim::cached_callback(id) {
+                    Some(function) => functions.push(function),
+                    None => remaining.push(id),
+                }
+            }
+            if !functions.is_empty() {
+                let value = js_sys::JSON::parse(json_data)
+                    .map_err(|e| format!("JSON parse error: {:?}", e))?;
+                for function in functions {
+                    function
+                        .call1(&JsValue::NULL, &value)
+                        .map_err(|e| format!("JS call error: {:?}", e))?;
+                }
+            }
+            if remaining.is_empty() {
+                return Ok(());
+            }
+            let js_code = format!(
+                "if (window.dxBridge) {{ const data = {}; for (const id of {}) {{ window.dxBridge.send(id, data); }} }}",
+                json_data,
+                serde_json::to_string(&remaining).unwrap_or_else(|_| "[]".to_string())
+            );
+            eval_js(&js_code).await
+        }
+
+        #[cfg(target_os = "android")]
+        {
+            for id in ids {
+                let message = format!("{{\"callback_id\":\"{}\",\"data\":{}}}", id, json_data);
+                crate::android_bridge::send_to_java(message).await?;
+            }
+            Ok(())
+        }
+
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        {
+            let js_code = format!(
+                "const data = {}; for (const id of {}) {{ const callback = window[\"__dioxus_bridge_\" + id]; if (callback) {{ callback(data); }} }}",
+                json_data,
+                serde_json::to_string(&ids).unwrap_or_else(|_| "[]".to_string())
+            );
+            eval_js(&js_code).await
+        }
+    }
+
+    /// Checks that JS answers and that this bridge's callback is still registered in the
+    /// page, returning the round trip time. Fails after `timeout`.
+    pub async fn ping(&self, timeout: Duration) -> Result<Duration, String> {
+        match self.probe(timeout).await? {
+            (round_trip, true) => Ok(round_trip),
+            (_, false) => Err("The bridge callback is missing from the page".to_string()),
+        }
+    }
+
+    /// Returns the round trip time and whether the callback is still registered. A missing
+    /// callback means JS answered from a fresh context.
+    pub(crate) async fn probe(&self, timeout: Duration) -> Result<(Duration, bool), String> {
+        let sent = crate::rust_now_ms();
+        let script = format!("return !!({});", self.js_callback());
+        let registered =
+            crate::watchdog::with_timeout(crate::eval_as::<bool>(&script), timeout).await??;
+        let round_trip = Duration::from_secs_f64((crate::rust_now_ms() - sent).max(0.0) / 1000.0);
+        Ok((round_trip, registered))
+    }
+
+    /// Sends an already serialized JSON value without waiting for it, for places where no
+    /// task can be spawned. Every transport hands the message off on the first poll.
+    pub fn send_json_detached(&self, json_data: &str) {
+        use futures_util::FutureExt;
+        if let Some(Err(e)) = self.send_json(json_data).now_or_never() {
+            eprintln!("Failed to send to JS: {}", e);
+        }
+    }
+
+    /// Registers the JS callback and returns every value JS passes to it, as JSON.
+    ///
+    /// Strings aren't parsed here, so JS may send either objects or `JSON.stringify`
+    /// output. Call [`RawBridge::close`] to unregister.
+    pub fn listen(&self) -> LocalBoxStream<'static, Result<serde_json::Value, String>> {
+        lifecycle::listening(&self.callback_id);
+        // --- Web: Register JS callback ---
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Values are converted when the stream is polled rather than in the callback,
+            // so the JS caller doesn't wait for Rust to parse them.
+            let (tx, rx) = futures_channel::mpsc::unbounded::<JsValue>();
+            let callback = Closure::<dyn FnMut(JsValue)>::new(move |val: JsValue| {
+                let _ = tx.unbounded_send(val);
+            });
+            web_shim::register(&self.callback_id, callback.as_ref());
+            callback.forget();
+            rx.map(|val| {
+                val.into_serde::<serde_json::Value>()
+                    .map_err(|e| format!("Deserialization error: {e}"))
+            })
+            .boxed_local()
+        }
+
+        // --- Android: Register JNI callback with channel to the listening task ---
+        #[cfg(target_os = "android")]
+        {
+            // JNI calls arrive on the Java thread, so hop onto the listener via a channel
+            let (tx, rx) = futures_channel::mpsc::unbounded::<String>();
+            crate::android_bridge::register_callback(self.callback_id.clone(), move |json| {
+                let _ = tx.unbounded_send(json);
+            });
+
+            self.inject();
+            rx.map(parse_json).boxed_local()
+        }
+
+        // --- Desktop: Register JS callback (Wry) ---
+        // Liveview uses the same path, with `dioxus.send` going over the liveview websocket.
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        {
+            if crate::STUB || cfg!(feature = "ssr") {
+                return futures_util::stream::empty().boxed_local();
+            }
+            // With simd-json, JS sends JSON text so that Rust does the parsing
+            let send = if cfg!(feature = "simd-json") {
+                "dioxus.send(JSON.stringify(data));"
+            } else {
+                "dioxus.send(data);"
+            };
+            // The eval never resolves, which keeps its `dioxus.send` channel open for
+            // as long as the callback is registered.
+            let channel = dioxus::document::eval(&format!(
+                "window.__dioxus_bridge_{} = function(data) {{
+                    {}
+                }};
+                await new Promise(() => {{}});",
+                self.callback_id, send
+            ));
+            futures_util::stream::unfold(channel, |mut channel| async move {
+                #[cfg(feature = "simd-json")]
+                let received = channel.recv::<String>().await.map(parse_json);
+                #[cfg(not(feature = "simd-json"))]
+                let received = channel.recv::<serde_json::Value>().await.map(Ok);
+                match received {
+                    Ok(value) => Some((value, channel)),
+                    Err(e) => {
+                        eprintln!("Desktop bridge channel closed: {:?}", e);
+                        None
+                    }
+                }
+            })
+            .boxed_local()
+        }
+    }
+
+    /// Injects the JS function that forwards to the JNI callback. After Android restarts
+    /// the WebView renderer, the page needs it again while the JNI callback stays registered.
+    #[cfg(target_os = "android")]
+    pub(crate) fn inject(&self) {
+        crate::bootstrap::inject_once(
+            &format!("callback:{}", self.callback_id),
+            &format!(
+                "window.__dioxus_bridge_{} = function(data) {{
+                    if (window.dxNative) {{
+                        window.dxNative.postMessage(JSON.stringify({{ callback_id: '{}', data }}));
+                    }} else if (window.RustBridge) {{
+                        window.RustBridge.postMessage('{}', JSON.stringify(data));
+                    }}
+                }}",
+                self.callback_id, self.callback_id, self.callback_id
+            ),
+        );
+    }
+
+    /// Stops accepting sends, waits up to `deadline` for sends in flight, then unregisters
+    /// the callback. Further sends fail.
+    pub async fn shutdown(&self, deadline: Duration) {
+        lifecycle::drain(&self.callback_id, deadline).await;
+        self.close();
+    }
+
+    /// Unregisters the callback installed by [`RawBridge::listen`].
+    pub fn close(&self) {
+        lifecycle::closed(&self.callback_id);
+        ids::release(&self.callback_id);
+        #[cfg(target_arch = "wasm32")]
+        web_shim::unregister(&self.callback_id);
+
+        #[cfg(target_os = "android")]
+        {
+            crate::android_bridge::unregister_callback(&self.callback_id);
+            crate::bootstrap::forget(&format!("callback:{}", self.callback_id));
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::eval_detached(&format!(
+            "delete window.__dioxus_bridge_{};",
+            self.callback_id
+        ));
+    }
+}
+
+/// Evaluates JS code without a result, on any platform.
+async fn eval_js(js_code: &str) -> Result<(), String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        dioxus::document::eval(js_code)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("JS eval error: {:?}", e))
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        // For Android, we'll use the JNI bridge to evaluate JS
+        crate::android_bridge::eval_js(js_code).await
+    }
+
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    {
+        // During server rendering there's no page yet; the hydrated client bridge
+        // takes over once the app is interactive
+        if cfg!(feature = "ssr") {
+            return Ok(());
+        }
+        if crate::STUB {
+            return Err(crate::BridgeError::Unsupported.into());
+        }
+        // For Desktop and liveview, we can use dioxus::document::eval
+        dioxus::document::eval(js_code)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("JS eval error: {:?}", e))
+    }
+}
+
+/// Parses JSON text received from JS, with simd-json when the `simd-json` feature is on.
+/// Large payloads such as state snapshots parse several times faster that way.
+#[cfg(any(
+    all(
+        not(target_arch = "wasm32"),
+        any(target_os = "android", feature = "simd-json")
+    ),
+    feature = "fuzzing"
+))]
+pub(crate) fn parse_json(json: String) -> Result<serde_json::Value, String> {
+    #[cfg(feature = "simd-json")]
+    {
+        let mut bytes = json.into_bytes();
+        simd_json::serde::from_slice(&mut bytes).map_err(|e| format!("Deserialization error: {e}"))
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    {
+        serde_json::from_str(&json).map_err(|e| format!("Deserialization error: {e}"))
+    }
+}
+
+/// A script for the page's `<head>` that lets JS send to web bridges before the wasm module
+/// has loaded: `window.dxBridge.send(id, data)` queues the value until a bridge with that
+/// ID registers. Only needed for messages sent that early; the registry queues by itself
+/// once the module runs.
+pub const INBOUND_QUEUE_JS: &str = r#"window.dxBridge = window.dxBridge || {
+    queued: {},
+    send(id, data) {
+        const queue = this.queued[id] = this.queued[id] || [];
+        if (queue.length < 100) queue.push(data);
+        return false;
+    },
+    get() {},
+    port() {},
+};"#;
+
+/// The `window.dxBridge` registry that web bridges register their callbacks in, instead of
+/// each setting its own property on `window`. JS delivers a value with
+/// `window.dxBridge.send(id, data)`, which returns whether a bridge with that ID exists.
+/// Values for an ID nobody registered yet are queued, up to 100, and replayed when a bridge
+/// registers it, including those queued by [`INBOUND_QUEUE_JS`]. Ports opened by
+/// [`use_js_port`](crate::use_js_port) are registered here too, for `window.dxBridge.port(id)`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) mod web_shim {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen(inline_js = r#"
+    function shim() {
+        if (!window.dxBridge || !window.dxBridge.register) {
+            const callbacks = new Map();
+            const ports = new Map();
+            const queued = new Map(Object.entries((window.dxBridge && window.dxBridge.queued) || {}));
+            window.dxBridge = {
+                register: (id, callback) => {
+                    callbacks.set(id, callback);
+                    const early = queued.get(id) || [];
+                    queued.delete(id);
+                    for (const data of early) callback(data);
+                },
+                unregister: (id) => { callbacks.delete(id); },
+                get: (id) => callbacks.get(id),
+                registerPort: (id, port) => { ports.set(id, port); },
+                unregisterPort: (id) => { ports.delete(id); },
+                port: (id) => ports.get(id),
+                send: (id, data) => {
+                    const callback = callbacks.get(id);
+                    if (callback) {
+                        callback(data);
+                    } else {
+                        const queue = queued.get(id) || [];
+                        if (queue.length < 100) queue.push(data);
+                        queued.set(id, queue);
+                    }
+                    return callback !== undefined;
+                },
+            };
+        }
+        return window.dxBridge;
+    }
+    export function register(id, callback) { shim().register(id, callback); }
+    export function unregister(id) { shim().unregister(id); }
+    export function lookup(id) { return shim().get(id); }
+    export function register_port(id, port) { shim().registerPort(id, port); }
+    export function unregister_port(id) { shim().unregisterPort(id); }
+    "#)]
+    extern "C" {
+        pub fn register(id: &str, callback: &JsValue);
+        pub fn register_port(id: &str, port: &web_sys::MessagePort);
+        pub fn unregister_port(id: &str);
+        #[wasm_bindgen(js_name = unregister)]
+        fn unregister_js(id: &str);
+        fn lookup(id: &str) -> JsValue;
+    }
+
+    thread_local! {
+        /// Callbacks already resolved by [`cached_callback`], by bridge ID.
+        static FUNCTIONS: std::cell::RefCell<std::collections::HashMap<String, js_sys::Function>> =
+            Default::default();
+    }
+
+    pub fn unregister(id: &str) {
+        FUNCTIONS.with(|functions| functions.borrow_mut().remove(id));
+        unregister_js(id);
+    }
+
+    /// Returns the callback registered for `id`, resolving it through the registry only
+    /// on first use.
+    pub fn cached_callback(id: &str) -> Option<js_sys::Function> {
+        FUNCTIONS.with(|functions| {
+            if let Some(function) = functions.borrow().get(id) {
+                return Some(function.clone());
+            }
+            let function = lookup(id).dyn_into::<js_sys::Function>().ok()?;
+            functions
+                .borrow_mut()
+                .insert(id.to_string(), function.clone());
+            Some(function)
+        })
+    }
+}
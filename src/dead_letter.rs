@@ -0,0 +1,24 @@
+/// How many undeliverable messages a bridge keeps; older ones are dropped first.
+pub const MAX_DEAD_LETTERS: usize = 100;
+
+/// A message to JS that couldn't be delivered, kept by the bridge instead of being dropped;
+/// see [`JsBridge::dead_letters`](crate::JsBridge::dead_letters).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadLetter {
+    /// The message's JSON, before any envelope.
+    pub json: String,
+    /// The error of the last attempt.
+    pub reason: String,
+    /// When delivery was given up, as a [`rust_now_ms`](crate::rust_now_ms) timestamp.
+    pub failed_at_ms: f64,
+}
+
+impl DeadLetter {
+    pub(crate) fn new(json: &str, reason: String) -> Self {
+        Self {
+            json: json.to_string(),
+            reason,
+            failed_at_ms: crate::rust_now_ms(),
+        }
+    }
+}
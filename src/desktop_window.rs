@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+/// A change to the native desktop window, applied by [`window_command`]. Sizes and
+/// positions are in logical pixels.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum WindowCommand {
+    /// Resizes the content area, excluding the title bar and borders.
+    SetSize {
+        width: f64,
+        height: f64,
+    },
+    /// Moves the window's top-left corner, including its decorations.
+    SetPosition {
+        x: f64,
+        y: f64,
+    },
+    Minimize,
+    Maximize,
+    /// Undoes minimizing and maximizing.
+    Restore,
+    SetAlwaysOnTop {
+        always_on_top: bool,
+    },
+    SetResizable {
+        resizable: bool,
+    },
+    SetTitle {
+        title: String,
+    },
+    Focus,
+    Close,
+}
+
+/// The window's geometry and state, from [`window_state`]. Logical pixels.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub width: f64,
+    pub height: f64,
+    /// `None` where the platform doesn't report window positions, such as Wayland.
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub focused: bool,
+    pub always_on_top: bool,
+    pub scale_factor: f64,
+}
+
+/// The window of the desktop app being rendered.
+#[cfg(all(
+    feature = "desktop",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+pub(crate) fn desktop_context() -> Result<dioxus_desktop::DesktopContext, String> {
+    dioxus::prelude::try_consume_context::<dioxus_desktop::DesktopContext>()
+        .ok_or_else(|| "Not called from inside a desktop app".to_string())
+}
+
+/// Applies `command` to the app's window through tao. Desktop only: other platforms
+/// return an error.
+pub fn window_command(command: &WindowCommand) -> Result<(), String> {
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        use dioxus_desktop::{LogicalPosition, LogicalSize};
+
+        let window = desktop_context()?;
+        match command {
+            WindowCommand::SetSize { width, height } => {
+                window.set_inner_size(LogicalSize::new(*width, *height))
+            }
+            WindowCommand::SetPosition { x, y } => {
+                window.set_outer_position(LogicalPosition::new(*x, *y))
+            }
+            WindowCommand::Minimize => window.set_minimized(true),
+            WindowCommand::Maximize => window.set_maximized(true),
+            WindowCommand::Restore => {
+                window.set_minimized(false);
+                window.set_maximized(false);
+            }
+            WindowCommand::SetAlwaysOnTop { always_on_top } => {
+                window.set_always_on_top(*always_on_top)
+            }
+            WindowCommand::SetResizable { resizable } => window.set_resizable(*resizable),
+            WindowCommand::SetTitle { title } => window.set_title(title),
+            WindowCommand::Focus => window.set_focus(),
+            WindowCommand::Close => window.close(),
+        }
+        Ok(())
+    }
+
+    #[cfg(not(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    )))]
+    {
+        let _ = command;
+        Err("Window control is only available on desktop".to_string())
+    }
+}
+
+/// Reads the app window's size, position and state. Desktop only.
+pub fn window_state() -> Result<WindowState, String> {
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        let window = desktop_context()?;
+        let scale_factor = window.scale_factor();
+        let size = window.inner_size().to_logical::<f64>(scale_factor);
+        let position = window
+            .outer_position()
+            .ok()
+            .map(|position| position.to_logical::<f64>(scale_factor));
+        Ok(WindowState {
+            width: size.width,
+            height: size.height,
+            x: position.map(|position| position.x),
+            y: position.map(|position| position.y),
+            minimized: window.is_minimized(),
+            maximized: window.is_maximized(),
+            focused: window.is_focused(),
+            always_on_top: window.is_always_on_top(),
+            scale_factor,
+        })
+    }
+
+    #[cfg(not(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    )))]
+    Err("Window control is only available on desktop".to_string())
+}
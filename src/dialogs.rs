@@ -0,0 +1,128 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, js_string};
+
+// A modal `<dialog>` resolving to the button pressed and the input's value. Native
+// dialogs would block the page's event loop, and some webviews don't show them at all.
+#[cfg(not(target_os = "android"))]
+const JS_DIALOG: &str = r#"
+window.__dxDialog = window.__dxDialog || ((message, input) => new Promise((resolve) => {
+    const dialog = document.createElement("dialog");
+    dialog.style.cssText = "max-width:min(90vw,420px);font:inherit;border:0;border-radius:8px;" +
+        "padding:20px;box-shadow:0 8px 32px rgba(0,0,0,.3)";
+    const form = document.createElement("form");
+    form.method = "dialog";
+    const text = document.createElement("p");
+    text.style.cssText = "margin:0 0 16px;white-space:pre-wrap";
+    text.textContent = message;
+    form.appendChild(text);
+    let field = null;
+    if (typeof input === "string") {
+        field = document.createElement("input");
+        field.value = input;
+        field.style.cssText = "display:block;width:100%;box-sizing:border-box;margin-bottom:16px";
+        form.appendChild(field);
+    }
+    const buttons = document.createElement("div");
+    buttons.style.cssText = "display:flex;justify-content:flex-end;gap:8px";
+    for (const [label, value] of input === undefined ? [["OK", "ok"]] : [["Cancel", "cancel"], ["OK", "ok"]]) {
+        const button = document.createElement("button");
+        button.textContent = label;
+        button.value = value;
+        buttons.appendChild(button);
+    }
+    form.appendChild(buttons);
+    dialog.appendChild(form);
+    dialog.addEventListener("close", () => {
+        dialog.remove();
+        resolve({ ok: dialog.returnValue === "ok", value: field ? field.value : null });
+    });
+    document.body.appendChild(dialog);
+    dialog.showModal();
+    (field || buttons.lastChild).focus();
+}));
+"#;
+
+#[cfg(not(target_os = "android"))]
+#[derive(serde::Deserialize)]
+struct DialogResult {
+    ok: bool,
+    value: Option<String>,
+}
+
+/// Shows a modal dialog and waits for it. `input` is `undefined` for an alert, `null` for
+/// a confirmation, and the default text for a prompt.
+#[cfg(not(target_os = "android"))]
+async fn show(message: &str, input: &str) -> Result<DialogResult, String> {
+    eval_as(&format!(
+        r#"
+        {helpers}
+        return await window.__dxDialog({message}, {input});
+        "#,
+        helpers = JS_DIALOG,
+        message = js_string(message),
+    ))
+    .await
+}
+
+/// Shows a message and resolves when the user dismisses it.
+///
+/// Web and desktop show a modal `<dialog>` in the page instead of `window.alert`, which
+/// would block the event loop. Android shows an `AlertDialog`:
+/// `RustBridge.showDialog({ kind: "alert", message })` answers with `null` once it's
+/// dismissed.
+pub async fn alert(message: &str) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android_bridge::call_native_async(
+            "showDialog",
+            serde_json::json!({ "kind": "alert", "message": message }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        show(message, "undefined").await.map(|_| ())
+    }
+}
+
+/// Asks the user to confirm, returning whether they chose OK. Shown the same way as
+/// [`alert`], with `kind: "confirm"` on Android, where the app answers with a bool.
+pub async fn confirm(message: &str) -> Result<bool, String> {
+    #[cfg(target_os = "android")]
+    {
+        let ok = crate::android_bridge::call_native_async(
+            "showDialog",
+            serde_json::json!({ "kind": "confirm", "message": message }),
+        )
+        .await?;
+        serde_json::from_value(ok).map_err(|e| format!("Deserialization error: {}", e))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        show(message, "null").await.map(|result| result.ok)
+    }
+}
+
+/// Asks the user for text, starting from `default`. `None` if they cancel. Shown the same
+/// way as [`alert`], with `kind: "prompt"` and `default` on Android, where the app answers
+/// with the text or `null`.
+pub async fn prompt(message: &str, default: &str) -> Result<Option<String>, String> {
+    #[cfg(target_os = "android")]
+    {
+        let value = crate::android_bridge::call_native_async(
+            "showDialog",
+            serde_json::json!({ "kind": "prompt", "message": message, "default": default }),
+        )
+        .await?;
+        serde_json::from_value(value).map_err(|e| format!("Deserialization error: {}", e))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let result = show(message, &js_string(default)).await?;
+        Ok(result.value.filter(|_| result.ok))
+    }
+}
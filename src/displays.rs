@@ -0,0 +1,140 @@
+#[cfg(any(
+    target_arch = "wasm32",
+    not(any(target_os = "android", feature = "desktop"))
+))]
+use crate::eval_as;
+use serde::{Deserialize, Serialize};
+
+/// A monitor or screen, from [`get_displays`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    pub name: Option<String>,
+    /// The resolution in physical pixels.
+    pub width: u32,
+    pub height: u32,
+    /// The top-left corner in the desktop's coordinate space, in physical pixels.
+    pub x: i32,
+    pub y: i32,
+    /// Physical pixels per CSS pixel.
+    pub scale_factor: f64,
+    /// In Hz, where the platform reports it.
+    pub refresh_rate: Option<f64>,
+    pub primary: bool,
+    /// Whether the app's window is on this display.
+    pub current: bool,
+}
+
+/// Lists the displays, so a renderer can size its canvases for them.
+///
+/// Desktop reads every monitor from tao. The web uses the Window Management API's
+/// `getScreenDetails` once the user has granted it, and otherwise reports the current
+/// screen only, with its refresh rate measured over a few animation frames. Android
+/// reports the default display's `DisplayMetrics`, without a refresh rate.
+pub async fn get_displays() -> Result<Vec<DisplayInfo>, String> {
+    #[cfg(target_os = "android")]
+    {
+        let (width, height, density) = crate::android_bridge::display_metrics()?;
+        Ok(vec![DisplayInfo {
+            name: None,
+            width: width as u32,
+            height: height as u32,
+            x: 0,
+            y: 0,
+            scale_factor: density as f64,
+            refresh_rate: None,
+            primary: true,
+            current: true,
+        }])
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        let window = crate::desktop_window::desktop_context()?;
+        let primary = window.primary_monitor();
+        let current = window.current_monitor();
+        Ok(window
+            .available_monitors()
+            .map(|monitor| {
+                let size = monitor.size();
+                let position = monitor.position();
+                // The current video mode isn't reported; take the fastest one at this size
+                let refresh_rate = monitor
+                    .video_modes()
+                    .filter(|mode| mode.size() == size)
+                    .map(|mode| mode.refresh_rate())
+                    .max()
+                    .filter(|rate| *rate > 0)
+                    .map(f64::from);
+                DisplayInfo {
+                    name: monitor.name(),
+                    width: size.width,
+                    height: size.height,
+                    x: position.x,
+                    y: position.y,
+                    scale_factor: monitor.scale_factor(),
+                    refresh_rate,
+                    primary: primary.as_ref() == Some(&monitor),
+                    current: current.as_ref() == Some(&monitor),
+                }
+            })
+            .collect())
+    }
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        not(any(target_os = "android", feature = "desktop"))
+    ))]
+    {
+        eval_as(
+            r#"
+            const measureRefreshRate = () => new Promise((resolve) => {
+                const times = [];
+                const frame = (time) => {
+                    times.push(time);
+                    if (times.length < 11) {
+                        requestAnimationFrame(frame);
+                        return;
+                    }
+                    const deltas = times.slice(1).map((t, i) => t - times[i]).sort((a, b) => a - b);
+                    const median = deltas[Math.floor(deltas.length / 2)];
+                    resolve(median > 0 ? Math.round(1000 / median) : null);
+                };
+                requestAnimationFrame(frame);
+                // Hidden pages don't get animation frames
+                setTimeout(() => resolve(null), 1000);
+            });
+            const refreshRate = await measureRefreshRate();
+            const describe = (screen, current) => {
+                const scale = screen.devicePixelRatio ?? window.devicePixelRatio;
+                return {
+                    name: screen.label || null,
+                    width: Math.round(screen.width * scale),
+                    height: Math.round(screen.height * scale),
+                    x: Math.round((screen.left ?? 0) * scale),
+                    y: Math.round((screen.top ?? 0) * scale),
+                    scale_factor: scale,
+                    refresh_rate: current ? refreshRate : null,
+                    primary: screen.isPrimary ?? true,
+                    current,
+                };
+            };
+            if (window.getScreenDetails && navigator.permissions) {
+                try {
+                    const status = await navigator.permissions.query({ name: "window-management" });
+                    if (status.state === "granted") {
+                        const details = await window.getScreenDetails();
+                        return details.screens.map((s) => describe(s, s === details.currentScreen));
+                    }
+                } catch (e) {
+                    // Unknown permission name: fall back to the current screen
+                }
+            }
+            return [describe(window.screen, true)];
+            "#,
+        )
+        .await
+    }
+}
@@ -0,0 +1,327 @@
+use serde::Deserialize;
+
+/// What a bridge does with messages from JS built against a different schema version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VersionPolicy {
+    /// Drop the message and report a schema version mismatch.
+    #[default]
+    Reject,
+    /// Deliver the message and log the mismatch.
+    Warn,
+    /// Deliver the message silently. Decoding errors still name the mismatch.
+    BestEffort,
+}
+
+/// Settings for a bridge, passed to [`use_js_bridge_with_options`](crate::use_js_bridge_with_options).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BridgeOptions {
+    /// The app's message schema version. When set, messages in both directions are
+    /// wrapped as `{ "v": version, "data": value }`, and JS may announce its version with
+    /// `{ "v": version, "handshake": true, "capabilities": { ... } }`, which Rust answers
+    /// with `{ "v": version, "handshake": true, "ack": true, "capabilities": { ... } }`.
+    pub schema_version: Option<u32>,
+    pub version_policy: VersionPolicy,
+    /// Stamps every message to JS with a monotonically increasing `"id"` in the envelope,
+    /// and drops messages from JS whose `"id"` isn't newer than the last one, so queues
+    /// that flush twice (e.g. on Android after a reconnect) don't deliver twice. JS
+    /// receivers can skip duplicates with `if (!window.dxAccept(callbackId, message)) return;`.
+    pub message_ids: bool,
+    /// Gzips messages to JS whose JSON is longer than this many bytes. They are sent as
+    /// `{ "z": "gzip", "data": base64 }`; JS unwraps any envelope with
+    /// `await window.dxOpen(message)`, which uses `DecompressionStream` or `window.pako`.
+    #[cfg(feature = "compression")]
+    pub compress_above: Option<usize>,
+    /// The largest message JS may send, in bytes of JSON, announced as `max_message_size`
+    /// in the handshake. Gzipped messages from JS are rejected once they inflate past it,
+    /// or past [`MAX_INFLATED_SIZE`](crate::MAX_INFLATED_SIZE) without one.
+    pub max_message_size: Option<usize>,
+    /// Signs or encrypts messages in both directions, and rejects messages from JS that
+    /// don't verify, handshakes included; a protected handshake seals its capabilities as
+    /// `data`. JS passes the bridge's key to `window.dxOpen(message, key)`. Protected
+    /// bridges always use [`message_ids`](Self::message_ids), and reject messages from JS
+    /// without an `"id"` or with one that was already received.
+    #[cfg(feature = "security")]
+    pub security: Option<crate::Security>,
+    /// Checks the sender of every message from JS before it is delivered, and accepts
+    /// messages posted from other frames.
+    pub origin_policy: Option<crate::OriginPolicy>,
+    /// Limits the topics JS may send, and which senders may send each one.
+    pub permissions: Option<crate::Permissions>,
+    /// Pings JS periodically and updates [`JsBridge::is_alive`](crate::JsBridge::is_alive).
+    pub watchdog: Option<crate::Watchdog>,
+    /// Retries sends to JS and [`JsBridge::eval`](crate::JsBridge::eval) calls that fail
+    /// for transient reasons instead of failing at once.
+    pub retry: Option<crate::RetryPolicy>,
+    /// Sends values from [`JsBridge::send_to_js`](crate::JsBridge::send_to_js) as JSON
+    /// Patch diffs against the previous one, marked `{ "delta": "patch" }`, with a full
+    /// `{ "delta": "snapshot" }` every this many messages. JS passes the callback ID to
+    /// `window.dxOpen(message, key, callbackId)`, which keeps the last value and applies
+    /// the diffs.
+    pub delta_resync: Option<u32>,
+    /// How the bridge's callback ID is generated. Generated IDs are made unique within
+    /// the process either way.
+    pub id_strategy: crate::IdStrategy,
+    /// How often received values are written to `data`. Throttling keeps re-renders
+    /// bounded for high-frequency streams; listeners still get every value.
+    pub update_policy: crate::UpdatePolicy,
+    /// Returns to the event loop before decoding each message from JS, so a burst of large
+    /// messages, e.g. a big state sync split into parts, doesn't block input in between.
+    pub yield_between_messages: bool,
+    /// How enums are represented in messages both ways, e.g. internally tagged so JS can
+    /// `switch (message.type)`. Permissions read the topic from the tag. Messages sent with
+    /// [`JsBridge::send_prepared`](crate::JsBridge::send_prepared) keep the representation
+    /// they were encoded with.
+    pub enum_tagging: crate::EnumTagging,
+}
+
+impl BridgeOptions {
+    pub(crate) fn uses_envelope(&self) -> bool {
+        #[cfg(feature = "compression")]
+        if self.compress_above.is_some() {
+            return true;
+        }
+        #[cfg(feature = "security")]
+        if self.security.is_some() {
+            return true;
+        }
+        self.schema_version.is_some() || self.message_ids || self.delta_resync.is_some()
+    }
+
+    /// Whether messages carry IDs: always for protected bridges, so that a captured
+    /// message can't be replayed.
+    pub(crate) fn stamps_ids(&self) -> bool {
+        #[cfg(feature = "security")]
+        if self.security.is_some() {
+            return true;
+        }
+        self.message_ids
+    }
+}
+
+/// Checks the ID of a message from JS against `last`, the newest one received, and records
+/// it. Returns `false` for a message that was already delivered. Protected messages must
+/// carry an ID, and one that isn't newer is rejected as a replay.
+pub(crate) fn admit_id(
+    last: &mut Option<u64>,
+    id: Option<u64>,
+    protected: bool,
+) -> Result<bool, String> {
+    let Some(id) = id else {
+        return match protected {
+            true => Err("Rejected protected message without an ID".to_string()),
+            false => Ok(true),
+        };
+    };
+    if last.is_some_and(|last| id <= last) {
+        return match protected {
+            true => Err(format!("Rejected replayed message {id}")),
+            false => Ok(false),
+        };
+    }
+    *last = Some(id);
+    Ok(true)
+}
+
+/// A message from JS wrapped with its schema version and ID.
+#[derive(Deserialize)]
+pub(crate) struct Envelope {
+    pub v: Option<u32>,
+    pub id: Option<u64>,
+    #[serde(default)]
+    pub handshake: bool,
+    /// Set on Rust's answer to a handshake, which must not be answered again.
+    #[serde(default)]
+    pub ack: bool,
+    /// What the sender of a handshake supports.
+    pub capabilities: Option<crate::Capabilities>,
+    /// The compression of `data`, if any.
+    pub z: Option<String>,
+    /// The signature of a signed message.
+    #[cfg(feature = "security")]
+    pub sig: Option<String>,
+    /// The IV of an encrypted message.
+    #[cfg(feature = "security")]
+    pub iv: Option<String>,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl Envelope {
+    /// Strings are parsed as JSON, so JS may send either objects or `JSON.stringify` output.
+    pub fn parse(wire: serde_json::Value) -> Result<Self, String> {
+        let parsed = match wire {
+            serde_json::Value::String(text) => serde_json::from_str(&text),
+            wire => serde_json::from_value(wire),
+        };
+        parsed.map_err(|e| format!("Invalid message envelope: {e}"))
+    }
+
+    /// The message, decompressed if JS compressed it, up to `limit` bytes. `text` is the
+    /// payload of a protected message, already verified or decrypted.
+    #[allow(unused_variables)]
+    pub fn into_data(
+        self,
+        text: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<serde_json::Value, String> {
+        let parse = |json: &str| {
+            serde_json::from_str(json).map_err(|e| format!("Deserialization error: {e}"))
+        };
+        match self.z.as_deref() {
+            None => match text {
+                Some(text) => parse(&text),
+                None => Ok(self.data),
+            },
+            #[cfg(feature = "compression")]
+            Some("gzip") => {
+                let compressed = match &text {
+                    Some(text) => text,
+                    None => self
+                        .data
+                        .as_str()
+                        .ok_or("Compressed data must be a string")?,
+                };
+                let limit = limit.unwrap_or(MAX_INFLATED_SIZE);
+                parse(&gunzip(&crate::binary::decode(compressed)?, limit)?)
+            }
+            Some(other) => Err(format!("Unsupported message compression: {other}")),
+        }
+    }
+}
+
+/// Envelope fields besides `v`, `id` and `data`, as names and JSON values.
+pub(crate) type Fields = Vec<(&'static str, String)>;
+
+/// Wraps an encoded message for the wire.
+pub(crate) fn wrap(
+    version: Option<u32>,
+    id: Option<u64>,
+    fields: &Fields,
+    json_data: &str,
+) -> String {
+    let mut wrapped = format!("{{{}", header(version, id));
+    for (name, value) in fields {
+        wrapped.push_str(&format!("\"{}\":{},", name, value));
+    }
+    format!("{}\"data\":{}}}", wrapped, json_data)
+}
+
+/// Gzips JSON text, returning it base64 encoded.
+#[cfg(feature = "compression")]
+pub(crate) fn gzip(json_data: &str) -> Result<String, String> {
+    use std::io::Write;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder
+        .write_all(json_data.as_bytes())
+        .and_then(|_| encoder.finish())
+        .map(|bytes| crate::binary::encode(&bytes))
+        .map_err(|e| format!("Compression error: {}", e))
+}
+
+/// The most a gzipped message from JS may inflate to when
+/// [`BridgeOptions::max_message_size`] isn't set: 16 MiB.
+pub const MAX_INFLATED_SIZE: usize = 16 << 20;
+
+/// Inflates gzipped JSON, stopping with an error once it exceeds `limit` bytes.
+#[cfg(feature = "compression")]
+fn gunzip(bytes: &[u8], limit: usize) -> Result<String, String> {
+    use std::io::Read;
+    let mut json = String::new();
+    flate2::read::GzDecoder::new(bytes)
+        .take(limit as u64 + 1)
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Decompression error: {}", e))?;
+    if json.len() > limit {
+        return Err(format!(
+            "Compressed message inflates past the {} bytes Rust accepts",
+            limit
+        ));
+    }
+    Ok(json)
+}
+
+pub(crate) fn handshake(
+    version: Option<u32>,
+    id: Option<u64>,
+    capabilities: &crate::Capabilities,
+) -> String {
+    format!(
+        "{{{}\"handshake\":true,\"ack\":true,\"capabilities\":{}}}",
+        header(version, id),
+        serde_json::to_string(capabilities).unwrap_or_else(|_| "{}".to_string())
+    )
+}
+
+/// The `"v"` and `"id"` fields, each followed by a comma.
+fn header(version: Option<u32>, id: Option<u64>) -> String {
+    let mut header = String::new();
+    if let Some(version) = version {
+        header.push_str(&format!("\"v\":{},", version));
+    }
+    if let Some(id) = id {
+        header.push_str(&format!("\"id\":{},", id));
+    }
+    header
+}
+
+pub(crate) fn mismatch_error(expected: u32, found: Option<u32>) -> String {
+    match found {
+        Some(found) => {
+            format!("Schema version mismatch: JS uses v{found}, but Rust expects v{expected}")
+        }
+        None => {
+            format!("Schema version mismatch: JS sent no version, but Rust expects v{expected}")
+        }
+    }
+}
+
+/// Defines `window.dxAccept(callbackId, message)`, which returns false for messages whose
+/// `id` isn't newer than the last one accepted for that bridge.
+pub(crate) const JS_DEDUP: &str = r#"
+window.__dioxus_seen = window.__dioxus_seen || {};
+window.dxAccept = window.dxAccept || function(callbackId, message) {
+    if (!message || typeof message.id !== "number") return true;
+    const last = window.__dioxus_seen[callbackId];
+    if (last !== undefined && message.id <= last) return false;
+    window.__dioxus_seen[callbackId] = message.id;
+    return true;
+};
+"#;
+
+/// Defines `window.dxOpen(message, key, callbackId)`, which resolves to the data of an
+/// envelope from Rust, verifying or decrypting it with the bridge's key and decompressing
+/// it as needed. Delta messages are applied to the bridge's last value.
+pub(crate) const JS_OPEN: &str = r#"
+window.dxOpen = window.dxOpen || (() => {
+    const openData = async (message, key) => {
+        if (!message) return message;
+        if (window.__dioxus_trace) console.debug("[dx_bridge] open message", { id: message.id, v: message.v });
+        let text;
+        if (message.sig !== undefined || message.iv !== undefined) {
+            if (!key || !window.dxSecurity) throw new Error("Protected message needs the bridge key");
+            text = await window.dxSecurity.unseal(key, message);
+        } else if (message.z === undefined) {
+            return message.data;
+        } else {
+            text = message.data;
+        }
+        if (message.z === undefined) return JSON.parse(text);
+        if (message.z !== "gzip") throw new Error("Unsupported message compression: " + message.z);
+        const bytes = Uint8Array.from(atob(text), (c) => c.charCodeAt(0));
+        if ("DecompressionStream" in window) {
+            const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream("gzip"));
+            return JSON.parse(await new Response(stream).text());
+        }
+        if (window.pako) return JSON.parse(window.pako.ungzip(bytes, { to: "string" }));
+        throw new Error("Neither DecompressionStream nor pako is available");
+    };
+    return async (message, key, callbackId) => {
+        const data = await openData(message, key);
+        if (!message || message.delta === undefined) return data;
+        const deltas = (window.__dioxus_deltas = window.__dioxus_deltas || {});
+        const slot = callbackId === undefined ? "" : callbackId;
+        deltas[slot] = message.delta === "patch" ? window.dxApplyPatch(deltas[slot], data) : data;
+        return deltas[slot];
+    };
+})();
+"#;
@@ -0,0 +1,54 @@
+#[cfg(any(
+    target_arch = "wasm32",
+    not(any(target_os = "android", feature = "desktop"))
+))]
+use crate::{eval_as, js_string};
+
+/// Opens `url` outside the app: in the default browser, or the app registered for its
+/// scheme, such as `mailto:` or `tel:`.
+///
+/// Web opens it in a new tab. Desktop hands it to the OS, the way links with
+/// `target="_blank"` are opened. Android starts an `ACTION_VIEW` intent with
+/// `RustBridge.openExternal({ url })`.
+pub async fn open_external(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::json!({ "url": url });
+        crate::android_bridge::call_native("openExternal", &args).map(|_| ())
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        webbrowser::open(url).map_err(|e| format!("Failed to open {}: {}", url, e))
+    }
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        not(any(target_os = "android", feature = "desktop"))
+    ))]
+    {
+        eval_as::<serde_json::Value>(&format!(
+            r#"
+            const url = {url};
+            const opened = window.open(url, "_blank");
+            if (opened) {{
+                opened.opener = null;
+            }} else {{
+                // Blocked as a popup: a link click may still be allowed
+                const link = document.createElement("a");
+                link.href = url;
+                link.target = "_blank";
+                link.rel = "noopener noreferrer";
+                link.click();
+            }}
+            return null;
+            "#,
+            url = js_string(url)
+        ))
+        .await
+        .map(|_| ())
+    }
+}
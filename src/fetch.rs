@@ -0,0 +1,214 @@
+use crate::{eval_as, Codec, FromJs, JsBridge};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// How many characters of an unexpected response body are kept in a [`FetchError`].
+const BODY_PREVIEW_LEN: usize = 200;
+
+/// An HTTP request performed with the page's `fetch`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FetchRequest {
+    pub url: String,
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+    /// The `credentials` mode passed to `fetch`, e.g. `"include"`.
+    pub credentials: Option<String>,
+}
+
+impl FetchRequest {
+    pub fn get(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            method: "GET".to_string(),
+            headers: HashMap::new(),
+            body: None,
+            credentials: None,
+        }
+    }
+
+    /// A `POST` request with `body` serialized as JSON.
+    pub fn post_json<B: Serialize>(url: &str, body: &B) -> Result<Self, FetchError> {
+        let body =
+            serde_json::to_string(body).map_err(|e| FetchError::Serialize(e.to_string()))?;
+        Ok(Self::get(url)
+            .method("POST")
+            .header("Content-Type", "application/json")
+            .body(body))
+    }
+
+    pub fn method(mut self, method: &str) -> Self {
+        self.method = method.to_string();
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn body(mut self, body: String) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    pub fn credentials(mut self, credentials: &str) -> Self {
+        self.credentials = Some(credentials.to_string());
+        self
+    }
+}
+
+/// A response whose body was parsed as JSON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchResponse<T> {
+    pub status: u16,
+    /// Header names are lower-case, as reported by `Headers.forEach`.
+    pub headers: HashMap<String, String>,
+    pub body: T,
+}
+
+/// Why [`fetch_json`] failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FetchError {
+    /// The request couldn't be serialized.
+    Serialize(String),
+    /// `fetch` rejected, e.g. because of a network or CORS failure.
+    Network(String),
+    /// The server answered with a non-2xx status.
+    Status { status: u16, body: String },
+    /// The response isn't JSON, e.g. an HTML error page.
+    NotJson {
+        status: u16,
+        content_type: Option<String>,
+        body: String,
+    },
+    /// The body is JSON but doesn't match the expected type.
+    Deserialize(String),
+    /// The bridge failed to run the request.
+    Bridge(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Serialize(e) => write!(f, "Serialization error: {}", e),
+            FetchError::Network(e) => write!(f, "Network error: {}", e),
+            FetchError::Status { status, body } => write!(f, "HTTP {}: {}", status, body),
+            FetchError::NotJson {
+                status,
+                content_type,
+                body,
+            } => write!(
+                f,
+                "Expected JSON but got {} (HTTP {}): {}",
+                content_type.as_deref().unwrap_or("no content type"),
+                status,
+                body
+            ),
+            FetchError::Deserialize(e) => write!(f, "Deserialization error: {}", e),
+            FetchError::Bridge(e) => write!(f, "Bridge error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// What the JS side reports back for a request.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum RawResponse {
+    Network {
+        message: String,
+    },
+    Response {
+        status: u16,
+        headers: HashMap<String, String>,
+        text: String,
+    },
+}
+
+fn preview(body: &str) -> String {
+    body.chars().take(BODY_PREVIEW_LEN).collect()
+}
+
+/// Performs `request` with `fetch` inside the page and parses the JSON body.
+///
+/// The request inherits the page's cookies, CORS context and service worker. Non-2xx
+/// statuses and non-JSON bodies are reported as [`FetchError`]s instead of parse failures.
+/// An empty body, e.g. of a `204 No Content`, is parsed as `null`, so requests that may
+/// not return one can ask for an `Option`, `()` or `serde_json::Value`.
+pub async fn fetch_json<T: FromJs>(
+    request: &FetchRequest,
+) -> Result<FetchResponse<T>, FetchError> {
+    let request_json =
+        serde_json::to_string(request).map_err(|e| FetchError::Serialize(e.to_string()))?;
+    let js_code = format!(
+        r#"
+        const request = {request_json};
+        const init = {{ method: request.method, headers: request.headers }};
+        if (request.body !== null) init.body = request.body;
+        if (request.credentials !== null) init.credentials = request.credentials;
+        let response;
+        try {{
+            response = await fetch(request.url, init);
+        }} catch (e) {{
+            return {{ kind: "network", message: String(e) }};
+        }}
+        const headers = {{}};
+        response.headers.forEach((value, name) => {{ headers[name] = value; }});
+        return {{ kind: "response", status: response.status, headers, text: await response.text() }};
+        "#
+    );
+
+    match eval_as::<RawResponse>(&js_code)
+        .await
+        .map_err(FetchError::Bridge)?
+    {
+        RawResponse::Network { message } => Err(FetchError::Network(message)),
+        RawResponse::Response {
+            status,
+            headers,
+            text,
+        } => {
+            if !(200..300).contains(&status) {
+                return Err(FetchError::Status {
+                    status,
+                    body: preview(&text),
+                });
+            }
+            let content_type = headers.get("content-type").cloned();
+            let is_json = content_type
+                .as_deref()
+                .is_some_and(|content_type| content_type.contains("json"));
+            let text = if text.trim().is_empty() {
+                "null".to_string()
+            } else if is_json {
+                text
+            } else {
+                return Err(FetchError::NotJson {
+                    status,
+                    content_type,
+                    body: preview(&text),
+                });
+            };
+            let body = serde_json::from_str::<T>(&text)
+                .map_err(|e| FetchError::Deserialize(e.to_string()))?;
+            Ok(FetchResponse {
+                status,
+                headers,
+                body,
+            })
+        }
+    }
+}
+
+impl<T: FromJs + Clone, C: Codec> JsBridge<T, C> {
+    /// Performs an HTTP request from the page context, see [`fetch_json`].
+    pub async fn fetch_json<R: FromJs>(
+        &self,
+        request: &FetchRequest,
+    ) -> Result<FetchResponse<R>, FetchError> {
+        fetch_json(request).await
+    }
+}
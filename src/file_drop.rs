@@ -0,0 +1,449 @@
+use crate::{eval_detached, js_string, use_js_listener, JsBridge};
+use base64::Engine;
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of raw bytes read from a dropped file per chunk message.
+const CHUNK_SIZE: usize = 256 * 1024;
+/// Most bytes reserved up front for a dropped file; larger files grow as chunks arrive.
+const MAX_RESERVE: u64 = 64 * CHUNK_SIZE as u64;
+
+/// Metadata of a file dropped onto the target element.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DroppedFileInfo {
+    pub name: String,
+    pub size: u64,
+    pub mime_type: String,
+    /// Milliseconds since the Unix epoch, as reported by `File.lastModified`.
+    pub last_modified: f64,
+}
+
+/// A typed event sent from the drop-zone listeners in JS.
+///
+/// Every drop gets a new `drop_id`, and file events name the drop and the file's `index`
+/// within it, so files still being read from an earlier drop can't mix into a later one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum FileDropEvent {
+    DragEnter,
+    DragLeave,
+    /// A drop started; `count` files will follow.
+    Drop {
+        drop_id: u64,
+        count: usize,
+    },
+    FileStart {
+        drop_id: u64,
+        index: usize,
+        info: DroppedFileInfo,
+    },
+    /// Base64-encoded bytes of a file starting at `offset`.
+    FileChunk {
+        drop_id: u64,
+        index: usize,
+        offset: u64,
+        data: String,
+    },
+    FileEnd {
+        drop_id: u64,
+        index: usize,
+    },
+    FileError {
+        drop_id: u64,
+        index: usize,
+        message: String,
+    },
+}
+
+/// A dropped file, assembled from its chunks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DroppedFile {
+    pub info: DroppedFileInfo,
+    pub bytes: Vec<u8>,
+    /// `true` once every chunk has been received.
+    pub complete: bool,
+    /// `true` if JS failed to read the file or a chunk couldn't be decoded. A failed file
+    /// never completes and ignores further chunks.
+    pub failed: bool,
+}
+
+/// State of a drop zone created by [`use_file_drop`].
+#[derive(Clone)]
+pub struct FileDrop {
+    /// Whether files are currently dragged over the target.
+    pub hovering: Signal<bool>,
+    /// Files of the most recent drop, in the order they were dropped.
+    pub files: Signal<Vec<DroppedFile>>,
+    /// Errors reading or decoding dropped files.
+    pub error: Signal<Option<String>>,
+    pub bridge: JsBridge<FileDropEvent>,
+}
+
+impl FileDrop {
+    pub fn is_hovering(&self) -> bool {
+        *self.hovering.read()
+    }
+    pub fn get_files(&self) -> Vec<DroppedFile> {
+        self.files.read().clone()
+    }
+    pub fn last_event(&self) -> Option<FileDropEvent> {
+        self.bridge.get_data()
+    }
+    pub fn get_error(&self) -> Option<String> {
+        self.error.read().clone().or_else(|| self.bridge.get_error())
+    }
+}
+
+/// Turns the element matching `target_selector` into a drop zone.
+///
+/// Dropped files are read in JS and streamed to Rust in chunks; `files` is updated as
+/// they arrive.
+pub fn use_file_drop(target_selector: &str) -> FileDrop {
+    let mut hovering = use_signal(|| false);
+    let mut files = use_signal(Vec::<DroppedFile>::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut assembly = use_signal(Assembly::default);
+
+    let bridge = use_js_listener::<FileDropEvent>(move |event| match event {
+        FileDropEvent::DragEnter => hovering.set(true),
+        FileDropEvent::DragLeave => hovering.set(false),
+        event => {
+            if matches!(event, FileDropEvent::Drop { .. }) {
+                hovering.set(false);
+                error.set(None);
+            }
+            let failure = files.with_mut(|files| assembly.write().apply(files, event));
+            if let Some(failure) = failure {
+                error.set(Some(failure));
+            }
+        }
+    });
+
+    let selector = target_selector.to_string();
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = install_script(
+            &bridge_for_effect.callback_id(),
+            &selector,
+            &bridge_for_effect.js_callback(),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = bridge.eval(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || eval_detached(&uninstall_script(&callback_id)));
+
+    FileDrop {
+        hovering,
+        files,
+        error,
+        bridge,
+    }
+}
+
+/// Positions in `files` of the files of the current drop, by drop ID and index.
+#[derive(Default)]
+struct Assembly {
+    current: Option<u64>,
+    slots: HashMap<(u64, usize), usize>,
+}
+
+impl Assembly {
+    /// Applies a file event to the files of the current drop, returning an error to report.
+    /// Events of earlier drops are ignored.
+    fn apply(&mut self, files: &mut Vec<DroppedFile>, event: FileDropEvent) -> Option<String> {
+        match event {
+            FileDropEvent::DragEnter | FileDropEvent::DragLeave => {}
+            FileDropEvent::Drop { drop_id, count } => {
+                self.current = Some(drop_id);
+                self.slots.clear();
+                files.clear();
+                files.reserve(count.min(64));
+            }
+            FileDropEvent::FileStart {
+                drop_id,
+                index,
+                info,
+            } => {
+                if self.current != Some(drop_id) || self.slots.contains_key(&(drop_id, index)) {
+                    return None;
+                }
+                files.push(DroppedFile {
+                    // The size comes from JS; don't let a bogus one reserve unbounded memory
+                    bytes: Vec::with_capacity(info.size.min(MAX_RESERVE) as usize),
+                    info,
+                    complete: false,
+                    failed: false,
+                });
+                self.slots.insert((drop_id, index), files.len() - 1);
+            }
+            FileDropEvent::FileChunk {
+                drop_id,
+                index,
+                data,
+                ..
+            } => {
+                let file = self.file(files, drop_id, index)?;
+                match base64::engine::general_purpose::STANDARD.decode(data) {
+                    Ok(bytes) => file.bytes.extend_from_slice(&bytes),
+                    Err(e) => {
+                        file.failed = true;
+                        return Some(format!("Invalid chunk of {}: {e}", file.info.name));
+                    }
+                }
+            }
+            FileDropEvent::FileEnd { drop_id, index } => {
+                if let Some(file) = self.file(files, drop_id, index) {
+                    file.complete = true;
+                }
+            }
+            FileDropEvent::FileError {
+                drop_id,
+                index,
+                message,
+            } => {
+                let file = self.file(files, drop_id, index)?;
+                file.failed = true;
+                return Some(format!("Failed to read {}: {message}", file.info.name));
+            }
+        }
+        None
+    }
+
+    /// The file still being received at `index` of drop `drop_id`.
+    fn file<'a>(
+        &self,
+        files: &'a mut [DroppedFile],
+        drop_id: u64,
+        index: usize,
+    ) -> Option<&'a mut DroppedFile> {
+        let position = *self.slots.get(&(drop_id, index))?;
+        files.get_mut(position).filter(|file| !file.failed)
+    }
+}
+
+/// Adds the drop-zone listeners for the bridge `id`, keeping them in
+/// `window.__dioxus_file_drop[id]` so [`uninstall_script`] can remove them.
+fn install_script(id: &str, selector: &str, callback: &str) -> String {
+    let id = js_string(id);
+    let selector = js_string(selector);
+    format!(
+        r#"
+        const target = document.querySelector({selector});
+        if (!target) {{
+            throw new Error("use_file_drop: no element matches " + {selector});
+        }}
+        window.__dioxus_file_drop = window.__dioxus_file_drop || {{}};
+        if (!window.__dioxus_file_drop[{id}]) {{
+            const zone = {{ target, closed: false }};
+            let drops = 0;
+            const emit = (event) => {{
+                if (!zone.closed && {callback}) {{
+                    {callback}(event);
+                }}
+            }};
+            zone.listeners = {{
+                dragover: (e) => e.preventDefault(),
+                dragenter: (e) => {{
+                    e.preventDefault();
+                    emit({{ event: "drag_enter" }});
+                }},
+                dragleave: () => emit({{ event: "drag_leave" }}),
+                drop: async (e) => {{
+                    e.preventDefault();
+                    const files = Array.from(e.dataTransfer ? e.dataTransfer.files : []);
+                    const drop_id = ++drops;
+                    emit({{ event: "drop", drop_id, count: files.length }});
+                    for (let index = 0; index < files.length && !zone.closed; index++) {{
+                        const file = files[index];
+                        emit({{
+                            event: "file_start",
+                            drop_id,
+                            index,
+                            info: {{
+                                name: file.name,
+                                size: file.size,
+                                mime_type: file.type,
+                                last_modified: file.lastModified,
+                            }},
+                        }});
+                        try {{
+                            for (let offset = 0; offset < file.size && !zone.closed; offset += {CHUNK_SIZE}) {{
+                                const bytes = new Uint8Array(
+                                    await file.slice(offset, offset + {CHUNK_SIZE}).arrayBuffer()
+                                );
+                                let binary = "";
+                                for (let i = 0; i < bytes.length; i += 0x8000) {{
+                                    binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+                                }}
+                                emit({{ event: "file_chunk", drop_id, index, offset, data: btoa(binary) }});
+                            }}
+                            emit({{ event: "file_end", drop_id, index }});
+                        }} catch (err) {{
+                            emit({{ event: "file_error", drop_id, index, message: String(err) }});
+                        }}
+                    }}
+                }},
+            }};
+            for (const [name, listener] of Object.entries(zone.listeners)) {{
+                target.addEventListener(name, listener);
+            }}
+            window.__dioxus_file_drop[{id}] = zone;
+        }}
+        "#
+    )
+}
+
+/// Removes the listeners added by [`install_script`] and stops reading files still being
+/// streamed.
+fn uninstall_script(id: &str) -> String {
+    format!(
+        r#"
+        const zone = window.__dioxus_file_drop && window.__dioxus_file_drop[{id}];
+        if (zone) {{
+            zone.closed = true;
+            for (const [name, listener] of Object.entries(zone.listeners)) {{
+                zone.target.removeEventListener(name, listener);
+            }}
+            delete window.__dioxus_file_drop[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(drop_id: u64, index: usize, name: &str) -> FileDropEvent {
+        FileDropEvent::FileStart {
+            drop_id,
+            index,
+            info: DroppedFileInfo {
+                name: name.to_string(),
+                size: 6,
+                mime_type: "text/plain".to_string(),
+                last_modified: 0.0,
+            },
+        }
+    }
+
+    fn chunk(drop_id: u64, index: usize, offset: u64, data: &str) -> FileDropEvent {
+        FileDropEvent::FileChunk {
+            drop_id,
+            index,
+            offset,
+            data: data.to_string(),
+        }
+    }
+
+    /// Applies `events` to a fresh drop zone, returning its files and the errors reported.
+    fn assemble(events: Vec<FileDropEvent>) -> (Vec<DroppedFile>, Vec<String>) {
+        let mut assembly = Assembly::default();
+        let mut files = Vec::new();
+        let errors = events
+            .into_iter()
+            .filter_map(|event| assembly.apply(&mut files, event))
+            .collect();
+        (files, errors)
+    }
+
+    #[test]
+    fn chunks_are_joined_per_file() {
+        let (files, errors) = assemble(vec![
+            FileDropEvent::Drop {
+                drop_id: 1,
+                count: 2,
+            },
+            start(1, 0, "a.txt"),
+            start(1, 1, "b.txt"),
+            chunk(1, 1, 0, "Ymls"),
+            chunk(1, 0, 0, "Zm9v"),
+            chunk(1, 0, 3, "YmFy"),
+            FileDropEvent::FileEnd {
+                drop_id: 1,
+                index: 0,
+            },
+        ]);
+        assert!(errors.is_empty());
+        assert_eq!(files[0].bytes, b"foobar");
+        assert!(files[0].complete);
+        assert_eq!(files[1].bytes, b"bil");
+        assert!(!files[1].complete);
+    }
+
+    #[test]
+    fn events_of_earlier_drops_are_ignored() {
+        let (files, _) = assemble(vec![
+            FileDropEvent::Drop {
+                drop_id: 1,
+                count: 1,
+            },
+            start(1, 0, "old.txt"),
+            FileDropEvent::Drop {
+                drop_id: 2,
+                count: 1,
+            },
+            start(2, 0, "new.txt"),
+            chunk(1, 0, 0, "Zm9v"),
+            FileDropEvent::FileEnd {
+                drop_id: 1,
+                index: 0,
+            },
+            start(1, 1, "late.txt"),
+        ]);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].info.name, "new.txt");
+        assert!(files[0].bytes.is_empty());
+        assert!(!files[0].complete);
+    }
+
+    #[test]
+    fn files_with_bad_chunks_fail() {
+        let (files, errors) = assemble(vec![
+            FileDropEvent::Drop {
+                drop_id: 1,
+                count: 1,
+            },
+            start(1, 0, "a.txt"),
+            chunk(1, 0, 0, "Zm9v"),
+            chunk(1, 0, 3, "not base64!"),
+            chunk(1, 0, 6, "YmFy"),
+            FileDropEvent::FileEnd {
+                drop_id: 1,
+                index: 0,
+            },
+        ]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("a.txt"), "{}", errors[0]);
+        assert!(files[0].failed);
+        assert!(!files[0].complete);
+        assert_eq!(files[0].bytes, b"foo");
+    }
+
+    #[test]
+    fn read_errors_fail_the_file() {
+        let (files, errors) = assemble(vec![
+            FileDropEvent::Drop {
+                drop_id: 1,
+                count: 1,
+            },
+            start(1, 0, "a.txt"),
+            FileDropEvent::FileError {
+                drop_id: 1,
+                index: 0,
+                message: "NotReadableError".to_string(),
+            },
+        ]);
+        assert_eq!(errors, ["Failed to read a.txt: NotReadableError"]);
+        assert!(files[0].failed);
+    }
+}
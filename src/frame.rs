@@ -0,0 +1,163 @@
+use crate::origin::OriginPolicy;
+use crate::{js_string, use_js_bridge_inner, BridgeOptions, FromJs, JsBridge};
+use std::fmt::Debug;
+
+/// The iframes a bridge from [`use_js_bridge_in_frame`] talks to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Frame {
+    /// Iframes matching a CSS selector.
+    Selector(String),
+    /// Iframes whose `src` has this origin.
+    Origin(String),
+}
+
+impl Frame {
+    fn parse(target: &str) -> Self {
+        if target.starts_with("https://") || target.starts_with("http://") {
+            Frame::Origin(target.trim_end_matches('/').to_string())
+        } else {
+            Frame::Selector(target.to_string())
+        }
+    }
+
+    /// Senders are matched to the frames in JS; origin targets also check `event.origin`.
+    fn policy(&self) -> OriginPolicy {
+        match self {
+            Frame::Selector(_) => OriginPolicy::new(|_| true),
+            Frame::Origin(origin) => OriginPolicy::allow(&[origin]),
+        }
+    }
+
+    /// A JS expression evaluating to the array of matching iframe elements.
+    pub(crate) fn elements(&self) -> String {
+        match self {
+            Frame::Selector(selector) => format!(
+                "Array.from(document.querySelectorAll({}))",
+                js_string(selector)
+            ),
+            Frame::Origin(origin) => format!(
+                "Array.from(document.querySelectorAll(\"iframe\")).filter((f) => {{ try {{ return new URL(f.src, location.href).origin === {}; }} catch (e) {{ return false; }} }})",
+                js_string(origin)
+            ),
+        }
+    }
+
+    /// Installs `window.dxParent` in same-origin frames and tells every frame the
+    /// callback ID, now and whenever it reloads.
+    pub(crate) fn attach_script(&self, callback_id: &str) -> String {
+        format!(
+            r#"
+            const id = {id};
+            const install = {install};
+            const attach = (frame) => {{
+                if (!window.__dioxus_origin || !window.__dioxus_origin[id] || !frame.contentWindow) return;
+                try {{ frame.contentWindow.eval(install); }} catch (e) {{}}
+                frame.contentWindow.postMessage({{ dxBridgeReady: id }}, {origin_of}(frame));
+            }};
+            for (const frame of {elements}) {{
+                attach(frame);
+                frame.addEventListener("load", () => attach(frame));
+            }}
+            "#,
+            id = js_string(callback_id),
+            install = js_string(&install_script(callback_id)),
+            origin_of = ORIGIN_OF,
+            elements = self.elements(),
+        )
+    }
+
+    /// Posts a message to every matching frame, restricted to the origin of its `src`.
+    pub(crate) fn send_script(&self, callback_id: &str, json_data: &str) -> String {
+        format!(
+            r#"
+            const data = {data};
+            for (const frame of {elements}) {{
+                if (frame.contentWindow) {{
+                    frame.contentWindow.postMessage({{ dxBridge: {id}, data }}, {origin_of}(frame));
+                }}
+            }}
+            "#,
+            data = json_data,
+            elements = self.elements(),
+            id = js_string(callback_id),
+            origin_of = ORIGIN_OF,
+        )
+    }
+}
+
+/// The origin to post to a frame with, or `"*"` for frames without one like `srcdoc`.
+const ORIGIN_OF: &str = r#"((frame) => { try { const origin = new URL(frame.src, location.href).origin; return origin === "null" ? "*" : origin; } catch (e) { return "*"; } })"#;
+
+/// Runs inside same-origin frames. Cross-origin widgets implement the same protocol
+/// themselves: wait for `{ dxBridgeReady: id }`, then post `{ dxBridge: id, data }`.
+fn install_script(callback_id: &str) -> String {
+    format!(
+        r#"
+        window.dxParent = {{
+            id: {id},
+            send: (data) => window.parent.postMessage({{ dxBridge: {id}, data }}, "*"),
+            listen: (handler) => window.addEventListener("message", (e) => {{
+                if (e.source === window.parent && e.data && e.data.dxBridge === {id}) handler(e.data.data);
+            }}),
+        }};
+        "#,
+        id = js_string(callback_id)
+    )
+}
+
+/// A bridge to the iframes matching `frame`: a CSS selector, or an origin such as
+/// `"https://widgets.example.com"` to match iframes by their `src`.
+///
+/// Messages from JS are only accepted from those frames (and the bridge's own document),
+/// and [`JsBridge::send_to_js`] posts to them instead of the page. Same-origin frames get
+/// `window.dxParent` with `send(data)` and `listen(handler)`; cross-origin frames are sent
+/// `{ dxBridgeReady: callbackId }` on load and post `{ dxBridge: callbackId, data }` back.
+pub fn use_js_bridge_in_frame<T>(frame: &str) -> JsBridge<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    let frame = Frame::parse(frame);
+    let options = BridgeOptions {
+        origin_policy: Some(frame.policy()),
+        ..Default::default()
+    };
+    use_js_bridge_inner(None, options, Some(frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_target_an_origin() {
+        assert_eq!(
+            Frame::parse("https://widgets.example.com/"),
+            Frame::Origin("https://widgets.example.com".to_string())
+        );
+        assert_eq!(
+            Frame::parse("http://localhost:8080"),
+            Frame::Origin("http://localhost:8080".to_string())
+        );
+    }
+
+    #[test]
+    fn anything_else_is_a_selector() {
+        for target in ["#widget", "iframe.chart", "iframe[src^=\"https://\"]"] {
+            assert_eq!(Frame::parse(target), Frame::Selector(target.to_string()));
+        }
+    }
+
+    #[test]
+    fn frames_are_found_by_selector_or_origin() {
+        let elements = Frame::parse("#chart").elements();
+        assert_eq!(
+            elements,
+            r##"Array.from(document.querySelectorAll("#chart"))"##
+        );
+        let elements = Frame::parse("https://a.example/").elements();
+        assert!(
+            elements.contains(r#".origin === "https://a.example""#),
+            "{elements}"
+        );
+    }
+}
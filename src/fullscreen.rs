@@ -0,0 +1,229 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Dispatched on `window` when Android immersive mode is toggled, as the WebView doesn't
+/// fire `fullscreenchange` for it.
+#[cfg(target_os = "android")]
+const IMMERSIVE_EVENT: &str = "dioxus-immersive-change";
+
+/// A fullscreen or pointer-lock change.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScreenModeEvent {
+    FullscreenChange { active: bool },
+    FullscreenError,
+    PointerLockChange { locked: bool },
+    PointerLockError,
+}
+
+/// Makes the element matching `selector` fullscreen; `None` means the whole document.
+///
+/// On Android this switches the activity to immersive mode with
+/// `RustBridge.setImmersiveMode({ enabled: true })`, and [`exit_fullscreen`] passes
+/// `false`.
+pub async fn request_fullscreen(selector: Option<&str>) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let _ = selector;
+        set_immersive_mode(true).await
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let target = match selector {
+            Some(selector) => format!("document.querySelector({})", js_string(selector)),
+            None => "document.documentElement".to_string(),
+        };
+        let js_code = format!(
+            r#"
+            const target = {target};
+            if (!target) {{
+                throw new Error("request_fullscreen: element not found");
+            }}
+            const request = target.requestFullscreen || target.webkitRequestFullscreen;
+            if (!request) {{
+                throw new Error("Fullscreen is not supported");
+            }}
+            await request.call(target);
+            "#
+        );
+        eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+    }
+}
+
+pub async fn exit_fullscreen() -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        set_immersive_mode(false).await
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        eval_as::<serde_json::Value>(
+            r#"
+            if (document.fullscreenElement) {
+                await document.exitFullscreen();
+            } else if (document.webkitFullscreenElement) {
+                document.webkitExitFullscreen();
+            }
+            "#,
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(target_os = "android")]
+async fn set_immersive_mode(enabled: bool) -> Result<(), String> {
+    let args = serde_json::json!({ "enabled": enabled });
+    crate::android_bridge::call_native("setImmersiveMode", &args)?;
+    eval_as::<serde_json::Value>(&format!(
+        "window.dispatchEvent(new CustomEvent({}, {{ detail: {} }}));",
+        js_string(IMMERSIVE_EVENT),
+        enabled
+    ))
+    .await
+    .map(|_| ())
+}
+
+/// Locks the pointer to the element matching `selector`, hiding the cursor and reporting
+/// relative mouse movement. Must be called from a user gesture.
+pub async fn request_pointer_lock(selector: &str) -> Result<(), String> {
+    let js_code = format!(
+        r#"
+        const target = document.querySelector({selector});
+        if (!target) {{
+            throw new Error("request_pointer_lock: no element matches " + {selector});
+        }}
+        if (!target.requestPointerLock) {{
+            throw new Error("Pointer lock is not supported");
+        }}
+        await target.requestPointerLock();
+        "#,
+        selector = js_string(selector)
+    );
+    eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+}
+
+pub async fn exit_pointer_lock() -> Result<(), String> {
+    eval_as::<serde_json::Value>("if (document.exitPointerLock) document.exitPointerLock();")
+        .await
+        .map(|_| ())
+}
+
+/// Fullscreen and pointer-lock state, created by [`use_screen_mode`].
+#[derive(Clone)]
+pub struct ScreenMode {
+    pub fullscreen: Signal<bool>,
+    pub pointer_locked: Signal<bool>,
+    pub bridge: JsBridge<ScreenModeEvent>,
+}
+
+impl ScreenMode {
+    pub fn is_fullscreen(&self) -> bool {
+        *self.fullscreen.read()
+    }
+    pub fn is_pointer_locked(&self) -> bool {
+        *self.pointer_locked.read()
+    }
+
+    /// Returns a stream of every change received after this call.
+    pub fn events(&self) -> UnboundedReceiver<ScreenModeEvent> {
+        self.bridge.stream()
+    }
+}
+
+fn unwatch_script(id: &str) -> String {
+    format!(
+        r#"
+        const watch = window.__dioxus_screen_mode && window.__dioxus_screen_mode[{id}];
+        if (watch) {{
+            for (const [target, name, handler] of watch) {{
+                target.removeEventListener(name, handler);
+            }}
+            delete window.__dioxus_screen_mode[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Tracks fullscreen and pointer-lock changes until the component unmounts.
+pub fn use_screen_mode() -> ScreenMode {
+    let mut fullscreen = use_signal(|| false);
+    let mut pointer_locked = use_signal(|| false);
+    let bridge = use_js_listener::<ScreenModeEvent>(move |event| match event {
+        ScreenModeEvent::FullscreenChange { active } => fullscreen.set(active),
+        ScreenModeEvent::PointerLockChange { locked } => pointer_locked.set(locked),
+        _ => {}
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        #[cfg(target_os = "android")]
+        let immersive_event = js_string(IMMERSIVE_EVENT);
+        #[cfg(not(target_os = "android"))]
+        let immersive_event = "null";
+
+        let js_code = format!(
+            r#"
+            window.__dioxus_screen_mode = window.__dioxus_screen_mode || {{}};
+            const id = {id};
+            if (!window.__dioxus_screen_mode[id]) {{
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const fullscreenChange = () => emit({{
+                    event: "fullscreen_change",
+                    active: !!(document.fullscreenElement || document.webkitFullscreenElement),
+                }});
+                const watch = [
+                    [document, "fullscreenchange", fullscreenChange],
+                    [document, "webkitfullscreenchange", fullscreenChange],
+                    [document, "fullscreenerror", () => emit({{ event: "fullscreen_error" }})],
+                    [document, "pointerlockchange", () => emit({{
+                        event: "pointer_lock_change",
+                        locked: document.pointerLockElement !== null,
+                    }})],
+                    [document, "pointerlockerror", () => emit({{ event: "pointer_lock_error" }})],
+                ];
+                const immersiveEvent = {immersive_event};
+                if (immersiveEvent) {{
+                    watch.push([window, immersiveEvent, (e) => emit({{
+                        event: "fullscreen_change",
+                        active: e.detail,
+                    }})]);
+                }}
+                for (const [target, name, handler] of watch) {{
+                    target.addEventListener(name, handler);
+                }}
+                window.__dioxus_screen_mode[id] = watch;
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&unwatch_script(&callback_id));
+    });
+
+    ScreenMode {
+        fullscreen,
+        pointer_locked,
+        bridge,
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{eval_as, js_string, FromJs};
+use futures_util::Stream;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// A JS function bound once by path, e.g. `window.game.spawn`, and called with typed
+/// arguments; see [`JsBridge::bind_function`](crate::JsBridge::bind_function).
+///
+/// Arguments that serialize to a JSON array, such as tuples, are spread into the call, so
+/// `(x, y)` calls `f(x, y)`. Wrap an array in a 1-tuple to pass it as one argument.
+pub struct JsFunction<Args, Ret> {
+    receiver: Option<String>,
+    name: String,
+    types: PhantomData<fn(Args) -> Ret>,
+}
+
+impl<Args, Ret> Clone for JsFunction<Args, Ret> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+            name: self.name.clone(),
+            types: PhantomData,
+        }
+    }
+}
+
+impl<Args: Serialize, Ret: FromJs> JsFunction<Args, Ret> {
+    /// Binds the function at `path`, failing if it isn't a function right now.
+    pub async fn bind(path: &str) -> Result<Self, String> {
+        let exists = eval_as::<bool>(&format!(
+            "try {{ return typeof ({}) === \"function\"; }} catch (e) {{ return false; }}",
+            path
+        ))
+        .await?;
+        if !exists {
+            return Err(format!("{} is not a function", path));
+        }
+        // Calling through the receiver keeps `this` bound for methods
+        let (receiver, name) = match path.rsplit_once('.') {
+            Some((receiver, name)) => (Some(receiver.to_string()), name.to_string()),
+            None => (None, path.to_string()),
+        };
+        Ok(Self {
+            receiver,
+            name,
+            types: PhantomData,
+        })
+    }
+
+    pub async fn call(&self, args: &Args) -> Result<Ret, String> {
+        eval_as(&self.script(args, "return result;")?).await
+    }
+
+    /// Calls a function that returns an async iterator, a `ReadableStream` or an iterable,
+    /// and receives its items as they are pulled. Each item is requested from JS only when
+    /// the stream is polled, so a slow consumer holds back the producer instead of
+    /// buffering. Dropping the stream cancels the iterator.
+    pub async fn call_stream(
+        &self,
+        args: &Args,
+    ) -> Result<impl Stream<Item = Result<Ret, String>>, String> {
+        crate::bootstrap::inject_once("streams", crate::streams::JS_STREAMS);
+        let id =
+            eval_as::<u64>(&self.script(args, "return window.dxStreams.start(result);")?).await?;
+        Ok(crate::streams::pull(id))
+    }
+
+    /// A script calling the function with `args` into `result`, followed by `then`.
+    fn script(&self, args: &Args, then: &str) -> Result<String, String> {
+        let args = serde_json::to_value(args).map_err(|e| format!("Serialization error: {}", e))?;
+        let args = match args {
+            serde_json::Value::Array(_) => args,
+            single => serde_json::Value::Array(vec![single]),
+        };
+        let call = match &self.receiver {
+            Some(receiver) => format!(
+                "const receiver = {}; const result = await receiver[{}](...args);",
+                receiver,
+                js_string(&self.name)
+            ),
+            None => format!("const result = await {}(...args);", self.name),
+        };
+        Ok(format!("const args = {}; {} {}", args, call, then))
+    }
+
+    /// The path the function was bound at.
+    pub fn path(&self) -> String {
+        match &self.receiver {
+            Some(receiver) => format!("{}.{}", receiver, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+/// Entry points for the targets in `fuzz/` and the property tests, over the code that
+/// parses data from JS or embeds data in scripts.
+pub mod inbound {
+    use crate::envelope::Envelope;
+    use serde_json::Value;
+
+    /// JSON text as desktop and Android receive it, through simd-json when enabled.
+    pub fn parse_json(text: String) -> Result<Value, String> {
+        crate::core::parse_json(text)
+    }
+
+    /// An envelope as a bridge with envelope options unwraps it, decompressing its data.
+    pub fn envelope(wire: Value) -> Result<Value, String> {
+        Envelope::parse(wire)?.into_data(None, None)
+    }
+
+    /// A message tagged with its sender by the origin script.
+    pub fn origin(wire: Value) -> Result<Value, String> {
+        crate::origin::unwrap(wire, "fuzz").map(|(data, _)| data)
+    }
+
+    /// A protected envelope, checked against a fresh key of the given kind.
+    #[cfg(feature = "security")]
+    pub fn unseal(mode: crate::Security, wire: Value) -> Result<Value, String> {
+        let envelope = Envelope::parse(wire)?;
+        let text = crate::security::Seal::generate(mode)?.unseal(&envelope)?;
+        envelope.into_data(Some(text), None)
+    }
+
+    /// JSON text sealed with a fresh key as message `sealed_as` (version and ID), then
+    /// checked as if it arrived stamped `received_as`.
+    #[cfg(feature = "security")]
+    pub fn reseal(
+        mode: crate::Security,
+        json: &str,
+        sealed_as: (Option<u32>, Option<u64>),
+        received_as: (Option<u32>, Option<u64>),
+    ) -> Result<Value, String> {
+        let seal = crate::security::Seal::generate(mode)?;
+        let (field, value, data) = seal.seal(json, sealed_as.0, sealed_as.1)?;
+        let fields = vec![(field, crate::js_string(&value))];
+        let wire = crate::envelope::wrap(
+            received_as.0,
+            received_as.1,
+            &fields,
+            &crate::js_string(&data),
+        );
+        let envelope = Envelope::parse(Value::String(wire))?;
+        let text = seal.unseal(&envelope)?;
+        envelope.into_data(Some(text), None)
+    }
+
+    /// Seals messages with a fresh key and receives them as a protected bridge does,
+    /// checking their IDs against the ones already received.
+    #[cfg(feature = "security")]
+    pub struct Sealed {
+        seal: crate::security::Seal,
+        last: Option<u64>,
+    }
+
+    #[cfg(feature = "security")]
+    impl Sealed {
+        pub fn new(mode: crate::Security) -> Result<Self, String> {
+            Ok(Self {
+                seal: crate::security::Seal::generate(mode)?,
+                last: None,
+            })
+        }
+
+        /// The envelope JS would send for `json` as message `id`.
+        pub fn seal(&self, json: &str, id: Option<u64>) -> Result<Value, String> {
+            let (field, value, data) = self.seal.seal(json, None, id)?;
+            let fields = vec![(field, crate::js_string(&value))];
+            let wire = crate::envelope::wrap(None, id, &fields, &crate::js_string(&data));
+            Ok(Value::String(wire))
+        }
+
+        /// Verifies an envelope and checks its ID, returning its data.
+        pub fn receive(&mut self, wire: Value) -> Result<Value, String> {
+            let envelope = Envelope::parse(wire)?;
+            let text = self.seal.unseal(&envelope)?;
+            crate::envelope::admit_id(&mut self.last, envelope.id, true)?;
+            envelope.into_data(Some(text), None)
+        }
+    }
+
+    /// A Rust string as evals embed it in a script.
+    pub fn js_string(value: &str) -> String {
+        crate::js_string(value)
+    }
+
+    /// Base64 text, as JS sends bytes.
+    pub fn base64(text: &str) -> Result<Vec<u8>, String> {
+        crate::binary::decode(text)
+    }
+}
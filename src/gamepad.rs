@@ -0,0 +1,209 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GamepadButton {
+    pub pressed: bool,
+    pub touched: bool,
+    /// From 0.0 to 1.0; analog triggers report values in between.
+    pub value: f64,
+}
+
+/// The state of one controller, in the shape of the Web `Gamepad`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GamepadState {
+    /// The slot of the controller, stable while it stays connected.
+    pub index: u32,
+    /// The browser's description of the controller, e.g. its vendor and product.
+    pub id: String,
+    /// `"standard"` when buttons and axes follow the W3C standard gamepad layout.
+    pub mapping: String,
+    pub buttons: Vec<GamepadButton>,
+    /// From -1.0 to 1.0; sticks are pairs of axes, x then y.
+    pub axes: Vec<f64>,
+    /// Milliseconds, from the page's clock, of the last change.
+    pub timestamp: f64,
+}
+
+impl GamepadState {
+    pub fn is_standard(&self) -> bool {
+        self.mapping == "standard"
+    }
+
+    /// Whether the button at `index` is held; `false` for buttons the controller lacks.
+    pub fn pressed(&self, index: usize) -> bool {
+        self.buttons.get(index).is_some_and(|button| button.pressed)
+    }
+
+    /// The axis at `index`, or 0.0 when the controller lacks it.
+    pub fn axis(&self, index: usize) -> f64 {
+        self.axes.get(index).copied().unwrap_or(0.0)
+    }
+}
+
+/// A typed event sent from the gamepad poller in JS.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GamepadEvent {
+    Connected {
+        index: u32,
+        id: String,
+    },
+    Disconnected {
+        index: u32,
+        id: String,
+    },
+    /// Every connected controller, sent when any of them changed since the last poll.
+    Snapshot {
+        pads: Vec<GamepadState>,
+    },
+}
+
+/// How often controllers are polled; the Gamepad API has no events for input.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GamepadOptions {
+    /// Milliseconds between polls. Polls once per animation frame when `None`.
+    pub poll_interval_ms: Option<u32>,
+    /// Also sends snapshots when nothing changed, e.g. for a fixed-rate game loop.
+    pub every_poll: bool,
+}
+
+/// Controllers connected to the page, created by [`use_gamepads`].
+#[derive(Clone)]
+pub struct Gamepads {
+    /// The latest state of each connected controller, ordered by index.
+    pub pads: Signal<Vec<GamepadState>>,
+    pub bridge: JsBridge<GamepadEvent>,
+}
+
+impl Gamepads {
+    pub fn get_pads(&self) -> Vec<GamepadState> {
+        self.pads.read().clone()
+    }
+
+    pub fn pad(&self, index: u32) -> Option<GamepadState> {
+        self.pads
+            .read()
+            .iter()
+            .find(|pad| pad.index == index)
+            .cloned()
+    }
+
+    /// Returns a stream of every connection event and snapshot received after this call,
+    /// for a game loop that consumes input outside rendering.
+    pub fn events(&self) -> UnboundedReceiver<GamepadEvent> {
+        self.bridge.stream()
+    }
+}
+
+/// Polls the Gamepad API once per animation frame until the component unmounts.
+///
+/// Browsers only expose controllers after a button was pressed while the page was
+/// focused, so `pads` may stay empty until then.
+pub fn use_gamepads() -> Gamepads {
+    use_gamepads_with_options(GamepadOptions::default())
+}
+
+pub fn use_gamepads_with_options(options: GamepadOptions) -> Gamepads {
+    let mut pads = use_signal(Vec::<GamepadState>::new);
+    let bridge = use_js_listener::<GamepadEvent>(move |event| match event {
+        GamepadEvent::Snapshot { pads: snapshot } => pads.set(snapshot),
+        GamepadEvent::Disconnected { index, .. } => {
+            pads.with_mut(|pads| pads.retain(|pad| pad.index != index))
+        }
+        GamepadEvent::Connected { .. } => {}
+    });
+
+    let options = use_hook(move || options);
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let mut bridge = bridge_for_effect.clone();
+        let js_code = format!(
+            r#"
+            window.__dioxus_gamepads = window.__dioxus_gamepads || {{}};
+            const id = {id};
+            if (!window.__dioxus_gamepads[id]) {{
+                const options = {options};
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const last = {{}};
+                const poll = () => {{
+                    const pads = Array.from(navigator.getGamepads ? navigator.getGamepads() : [])
+                        .filter((pad) => pad && pad.connected);
+                    const changed = pads.some((pad) => last[pad.index] !== pad.timestamp)
+                        || Object.keys(last).length !== pads.length;
+                    if (!changed && !options.every_poll) return;
+                    for (const key of Object.keys(last)) delete last[key];
+                    pads.forEach((pad) => (last[pad.index] = pad.timestamp));
+                    emit({{
+                        event: "snapshot",
+                        pads: pads.map((pad) => ({{
+                            index: pad.index,
+                            id: pad.id,
+                            mapping: pad.mapping,
+                            buttons: pad.buttons.map((b) => ({{
+                                pressed: b.pressed,
+                                touched: !!b.touched,
+                                value: b.value,
+                            }})),
+                            axes: Array.from(pad.axes),
+                            timestamp: pad.timestamp,
+                        }})),
+                    }});
+                }};
+                const state = {{}};
+                if (options.poll_interval_ms === null) {{
+                    const frame = () => {{
+                        poll();
+                        state.frame = requestAnimationFrame(frame);
+                    }};
+                    state.frame = requestAnimationFrame(frame);
+                }} else {{
+                    state.interval = setInterval(poll, options.poll_interval_ms);
+                }}
+                state.onConnected = (e) => emit({{ event: "connected", index: e.gamepad.index, id: e.gamepad.id }});
+                state.onDisconnected = (e) => {{
+                    delete last[e.gamepad.index];
+                    emit({{ event: "disconnected", index: e.gamepad.index, id: e.gamepad.id }});
+                }};
+                window.addEventListener("gamepadconnected", state.onConnected);
+                window.addEventListener("gamepaddisconnected", state.onDisconnected);
+                window.__dioxus_gamepads[id] = state;
+            }}
+            "#,
+            id = js_string(&bridge.callback_id()),
+            options = serde_json::to_string(&options).unwrap_or_else(|_| "{}".to_string()),
+            callback = bridge.js_callback(),
+        );
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const state = window.__dioxus_gamepads && window.__dioxus_gamepads[{id}];
+            if (state) {{
+                cancelAnimationFrame(state.frame);
+                clearInterval(state.interval);
+                window.removeEventListener("gamepadconnected", state.onConnected);
+                window.removeEventListener("gamepaddisconnected", state.onDisconnected);
+                delete window.__dioxus_gamepads[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    Gamepads { pads, bridge }
+}
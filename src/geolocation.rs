@@ -0,0 +1,283 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, eval_detached, js_string};
+use crate::{use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A position fix, in the shape of the Web `GeolocationPosition`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeoPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Accuracy of latitude and longitude, in meters.
+    pub accuracy: f64,
+    pub altitude: Option<f64>,
+    pub altitude_accuracy: Option<f64>,
+    /// Direction of travel in degrees clockwise from true north.
+    pub heading: Option<f64>,
+    /// Speed in meters per second.
+    pub speed: Option<f64>,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoOptions {
+    pub high_accuracy: bool,
+    pub timeout_ms: Option<u32>,
+    pub maximum_age_ms: Option<u32>,
+}
+
+/// Why a position couldn't be obtained.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeoError {
+    /// The user or platform denied location access.
+    PermissionDenied,
+    PositionUnavailable(String),
+    Timeout,
+    /// No location provider exists on this platform.
+    Unsupported,
+    Bridge(String),
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoError::PermissionDenied => write!(f, "Location permission denied"),
+            GeoError::PositionUnavailable(e) => write!(f, "Position unavailable: {}", e),
+            GeoError::Timeout => write!(f, "Timed out waiting for a position"),
+            GeoError::Unsupported => write!(f, "Geolocation is not supported"),
+            GeoError::Bridge(e) => write!(f, "Bridge error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GeoError {}
+
+/// A position or error as reported by JS (or the Android location service).
+/// Error codes follow `GeolocationPositionError`; 0 means unsupported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct GeoReport {
+    position: Option<GeoPosition>,
+    error: Option<GeoErrorReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct GeoErrorReport {
+    code: u8,
+    message: String,
+}
+
+impl GeoReport {
+    fn into_result(self) -> Result<GeoPosition, GeoError> {
+        match (self.position, self.error) {
+            (Some(position), _) => Ok(position),
+            (None, Some(error)) => Err(match error.code {
+                0 => GeoError::Unsupported,
+                1 => GeoError::PermissionDenied,
+                3 => GeoError::Timeout,
+                _ => GeoError::PositionUnavailable(error.message),
+            }),
+            (None, None) => Err(GeoError::PositionUnavailable("empty report".to_string())),
+        }
+    }
+}
+
+/// JS mapping a `GeolocationPosition` to a [`GeoReport`].
+#[cfg(not(target_os = "android"))]
+const JS_TO_REPORT: &str = r#"
+const toReport = (p) => ({
+    position: {
+        latitude: p.coords.latitude,
+        longitude: p.coords.longitude,
+        accuracy: p.coords.accuracy,
+        altitude: p.coords.altitude,
+        altitude_accuracy: p.coords.altitudeAccuracy,
+        heading: p.coords.heading,
+        speed: p.coords.speed,
+        timestamp: p.timestamp,
+    },
+});
+const toErrorReport = (e) => ({ error: { code: e.code, message: e.message } });
+"#;
+
+#[cfg(not(target_os = "android"))]
+fn js_options(options: &GeoOptions) -> String {
+    format!(
+        "{{ enableHighAccuracy: {}, timeout: {}, maximumAge: {} }}",
+        options.high_accuracy,
+        options
+            .timeout_ms
+            .map_or("Infinity".to_string(), |t| t.to_string()),
+        options.maximum_age_ms.unwrap_or(0)
+    )
+}
+
+/// Gets the current position.
+///
+/// Uses `navigator.geolocation` on web and desktop, and the app's location service on
+/// Android: `RustBridge.getCurrentPosition({ high_accuracy, timeout_ms, maximum_age_ms })`
+/// answers with `{ "position": ... }` in the shape of [`GeoPosition`], or
+/// `{ "error": { "code", "message" } }` with `GeolocationPositionError` codes.
+pub async fn get_position(options: &GeoOptions) -> Result<GeoPosition, GeoError> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::to_value(options).map_err(|e| GeoError::Bridge(e.to_string()))?;
+        let report = crate::android_bridge::call_native_async("getCurrentPosition", args)
+            .await
+            .map_err(GeoError::Bridge)?;
+        serde_json::from_value::<GeoReport>(report)
+            .map_err(|e| GeoError::Bridge(e.to_string()))?
+            .into_result()
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let js_code = format!(
+            r#"
+            {JS_TO_REPORT}
+            if (!navigator.geolocation) {{
+                return {{ error: {{ code: 0, message: "unsupported" }} }};
+            }}
+            return await new Promise((resolve) => navigator.geolocation.getCurrentPosition(
+                (p) => resolve(toReport(p)),
+                (e) => resolve(toErrorReport(e)),
+                {options},
+            ));
+            "#,
+            options = js_options(options)
+        );
+        eval_as::<GeoReport>(&js_code)
+            .await
+            .map_err(GeoError::Bridge)?
+            .into_result()
+    }
+}
+
+/// Continuous position updates, created by [`use_watch_position`].
+#[derive(Clone)]
+pub struct GeoWatch {
+    pub position: Signal<Option<GeoPosition>>,
+    pub error: Signal<Option<GeoError>>,
+    bridge: JsBridge<GeoReport>,
+}
+
+impl GeoWatch {
+    pub fn get_position(&self) -> Option<GeoPosition> {
+        self.position.read().clone()
+    }
+    pub fn get_error(&self) -> Option<GeoError> {
+        self.error.read().clone()
+    }
+
+    /// Returns a stream of every update received after this call.
+    pub fn updates(&self) -> impl Stream<Item = Result<GeoPosition, GeoError>> {
+        self.bridge.stream().map(GeoReport::into_result)
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn clear_watch_script(id: &str) -> String {
+    format!(
+        r#"
+        const watch = window.__dioxus_geo && window.__dioxus_geo[{id}];
+        if (watch !== undefined) {{
+            navigator.geolocation.clearWatch(watch);
+            delete window.__dioxus_geo[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Watches the device position until the component unmounts.
+///
+/// On Android, `RustBridge.watchPosition({ ...options, callback_id })` posts reports shaped
+/// like those of [`get_position`] to `callback_id` until `clearWatch({ callback_id })`.
+pub fn use_watch_position(options: GeoOptions) -> GeoWatch {
+    let mut position = use_signal(|| None::<GeoPosition>);
+    let mut error = use_signal(|| None::<GeoError>);
+    let bridge = use_js_listener::<GeoReport>(move |report| match report.into_result() {
+        Ok(update) => {
+            position.set(Some(update));
+            error.set(None);
+        }
+        Err(e) => error.set(Some(e)),
+    });
+
+    let options = use_hook(move || options);
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let callback_id = bridge_for_effect.callback_id();
+
+        #[cfg(target_os = "android")]
+        {
+            // The location service posts reports straight to this bridge's callback ID
+            let mut args = serde_json::to_value(&options).unwrap_or_default();
+            if let Some(args) = args.as_object_mut() {
+                args.insert("callback_id".to_string(), callback_id.into());
+            }
+            if let Err(e) = crate::android_bridge::call_native("watchPosition", &args) {
+                error.set(Some(GeoError::Bridge(e)));
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let js_code = format!(
+                r#"
+                {JS_TO_REPORT}
+                window.__dioxus_geo = window.__dioxus_geo || {{}};
+                const id = {id};
+                if (window.__dioxus_geo[id] === undefined) {{
+                    const emit = (report) => {{
+                        if ({callback}) {{
+                            {callback}(report);
+                        }}
+                    }};
+                    if (!navigator.geolocation) {{
+                        emit({{ error: {{ code: 0, message: "unsupported" }} }});
+                    }} else {{
+                        window.__dioxus_geo[id] = navigator.geolocation.watchPosition(
+                            (p) => emit(toReport(p)),
+                            (e) => emit(toErrorReport(e)),
+                            {options},
+                        );
+                    }}
+                }}
+                "#,
+                id = js_string(&callback_id),
+                callback = bridge_for_effect.js_callback(),
+                options = js_options(&options),
+            );
+            spawn(async move {
+                if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                    error.set(Some(GeoError::Bridge(e)));
+                }
+            });
+        }
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("clearWatch", &args) {
+                eprintln!("Failed to clear position watch: {}", e);
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        eval_detached(&clear_watch_script(&callback_id));
+    });
+
+    GeoWatch {
+        position,
+        error,
+        bridge,
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// An element's box relative to the viewport, as returned by `getBoundingClientRect`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollBehavior {
+    #[default]
+    Auto,
+    Smooth,
+    Instant,
+}
+
+/// Where the element ends up along an axis, as in `scrollIntoView`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    Nearest,
+}
+
+/// Options for [`scroll_to`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScrollOptions {
+    pub behavior: ScrollBehavior,
+    /// Vertical alignment.
+    pub block: ScrollAlign,
+    /// Horizontal alignment.
+    pub inline: ScrollAlign,
+}
+
+impl Default for ScrollOptions {
+    fn default() -> Self {
+        Self {
+            behavior: ScrollBehavior::Auto,
+            block: ScrollAlign::Start,
+            inline: ScrollAlign::Nearest,
+        }
+    }
+}
+
+/// Scroll offsets of the window or a scrollable element, in CSS pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScrollPosition {
+    pub x: f64,
+    pub y: f64,
+    /// Largest possible `x`.
+    pub max_x: f64,
+    /// Largest possible `y`.
+    pub max_y: f64,
+}
+
+/// Returns the bounding box of the first element matching `selector`.
+pub async fn get_bounding_rect(selector: &str) -> Result<Rect, String> {
+    let js_code = format!(
+        r#"
+        const el = document.querySelector({selector});
+        if (!el) {{
+            throw new Error("get_bounding_rect: no element matches " + {selector});
+        }}
+        const r = el.getBoundingClientRect();
+        return {{
+            x: r.x,
+            y: r.y,
+            width: r.width,
+            height: r.height,
+            top: r.top,
+            right: r.right,
+            bottom: r.bottom,
+            left: r.left,
+        }};
+        "#,
+        selector = js_string(selector)
+    );
+    eval_as(&js_code).await
+}
+
+/// Scrolls the first element matching `selector` into view.
+pub async fn scroll_to(selector: &str, options: &ScrollOptions) -> Result<(), String> {
+    let js_code = format!(
+        r#"
+        const el = document.querySelector({selector});
+        if (!el) {{
+            throw new Error("scroll_to: no element matches " + {selector});
+        }}
+        el.scrollIntoView({options});
+        "#,
+        selector = js_string(selector),
+        options =
+            serde_json::to_string(options).map_err(|e| format!("Serialization error: {}", e))?,
+    );
+    eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+}
+
+/// Scroll position tracked by [`use_scroll_position`].
+#[derive(Clone)]
+pub struct ScrollTracker {
+    pub position: Signal<ScrollPosition>,
+    pub bridge: JsBridge<ScrollPosition>,
+}
+
+impl ScrollTracker {
+    pub fn get_position(&self) -> ScrollPosition {
+        *self.position.read()
+    }
+
+    /// Returns a stream of every position received after this call.
+    pub fn positions(&self) -> UnboundedReceiver<ScrollPosition> {
+        self.bridge.stream()
+    }
+}
+
+/// Tracks the scroll position of the element matching `selector`, or of the window when
+/// `None`, until the component unmounts. Updates are sent at most once per animation
+/// frame.
+pub fn use_scroll_position(selector: Option<&str>) -> ScrollTracker {
+    let mut position = use_signal(ScrollPosition::default);
+    let bridge = use_js_listener::<ScrollPosition>(move |update| position.set(update));
+
+    let selector = use_hook(|| selector.map(str::to_string));
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let (target, scroller) = match &selector {
+            Some(selector) => {
+                let element = format!("document.querySelector({})", js_string(selector));
+                (element.clone(), element)
+            }
+            None => (
+                "window".to_string(),
+                "(document.scrollingElement || document.documentElement)".to_string(),
+            ),
+        };
+        let js_code = format!(
+            r#"
+            window.__dioxus_scroll = window.__dioxus_scroll || {{}};
+            const id = {id};
+            if (!window.__dioxus_scroll[id]) {{
+                const target = {target};
+                const scroller = {scroller};
+                if (!target || !scroller) {{
+                    throw new Error("use_scroll_position: element not found");
+                }}
+                let pending = false;
+                const report = () => {{
+                    pending = false;
+                    if ({callback}) {{
+                        {callback}({{
+                            x: scroller.scrollLeft,
+                            y: scroller.scrollTop,
+                            max_x: scroller.scrollWidth - scroller.clientWidth,
+                            max_y: scroller.scrollHeight - scroller.clientHeight,
+                        }});
+                    }}
+                }};
+                const handler = () => {{
+                    if (!pending) {{
+                        pending = true;
+                        requestAnimationFrame(report);
+                    }}
+                }};
+                target.addEventListener("scroll", handler, {{ passive: true }});
+                window.__dioxus_scroll[id] = {{ target, handler }};
+                report();
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const watch = window.__dioxus_scroll && window.__dioxus_scroll[{id}];
+            if (watch) {{
+                watch.target.removeEventListener("scroll", watch.handler);
+                delete window.__dioxus_scroll[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    ScrollTracker { position, bridge }
+}
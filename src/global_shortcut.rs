@@ -0,0 +1,63 @@
+use crate::desktop_window::desktop_context;
+use dioxus::prelude::*;
+use dioxus_desktop::{DesktopContext, HotKeyState, ShortcutHandle};
+use global_hotkey::hotkey::HotKey;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutState {
+    Pressed,
+    Released,
+}
+
+/// A shortcut registered by [`register_global_shortcut`]. Dropping it unregisters the
+/// shortcut.
+pub struct GlobalShortcut {
+    accelerator: String,
+    handle: ShortcutHandle,
+    context: DesktopContext,
+}
+
+impl GlobalShortcut {
+    pub fn accelerator(&self) -> &str {
+        &self.accelerator
+    }
+}
+
+impl Drop for GlobalShortcut {
+    fn drop(&mut self) {
+        self.context.remove_shortcut(self.handle);
+    }
+}
+
+/// Calls `handler` when `accelerator` is pressed and released, even while another app
+/// has focus. Desktop only; on Linux it needs X11.
+///
+/// Accelerators are modifiers and a key joined with `+`, such as `"CmdOrCtrl+Shift+K"`,
+/// where `CmdOrCtrl` is Command on macOS and Control elsewhere. The handler runs in the
+/// calling component's scope, like a bridge's message handler, so keep the returned
+/// guard no longer than that component, e.g. in `use_hook`.
+pub fn register_global_shortcut(
+    accelerator: &str,
+    handler: impl FnMut(ShortcutState) + 'static,
+) -> Result<GlobalShortcut, String> {
+    let hotkey = HotKey::from_str(accelerator)
+        .map_err(|e| format!("Invalid shortcut {:?}: {}", accelerator, e))?;
+    let context = desktop_context()?;
+    let handler = Callback::new(handler);
+    let handle = context
+        .create_shortcut(hotkey, move |state| {
+            handler.call(match state {
+                HotKeyState::Pressed => ShortcutState::Pressed,
+                HotKeyState::Released => ShortcutState::Released,
+            })
+        })
+        .map_err(|e| format!("Failed to register shortcut {:?}: {:?}", accelerator, e))?;
+    Ok(GlobalShortcut {
+        accelerator: accelerator.to_string(),
+        handle,
+        context,
+    })
+}
@@ -0,0 +1,109 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A `popstate` event: the user navigated back or forward.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PopStateEvent {
+    /// `location.href` after the navigation.
+    pub url: String,
+    /// The state passed to [`push_state`] or [`replace_state`] for this entry.
+    pub state: Option<serde_json::Value>,
+}
+
+/// Adds a history entry for `url` without reloading the page.
+pub async fn push_state<S: Serialize>(url: &str, state: Option<&S>) -> Result<(), String> {
+    change_state("pushState", url, state).await
+}
+
+/// Replaces the current history entry with `url` without reloading the page.
+pub async fn replace_state<S: Serialize>(url: &str, state: Option<&S>) -> Result<(), String> {
+    change_state("replaceState", url, state).await
+}
+
+/// Moves `delta` entries through the history, like `history.go`. The resulting
+/// `popstate` arrives through [`use_popstate`].
+pub async fn history_go(delta: i32) -> Result<(), String> {
+    eval_as::<serde_json::Value>(&format!("history.go({});", delta))
+        .await
+        .map(|_| ())
+}
+
+async fn change_state<S: Serialize>(
+    method: &str,
+    url: &str,
+    state: Option<&S>,
+) -> Result<(), String> {
+    let state = serde_json::to_string(&state).map_err(|e| format!("Serialization error: {}", e))?;
+    let js_code = format!(
+        "history.{method}({state}, \"\", {url}); return location.href;",
+        url = js_string(url)
+    );
+    let _url = eval_as::<String>(&js_code).await?;
+    #[cfg(target_os = "android")]
+    sync_android_url(&_url);
+    Ok(())
+}
+
+/// Tells the app the WebView's URL changed with `RustBridge.onUrlChanged({ url })`, so
+/// native navigation state such as the back button stays in step.
+#[cfg(target_os = "android")]
+fn sync_android_url(url: &str) {
+    let args = serde_json::json!({ "url": url });
+    if let Err(e) = crate::android_bridge::call_native("onUrlChanged", &args) {
+        eprintln!("Failed to sync URL: {}", e);
+    }
+}
+
+/// Receives `popstate` events until the component unmounts. The returned bridge holds
+/// the latest event; use [`JsBridge::stream`] to receive every one.
+pub fn use_popstate() -> JsBridge<PopStateEvent> {
+    let bridge = use_js_listener::<PopStateEvent>(|_event| {
+        #[cfg(target_os = "android")]
+        sync_android_url(&_event.url);
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            window.__dioxus_popstate = window.__dioxus_popstate || {{}};
+            const id = {id};
+            if (!window.__dioxus_popstate[id]) {{
+                const handler = (e) => {{
+                    if ({callback}) {{
+                        {callback}({{ url: location.href, state: e.state ?? null }});
+                    }}
+                }};
+                window.addEventListener("popstate", handler);
+                window.__dioxus_popstate[id] = handler;
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const handler = window.__dioxus_popstate && window.__dioxus_popstate[{id}];
+            if (handler) {{
+                window.removeEventListener("popstate", handler);
+                delete window.__dioxus_popstate[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    bridge
+}
@@ -0,0 +1,265 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A key combination such as `"ctrl+k"`, `"shift+alt+ArrowUp"` or `"mod+s"`, where `mod`
+/// is Cmd on Apple platforms and Ctrl elsewhere.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotkey {
+    /// Lowercase `KeyboardEvent.key`, e.g. `"k"`, `"escape"` or `"arrowup"`.
+    pub key: String,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    /// The platform's primary modifier (`mod`).
+    pub primary: bool,
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    fn from_str(combo: &str) -> Result<Self, Self::Err> {
+        let mut hotkey = Hotkey {
+            key: String::new(),
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            primary: false,
+        };
+        let parts: Vec<&str> = combo.split('+').map(str::trim).collect();
+        let (key, modifiers) = match parts.split_last() {
+            // A trailing "+" means the plus key itself
+            Some((&"", rest)) if combo.ends_with("++") || combo == "+" => ("+", rest),
+            Some((key, rest)) => (*key, rest),
+            None => return Err(format!("Empty hotkey: {:?}", combo)),
+        };
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => hotkey.ctrl = true,
+                "alt" | "option" => hotkey.alt = true,
+                "shift" => hotkey.shift = true,
+                "meta" | "cmd" | "command" | "super" | "win" => hotkey.meta = true,
+                "mod" => hotkey.primary = true,
+                "" => {}
+                other => return Err(format!("Unknown modifier {:?} in {:?}", other, combo)),
+            }
+        }
+        if key.is_empty() {
+            return Err(format!("Missing key in hotkey {:?}", combo));
+        }
+        hotkey.key = match key.to_ascii_lowercase().as_str() {
+            "esc" => "escape".to_string(),
+            "space" => " ".to_string(),
+            "up" => "arrowup".to_string(),
+            "down" => "arrowdown".to_string(),
+            "left" => "arrowleft".to_string(),
+            "right" => "arrowright".to_string(),
+            "del" => "delete".to_string(),
+            "return" => "enter".to_string(),
+            other => other.to_string(),
+        };
+        Ok(hotkey)
+    }
+}
+
+/// How a hotkey listener treats matching key presses.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyOptions {
+    /// Calls `preventDefault` on matching events, e.g. to override the browser's `ctrl+s`.
+    pub prevent_default: bool,
+    /// Also fires while focus is in an input, textarea or contenteditable element.
+    pub in_inputs: bool,
+    /// Also fires for auto-repeated key presses.
+    pub repeat: bool,
+}
+
+impl Default for HotkeyOptions {
+    fn default() -> Self {
+        Self {
+            prevent_default: true,
+            in_inputs: false,
+            repeat: true,
+        }
+    }
+}
+
+/// A matched key press.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyEvent {
+    /// `KeyboardEvent.key` as pressed.
+    pub key: String,
+    pub repeat: bool,
+}
+
+/// Calls `handler` whenever `combo` (e.g. `"ctrl+k"`, see [`Hotkey`]) is pressed while the
+/// component is mounted.
+pub fn use_hotkey(
+    combo: &str,
+    handler: impl FnMut(HotkeyEvent) + 'static,
+) -> JsBridge<HotkeyEvent> {
+    use_hotkey_with_options(combo, HotkeyOptions::default(), handler)
+}
+
+/// Like [`use_hotkey`], with control over `preventDefault` and which presses match.
+///
+/// On Android, hardware-keyboard presses the WebView doesn't receive are forwarded by the
+/// app: `RustBridge.registerHotkey({ hotkey, callback_id })`, with the parsed [`Hotkey`],
+/// posts a [`HotkeyEvent`] to `callback_id` for each match until
+/// `unregisterHotkey({ callback_id })`.
+pub fn use_hotkey_with_options(
+    combo: &str,
+    options: HotkeyOptions,
+    handler: impl FnMut(HotkeyEvent) + 'static,
+) -> JsBridge<HotkeyEvent> {
+    let bridge = use_js_listener::<HotkeyEvent>(handler);
+
+    let hotkey = use_hook(|| combo.parse::<Hotkey>());
+    let options = use_hook(move || options);
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let mut bridge = bridge_for_effect.clone();
+        let hotkey = match &hotkey {
+            Ok(hotkey) => hotkey,
+            Err(e) => {
+                bridge.set_error(Some(e.clone()));
+                return;
+            }
+        };
+        let callback_id = bridge.callback_id();
+
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "hotkey": hotkey, "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("registerHotkey", &args) {
+                bridge.set_error(Some(e));
+            }
+        }
+
+        let js_code = format!(
+            r#"
+            window.__dioxus_hotkeys = window.__dioxus_hotkeys || {{}};
+            const id = {id};
+            if (!window.__dioxus_hotkeys[id]) {{
+                const hotkey = {hotkey};
+                const options = {options};
+                const isApple = /Mac|iPhone|iPad/.test(navigator.platform || navigator.userAgent);
+                const ctrl = hotkey.ctrl || (hotkey.primary && !isApple);
+                const meta = hotkey.meta || (hotkey.primary && isApple);
+                const keyFromCode = (code) => {{
+                    if (/^Key[A-Z]$/.test(code)) return code.slice(3).toLowerCase();
+                    if (/^Digit[0-9]$/.test(code)) return code.slice(5);
+                    return null;
+                }};
+                const handler = (e) => {{
+                    if (e.ctrlKey !== ctrl || e.altKey !== hotkey.alt || e.metaKey !== meta) return;
+                    // Shift is part of typing symbols such as "?", so it only has to match
+                    // for letters and named keys
+                    if (e.shiftKey !== hotkey.shift) {{
+                        if (hotkey.shift || e.key.length !== 1 || /[a-z]/i.test(e.key)) return;
+                    }}
+                    const key = e.key.toLowerCase();
+                    if (key !== hotkey.key && keyFromCode(e.code) !== hotkey.key) return;
+                    if (e.repeat && !options.repeat) return;
+                    const t = e.target;
+                    if (!options.in_inputs && t && (t.isContentEditable || /^(INPUT|TEXTAREA|SELECT)$/.test(t.tagName))) return;
+                    if (options.prevent_default) e.preventDefault();
+                    if ({callback}) {{
+                        {callback}({{ key: e.key, repeat: e.repeat }});
+                    }}
+                }};
+                window.addEventListener("keydown", handler);
+                window.__dioxus_hotkeys[id] = handler;
+            }}
+            "#,
+            id = js_string(&callback_id),
+            hotkey = serde_json::to_string(hotkey).unwrap_or_else(|_| "{}".to_string()),
+            options = serde_json::to_string(&options).unwrap_or_else(|_| "{}".to_string()),
+            callback = bridge.js_callback(),
+        );
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("unregisterHotkey", &args) {
+                eprintln!("Failed to unregister hotkey: {}", e);
+            }
+        }
+        eval_detached(&format!(
+            r#"
+            const handler = window.__dioxus_hotkeys && window.__dioxus_hotkeys[{id}];
+            if (handler) {{
+                window.removeEventListener("keydown", handler);
+                delete window.__dioxus_hotkeys[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    bridge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_and_key_are_parsed() {
+        let hotkey: Hotkey = "Ctrl+Shift+K".parse().unwrap();
+        assert_eq!(
+            hotkey,
+            Hotkey {
+                key: "k".to_string(),
+                ctrl: true,
+                alt: false,
+                shift: true,
+                meta: false,
+                primary: false,
+            }
+        );
+        let hotkey: Hotkey = "mod + option + cmd + s".parse().unwrap();
+        assert!(hotkey.primary && hotkey.alt && hotkey.meta && !hotkey.ctrl);
+        assert_eq!(hotkey.key, "s");
+    }
+
+    #[test]
+    fn key_aliases_match_keyboard_event_keys() {
+        for (combo, key) in [
+            ("esc", "escape"),
+            ("space", " "),
+            ("alt+Up", "arrowup"),
+            ("del", "delete"),
+            ("return", "enter"),
+            ("F5", "f5"),
+        ] {
+            assert_eq!(combo.parse::<Hotkey>().unwrap().key, key, "{combo}");
+        }
+    }
+
+    #[test]
+    fn a_trailing_plus_is_the_plus_key() {
+        assert_eq!("+".parse::<Hotkey>().unwrap().key, "+");
+        let hotkey: Hotkey = "ctrl++".parse().unwrap();
+        assert!(hotkey.ctrl);
+        assert_eq!(hotkey.key, "+");
+    }
+
+    #[test]
+    fn invalid_combos_are_rejected() {
+        assert!("".parse::<Hotkey>().is_err());
+        assert!("ctrl+".parse::<Hotkey>().is_err());
+        assert!("hyper+k".parse::<Hotkey>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Whether the user is present.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleState {
+    #[default]
+    Active,
+    /// No input for the threshold, or the screen is locked.
+    Idle,
+}
+
+/// The user's presence, created by [`use_idle`].
+#[derive(Clone)]
+pub struct IdleStatus {
+    pub state: Signal<IdleState>,
+    pub bridge: JsBridge<IdleState>,
+}
+
+impl IdleStatus {
+    pub fn get_state(&self) -> IdleState {
+        *self.state.read()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.get_state() == IdleState::Idle
+    }
+
+    /// Returns a stream of every transition received after this call.
+    pub fn changes(&self) -> UnboundedReceiver<IdleState> {
+        self.bridge.stream()
+    }
+}
+
+// Page input that counts as activity when the Idle Detection API can't be used.
+const INPUT_EVENTS: &str =
+    r#"["pointermove", "pointerdown", "keydown", "wheel", "touchstart", "scroll"]"#;
+
+/// Reports when the user goes idle after `threshold` without input, and when they return,
+/// until the component unmounts. The threshold is read on the first render.
+///
+/// Uses the Idle Detection API when the page already has its permission, which also
+/// notices input in other apps and a locked screen; its threshold is at least a minute.
+/// Otherwise input events on the page are watched, which works everywhere, including
+/// Android.
+pub fn use_idle(threshold: Duration) -> IdleStatus {
+    let threshold = use_hook(|| threshold);
+    let mut state = use_signal(IdleState::default);
+    let bridge = use_js_listener::<IdleState>(move |update| {
+        if *state.peek() != update {
+            state.set(update);
+        }
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            window.__dioxus_idle = window.__dioxus_idle || {{}};
+            const id = {id};
+            if (!window.__dioxus_idle[id]) {{
+                const threshold = {threshold};
+                const watch = {{ last: null, timer: null, controller: new AbortController() }};
+                window.__dioxus_idle[id] = watch;
+                const report = (value) => {{
+                    if (watch.last !== value && {callback}) {{
+                        watch.last = value;
+                        {callback}(value);
+                    }}
+                }};
+
+                let detected = false;
+                if ("IdleDetector" in window && threshold >= 60000) {{
+                    try {{
+                        const status = await navigator.permissions.query({{ name: "idle-detection" }});
+                        if (status.state === "granted") {{
+                            const detector = new IdleDetector();
+                            const update = () => report(
+                                detector.userState === "idle" || detector.screenState === "locked"
+                                    ? "idle"
+                                    : "active"
+                            );
+                            detector.addEventListener("change", update);
+                            await detector.start({{ threshold, signal: watch.controller.signal }});
+                            update();
+                            detected = true;
+                        }}
+                    }} catch (e) {{
+                        // Not permitted here: fall back to input events
+                    }}
+                }}
+
+                if (!detected && window.__dioxus_idle[id] === watch) {{
+                    const activity = () => {{
+                        report("active");
+                        clearTimeout(watch.timer);
+                        watch.timer = setTimeout(() => report("idle"), threshold);
+                    }};
+                    for (const name of {events}) {{
+                        window.addEventListener(name, activity, {{
+                            capture: true,
+                            passive: true,
+                            signal: watch.controller.signal,
+                        }});
+                    }}
+                    activity();
+                }}
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            threshold = threshold.as_millis(),
+            callback = bridge_for_effect.js_callback(),
+            events = INPUT_EVENTS,
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const watch = window.__dioxus_idle && window.__dioxus_idle[{id}];
+            if (watch) {{
+                watch.controller.abort();
+                clearTimeout(watch.timer);
+                delete window.__dioxus_idle[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    IdleStatus { state, bridge }
+}
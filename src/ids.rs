@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// How bridges generate their callback IDs; see
+/// [`BridgeOptions::id_strategy`](crate::BridgeOptions::id_strategy).
+#[derive(Clone, Default)]
+pub enum IdStrategy {
+    /// A random v4 UUID with the `uuid` feature, otherwise a timestamp with a random part.
+    #[default]
+    Uuid,
+    /// `callback_1`, `callback_2`, ... counted per process, e.g. for snapshot tests.
+    Sequential,
+    /// IDs from a function. They must be valid in a JS identifier, since platforms other
+    /// than web name the callback `window.__dioxus_bridge_<id>`.
+    Custom(Arc<dyn Fn() -> String + Send + Sync>),
+}
+
+impl IdStrategy {
+    pub fn custom(generate: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(generate))
+    }
+
+    /// Generates an ID. It isn't reserved, so it may be taken by another bridge.
+    pub fn generate(&self) -> String {
+        match self {
+            Self::Uuid => random_id(),
+            Self::Sequential => {
+                static NEXT: AtomicU64 = AtomicU64::new(1);
+                format!("callback_{}", NEXT.fetch_add(1, Ordering::Relaxed))
+            }
+            Self::Custom(generate) => generate(),
+        }
+    }
+}
+
+impl fmt::Debug for IdStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uuid => f.write_str("Uuid"),
+            Self::Sequential => f.write_str("Sequential"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl PartialEq for IdStrategy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Uuid, Self::Uuid) | (Self::Sequential, Self::Sequential) => true,
+            (Self::Custom(a), Self::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Generates a hard-to-guess ID in a platform-specific way.
+pub(crate) fn random_id() -> String {
+    #[cfg(feature = "uuid")]
+    {
+        uuid::Uuid::new_v4().to_string().replace("-", "_")
+    }
+    #[cfg(all(target_arch = "wasm32", not(feature = "uuid")))]
+    {
+        let random_part: String = js_sys::Math::random().to_string().chars().skip(2).collect();
+        format!("callback_{}_{}", js_sys::Date::now(), random_part)
+    }
+    #[cfg(not(any(target_arch = "wasm32", feature = "uuid")))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        format!("callback_{}", timestamp)
+    }
+}
+
+/// Callback IDs in use by bridges in this process.
+static TAKEN: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+fn taken() -> std::sync::MutexGuard<'static, BTreeSet<String>> {
+    TAKEN.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Generates a callback ID no other bridge holds and reserves it. A generated ID that is
+/// taken, e.g. two timestamp IDs from the same millisecond, gets a numeric suffix.
+pub(crate) fn allocate(strategy: &IdStrategy) -> String {
+    let base = strategy.generate();
+    let mut taken = taken();
+    let id = std::iter::once(base.clone())
+        .chain((1..).map(|n| format!("{base}_{n}")))
+        .find(|id| !taken.contains(id))
+        .unwrap_or(base);
+    taken.insert(id.clone());
+    id
+}
+
+/// Reserves a callback ID chosen by the caller, failing if another bridge holds it.
+pub(crate) fn reserve(id: &str) -> Result<(), String> {
+    if taken().insert(id.to_string()) {
+        Ok(())
+    } else {
+        Err(format!("Callback ID {id} is already in use"))
+    }
+}
+
+/// Frees `id` once its bridge is closed.
+pub(crate) fn release(id: &str) {
+    taken().remove(id);
+}
@@ -0,0 +1,206 @@
+use crate::binary::{self, JS_BASE64_HELPERS};
+use crate::{eval_as, js_string};
+use serde::{Deserialize, Serialize};
+
+/// JS functions decoding images with the WebView's codecs and encoding canvases, inlined
+/// into the image scripts.
+const JS_IMAGE_HELPERS: &str = r#"
+const __dxBitmap = (text) => createImageBitmap(new Blob([__dxFromBase64(text)]), {
+    premultiplyAlpha: "none",
+    colorSpaceConversion: "none",
+});
+const __dxCanvas = (width, height) => {
+    if (typeof OffscreenCanvas !== "undefined") return new OffscreenCanvas(width, height);
+    const canvas = document.createElement("canvas");
+    canvas.width = width;
+    canvas.height = height;
+    return canvas;
+};
+const __dxEncode = async (canvas, type, quality) => {
+    const blob = canvas.convertToBlob
+        ? await canvas.convertToBlob({ type, quality: quality ?? undefined })
+        : await new Promise((resolve) => canvas.toBlob(resolve, type, quality ?? undefined));
+    if (!blob || blob.type !== type) throw new Error("Encoding " + type + " is not supported");
+    return __dxToBase64(new Uint8Array(await blob.arrayBuffer()));
+};
+"#;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An image decoded to straight (not premultiplied) RGBA, 4 bytes per pixel, row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedImage {
+    pub width: u32,
+    pub height: u32,
+    #[serde(with = "crate::binary::base64_bytes")]
+    pub rgba: Vec<u8>,
+}
+
+/// Formats images can be encoded to. Browsers can decode more, such as GIF and AVIF.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// Not every WebView encodes WebP; Safari's WebKit doesn't.
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EncodeOptions {
+    pub format: ImageFormat,
+    /// From 0.0 to 1.0, for JPEG and WebP. The browser's default when `None`.
+    pub quality: Option<f32>,
+    /// Scales the image down, keeping its aspect ratio, to fit within these bounds.
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+}
+
+impl EncodeOptions {
+    fn quality(&self) -> String {
+        self.quality.map_or_else(
+            || "null".to_string(),
+            |quality| quality.clamp(0.0, 1.0).to_string(),
+        )
+    }
+
+    /// The size an image of `width` by `height` is scaled down to.
+    fn fit(&self, width: u32, height: u32) -> (u32, u32) {
+        let scale = [
+            self.max_width.map(|max| max as f64 / width.max(1) as f64),
+            self.max_height.map(|max| max as f64 / height.max(1) as f64),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(1.0, f64::min);
+        let scaled = |side: u32| ((side as f64 * scale).round() as u32).max(1);
+        (scaled(width), scaled(height))
+    }
+}
+
+/// Reads the dimensions of an encoded image, e.g. PNG, JPEG, WebP or GIF, with the
+/// WebView's decoder.
+pub async fn image_size(bytes: &[u8]) -> Result<ImageSize, String> {
+    let js_code = format!(
+        r#"
+        {JS_BASE64_HELPERS}
+        {JS_IMAGE_HELPERS}
+        const bitmap = await __dxBitmap({bytes});
+        const size = {{ width: bitmap.width, height: bitmap.height }};
+        bitmap.close();
+        return size;
+        "#,
+        bytes = js_string(&binary::encode(bytes)),
+    );
+    eval_as(&js_code).await
+}
+
+/// Decodes an image to RGBA pixels with the WebView's decoder, instead of an image crate
+/// in the binary.
+pub async fn decode_image(bytes: &[u8]) -> Result<DecodedImage, String> {
+    let js_code = format!(
+        r#"
+        {JS_BASE64_HELPERS}
+        {JS_IMAGE_HELPERS}
+        const bitmap = await __dxBitmap({bytes});
+        const {{ width, height }} = bitmap;
+        const context = __dxCanvas(width, height).getContext("2d");
+        context.drawImage(bitmap, 0, 0);
+        bitmap.close();
+        const pixels = context.getImageData(0, 0, width, height).data;
+        return {{ width, height, rgba: __dxToBase64(new Uint8Array(pixels.buffer)) }};
+        "#,
+        bytes = js_string(&binary::encode(bytes)),
+    );
+    eval_as(&js_code).await
+}
+
+/// Encodes RGBA pixels, scaled to fit `options`, with the WebView's encoder.
+pub async fn encode_image(
+    image: &DecodedImage,
+    options: &EncodeOptions,
+) -> Result<Vec<u8>, String> {
+    let expected = image.width as usize * image.height as usize * 4;
+    if image.rgba.len() != expected {
+        return Err(format!(
+            "Expected {} bytes of RGBA for {}x{}, got {}",
+            expected,
+            image.width,
+            image.height,
+            image.rgba.len()
+        ));
+    }
+    let (width, height) = options.fit(image.width, image.height);
+    let js_code = format!(
+        r#"
+        {JS_BASE64_HELPERS}
+        {JS_IMAGE_HELPERS}
+        const pixels = new ImageData(
+            new Uint8ClampedArray(__dxFromBase64({rgba}).buffer), {source_width}, {source_height}
+        );
+        const bitmap = await createImageBitmap(pixels, {{ premultiplyAlpha: "none" }});
+        const canvas = __dxCanvas({width}, {height});
+        const context = canvas.getContext("2d");
+        context.imageSmoothingQuality = "high";
+        context.drawImage(bitmap, 0, 0, {width}, {height});
+        bitmap.close();
+        return await __dxEncode(canvas, {mime_type}, {quality});
+        "#,
+        rgba = js_string(&binary::encode(&image.rgba)),
+        source_width = image.width,
+        source_height = image.height,
+        mime_type = js_string(options.format.mime_type()),
+        quality = options.quality(),
+    );
+    binary::decode(&eval_as::<String>(&js_code).await?)
+}
+
+/// Converts, compresses or scales down an encoded image without decoding it in Rust, e.g.
+/// a photo to a JPEG thumbnail before uploading it.
+pub async fn reencode_image(bytes: &[u8], options: &EncodeOptions) -> Result<Vec<u8>, String> {
+    let js_code = format!(
+        r#"
+        {JS_BASE64_HELPERS}
+        {JS_IMAGE_HELPERS}
+        const bitmap = await __dxBitmap({bytes});
+        const limits = [{max_width}, {max_height}];
+        const scale = Math.min(
+            1,
+            limits[0] === null ? 1 : limits[0] / bitmap.width,
+            limits[1] === null ? 1 : limits[1] / bitmap.height,
+        );
+        const width = Math.max(1, Math.round(bitmap.width * scale));
+        const height = Math.max(1, Math.round(bitmap.height * scale));
+        const canvas = __dxCanvas(width, height);
+        const context = canvas.getContext("2d");
+        context.imageSmoothingQuality = "high";
+        context.drawImage(bitmap, 0, 0, width, height);
+        bitmap.close();
+        return await __dxEncode(canvas, {mime_type}, {quality});
+        "#,
+        bytes = js_string(&binary::encode(bytes)),
+        max_width = options
+            .max_width
+            .map_or("null".to_string(), |max| max.to_string()),
+        max_height = options
+            .max_height
+            .map_or("null".to_string(), |max| max.to_string()),
+        mime_type = js_string(options.format.mime_type()),
+        quality = options.quality(),
+    );
+    binary::decode(&eval_as::<String>(&js_code).await?)
+}
@@ -0,0 +1,77 @@
+/// Paths used by [`android_bridge_exports!`](crate::android_bridge_exports).
+#[cfg(target_os = "android")]
+pub mod android {
+    pub use crate::android_bridge::{
+        cache_bridge_class, on_message_from_frame, on_message_from_java, set_bridge_class,
+    };
+    pub use jni;
+}
+
+/// Exports the JNI functions the Kotlin bridge class calls, for a class in the app's own
+/// package, and makes calls into Kotlin go to that class. The built-in exports are for
+/// `io.github.memkit.RustBridge`.
+///
+/// Use it once, at the top level of the app crate:
+/// `dx_use_js_bridge::android_bridge_exports!(com.mygame.app.RustBridge);`. Package and
+/// class names can't contain underscores, which JNI would have to escape. It expands to
+/// nothing on other targets.
+#[macro_export]
+macro_rules! android_bridge_exports {
+    ($first:ident $(. $rest:ident)*) => {
+        #[cfg(target_os = "android")]
+        const _: () = {
+            use $crate::__android::jni::objects::{JClass, JObject, JString};
+            use $crate::__android::jni::JNIEnv;
+
+            #[export_name = concat!(
+                "Java_", stringify!($first), $("_", stringify!($rest),)* "_onMessageFromJava"
+            )]
+            pub extern "system" fn on_message_from_java(
+                mut env: JNIEnv,
+                _class: JClass,
+                callback_id: JString,
+                json_data: JString,
+            ) {
+                $crate::__android::set_bridge_class(
+                    concat!(stringify!($first) $(, "/", stringify!($rest))*),
+                );
+                $crate::__android::on_message_from_java(&mut env, &callback_id, &json_data);
+            }
+
+            #[export_name = concat!(
+                "Java_", stringify!($first), $("_", stringify!($rest),)* "_onMessageFromFrame"
+            )]
+            pub extern "system" fn on_message_from_frame(
+                mut env: JNIEnv,
+                _class: JClass,
+                message: JString,
+                source_origin: JString,
+                is_main_frame: $crate::__android::jni::sys::jboolean,
+            ) {
+                $crate::__android::set_bridge_class(
+                    concat!(stringify!($first) $(, "/", stringify!($rest))*),
+                );
+                $crate::__android::on_message_from_frame(
+                    &mut env,
+                    &message,
+                    &source_origin,
+                    is_main_frame != 0,
+                );
+            }
+
+            #[export_name = concat!(
+                "Java_", stringify!($first), $("_", stringify!($rest),)* "_registerInstance"
+            )]
+            pub extern "system" fn register_instance(
+                mut env: JNIEnv,
+                _class: JClass,
+                _activity: JObject,
+            ) {
+                $crate::__android::set_bridge_class(
+                    concat!(stringify!($first) $(, "/", stringify!($rest))*),
+                );
+                $crate::__android::cache_bridge_class(&mut env);
+            }
+        };
+    };
+}
@@ -0,0 +1,167 @@
+use crate::{eval_as, js_string, FromJs};
+use base64::Engine;
+use serde::Serialize;
+
+/// An IndexedDB object store in the page, used as an async key-value store.
+///
+/// JSON values are stored as structured objects, bytes as `Uint8Array`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KvStore {
+    pub database: String,
+    pub store: String,
+}
+
+impl Default for KvStore {
+    fn default() -> Self {
+        Self::new("dioxus_bridge", "kv")
+    }
+}
+
+impl KvStore {
+    pub fn new(database: &str, store: &str) -> Self {
+        Self {
+            database: database.to_string(),
+            store: store.to_string(),
+        }
+    }
+
+    pub async fn get<T: FromJs>(&self, key: &str) -> Result<Option<T>, String> {
+        self.run(
+            "readonly",
+            &format!("store.get({})", js_string(key)),
+            "result === undefined ? null : result",
+        )
+        .await
+    }
+
+    pub async fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<(), String> {
+        let json =
+            serde_json::to_string(value).map_err(|e| format!("Serialization error: {}", e))?;
+        self.run::<serde_json::Value>(
+            "readwrite",
+            &format!("store.put({}, {})", json, js_string(key)),
+            "null",
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let encoded: Option<String> = self
+            .run(
+                "readonly",
+                &format!("store.get({})", js_string(key)),
+                r#"(() => {
+                    if (result === undefined) return null;
+                    if (!(result instanceof Uint8Array)) throw new Error("value is not binary");
+                    let binary = "";
+                    for (let i = 0; i < result.length; i += 0x8000) {
+                        binary += String.fromCharCode.apply(null, result.subarray(i, i + 0x8000));
+                    }
+                    return btoa(binary);
+                })()"#,
+            )
+            .await?;
+        encoded
+            .map(|encoded| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|e| format!("Invalid binary value: {}", e))
+            })
+            .transpose()
+    }
+
+    pub async fn put_bytes(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+        self.run::<serde_json::Value>(
+            "readwrite",
+            &format!(
+                "store.put(Uint8Array.from(atob({}), (c) => c.charCodeAt(0)), {})",
+                js_string(&encoded),
+                js_string(key)
+            ),
+            "null",
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        self.run::<serde_json::Value>(
+            "readwrite",
+            &format!("store.delete({})", js_string(key)),
+            "null",
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Lists all keys in the store.
+    pub async fn list(&self) -> Result<Vec<String>, String> {
+        self.run("readonly", "store.getAllKeys()", "result.map(String)")
+            .await
+    }
+
+    /// Runs one IndexedDB request against the store and returns `output`, a JS expression
+    /// over the request's `result`. The store is created on first use.
+    async fn run<R: FromJs>(&self, mode: &str, request: &str, output: &str) -> Result<R, String> {
+        let js_code = format!(
+            r#"
+            const name = {database};
+            const storeName = {store};
+            const open = (version) => new Promise((resolve, reject) => {{
+                const req = version ? indexedDB.open(name, version) : indexedDB.open(name);
+                req.onupgradeneeded = () => {{
+                    if (!req.result.objectStoreNames.contains(storeName)) {{
+                        req.result.createObjectStore(storeName);
+                    }}
+                }};
+                req.onsuccess = () => resolve(req.result);
+                req.onerror = () => reject(req.error);
+            }});
+            let db = await open();
+            if (!db.objectStoreNames.contains(storeName)) {{
+                const version = db.version + 1;
+                db.close();
+                db = await open(version);
+            }}
+            try {{
+                const store = db.transaction(storeName, "{mode}").objectStore(storeName);
+                const req = {request};
+                const result = await new Promise((resolve, reject) => {{
+                    req.onsuccess = () => resolve(req.result);
+                    req.onerror = () => reject(req.error);
+                }});
+                return {output};
+            }} finally {{
+                db.close();
+            }}
+            "#,
+            database = js_string(&self.database),
+            store = js_string(&self.store),
+        );
+        eval_as(&js_code)
+            .await
+            .map_err(|e| format!("IndexedDB error: {}", e))
+    }
+}
+
+/// Reads a JSON value from the default [`KvStore`].
+pub async fn kv_get<T: FromJs>(key: &str) -> Result<Option<T>, String> {
+    KvStore::default().get(key).await
+}
+
+/// Writes a JSON value to the default [`KvStore`].
+pub async fn kv_put<T: Serialize>(key: &str, value: &T) -> Result<(), String> {
+    KvStore::default().put(key, value).await
+}
+
+/// Removes a key from the default [`KvStore`].
+pub async fn kv_delete(key: &str) -> Result<(), String> {
+    KvStore::default().delete(key).await
+}
+
+/// Lists the keys of the default [`KvStore`].
+pub async fn kv_list() -> Result<Vec<String>, String> {
+    KvStore::default().list().await
+}
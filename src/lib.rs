@@ -1,52 +1,286 @@
 use dioxus::core::use_drop;
 use dioxus::prelude::*;
-use dioxus_signals::{Readable, Writable};
+use dioxus_signals::{CopyValue, Readable, Writable};
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-// Only import wasm-specific modules when targeting wasm
-#[cfg(target_arch = "wasm32")]
-use gloo_utils::format::JsValueSerdeExt;
-#[cfg(all(target_arch = "wasm32", feature = "web"))]
-use js_sys;
-#[cfg(target_arch = "wasm32")]
-use serde_wasm_bindgen;
-#[cfg(target_arch = "wasm32")]
-use wasm_bindgen::{prelude::Closure, JsValue};
-#[cfg(target_arch = "wasm32")]
-use web_sys;
-
 // Import the android_bridge module
 #[cfg(target_os = "android")]
 mod android_bridge;
+#[cfg(target_os = "android")]
+pub use android_bridge::{on_jni_load, when_vm_loaded};
 
-// Always import uuid when the feature is enabled
-#[cfg(feature = "uuid")]
-use uuid;
+mod attachment;
+pub use attachment::{use_attachments, Attachment, AttachmentInfo, ATTACHMENT_PROTOCOL};
+mod audio;
+pub use audio::{use_audio, AudioCommand, AudioEngine, AudioEvent, PlayOptions};
+mod badge;
+pub use badge::{set_badge, set_title};
+mod barcode;
+pub use barcode::{scan_barcode, scan_barcode_with_options, Barcode, ScanOptions};
+mod binary;
+mod bootstrap;
+mod broadcast;
+pub use broadcast::{use_broadcast_channel, BroadcastChannel};
+mod canvas;
+pub use canvas::{use_canvas, CanvasBridge, CanvasConfig, CanvasContextKind, CanvasEvent, DrawCommand};
+mod capabilities;
+pub use capabilities::{Capabilities, PROTOCOL_VERSION};
+mod codec;
+pub use codec::{Codec, JsonCodec, PreparedMessage};
+mod color_scheme;
+pub use color_scheme::{use_color_scheme, ColorScheme, SystemTheme};
+mod cookies;
+pub use cookies::{get_cookie, set_cookie, CookieOptions, SameSite};
+mod core;
+pub use self::core::{RawBridge, INBOUND_QUEUE_JS};
+mod dead_letter;
+pub use dead_letter::{DeadLetter, MAX_DEAD_LETTERS};
+mod desktop_window;
+pub use desktop_window::{window_command, window_state, WindowCommand, WindowState};
+mod dialogs;
+pub use dialogs::{alert, confirm, prompt};
+mod displays;
+pub use displays::{get_displays, DisplayInfo};
+mod envelope;
+pub use envelope::{BridgeOptions, VersionPolicy, MAX_INFLATED_SIZE};
+mod external;
+pub use external::open_external;
+mod fetch;
+pub use fetch::{fetch_json, FetchError, FetchRequest, FetchResponse};
+mod file_drop;
+pub use file_drop::{use_file_drop, DroppedFile, DroppedFileInfo, FileDrop, FileDropEvent};
+mod frame;
+pub use frame::use_js_bridge_in_frame;
+mod fullscreen;
+pub use fullscreen::{
+    exit_fullscreen, exit_pointer_lock, request_fullscreen, request_pointer_lock, use_screen_mode,
+    ScreenMode, ScreenModeEvent,
+};
+mod function;
+pub use function::JsFunction;
+#[cfg(feature = "fuzzing")]
+mod fuzzing;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub use fuzzing::inbound as __fuzz;
+mod gamepad;
+pub use gamepad::{
+    use_gamepads, use_gamepads_with_options, GamepadButton, GamepadEvent, GamepadOptions, GamepadState,
+    Gamepads,
+};
+mod geometry;
+pub use geometry::{
+    get_bounding_rect, scroll_to, use_scroll_position, Rect, ScrollAlign, ScrollBehavior,
+    ScrollOptions, ScrollPosition, ScrollTracker,
+};
+mod geolocation;
+pub use geolocation::{
+    get_position, use_watch_position, GeoError, GeoOptions, GeoPosition, GeoWatch,
+};
+#[cfg(all(feature = "desktop", any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+mod global_shortcut;
+#[cfg(all(feature = "desktop", any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub use global_shortcut::{register_global_shortcut, GlobalShortcut, ShortcutState};
+mod history;
+pub use history::{history_go, push_state, replace_state, use_popstate, PopStateEvent};
+mod hotkeys;
+pub use hotkeys::{use_hotkey, use_hotkey_with_options, Hotkey, HotkeyEvent, HotkeyOptions};
+mod idle;
+pub use idle::{use_idle, IdleState, IdleStatus};
+mod ids;
+pub use ids::IdStrategy;
+mod image;
+pub use image::{
+    decode_image, encode_image, image_size, reencode_image, DecodedImage, EncodeOptions, ImageFormat,
+    ImageSize,
+};
+mod jni_exports;
+#[cfg(target_os = "android")]
+#[doc(hidden)]
+pub use jni_exports::android as __android;
+mod kv;
+pub use kv::{kv_delete, kv_get, kv_list, kv_put, KvStore};
+mod lifecycle;
+pub use lifecycle::{shutdown_all, SHUTDOWN_DEADLINE};
+mod locale;
+pub use locale::{get_locale_info, LocaleInfo};
+mod media;
+pub use media::{
+    use_media_capture, FacingMode, FrameKind, MediaCapture, MediaConstraints, MediaEvent, MediaFrame,
+};
+mod memo;
+pub use memo::use_js_memo;
+mod network;
+pub use network::{use_network_status, NetworkInfo, NetworkStatus};
+mod notifications;
+pub use notifications::{
+    request_notification_permission, use_notifications, NotificationEvent, NotificationOptions,
+    NotificationPermission, Notifications,
+};
+mod object_url;
+pub use object_url::{create_object_url, ObjectUrl};
+mod observe;
+pub use observe::MessageMeta;
+mod opened_files;
+pub use opened_files::{use_opened_files, OpenedFiles};
+mod origin;
+pub use origin::{MessageSource, OriginPolicy};
+mod page_lifecycle;
+pub use page_lifecycle::{use_page_lifecycle, PageLifecycle, PageState};
+mod patch;
+pub use patch::{json_diff, PatchOp};
+mod performance;
+pub use performance::{
+    clear_marks, mark, measure, navigation_timing, performance_now, rust_now_ms, sync_clock,
+    ClockSync, NavigationTiming,
+};
+mod permission_state;
+pub use permission_state::{query_permission, use_permission, PermissionState, PermissionStatus};
+mod permissions;
+pub use permissions::Permissions;
+mod pointer;
+pub use pointer::{
+    use_pointer_events, use_pointer_events_with_options, ActivePointer, PointerEvents, PointerInput,
+    PointerKind, PointerOptions, PointerSample,
+};
+#[cfg(target_arch = "wasm32")]
+mod port;
+#[cfg(target_arch = "wasm32")]
+pub use port::{use_js_port, JsPort};
+mod print;
+pub use print::{print_html, print_page, PrintOptions};
+mod retry;
+pub use retry::{is_transient, RetryPolicy};
+mod script;
+pub use script::install_script;
+mod secure_storage;
+pub use secure_storage::{secure_delete, secure_get, secure_set};
+mod sensors;
+pub use sensors::{
+    use_device_motion, use_device_orientation, DeviceMotion, DeviceOrientation, RotationRate, Sensor,
+    Vector3,
+};
+#[cfg(feature = "security")]
+mod security;
+#[cfg(feature = "security")]
+pub use security::Security;
+mod service_worker;
+pub use service_worker::{use_service_worker, ServiceWorker, ServiceWorkerEvent, ServiceWorkerState};
+mod share;
+pub use share::{share, ShareData, ShareFile};
+#[cfg(all(feature = "desktop", not(any(target_arch = "wasm32", target_os = "android"))))]
+mod single_instance;
+#[cfg(all(feature = "desktop", not(any(target_arch = "wasm32", target_os = "android"))))]
+pub use single_instance::{
+    single_instance, use_instance_launches, InstanceLaunch, InstanceLaunches, SingleInstance,
+};
+mod storage;
+pub use storage::{
+    use_local_storage, use_session_storage, use_storage, use_storage_with_error, StorageArea,
+};
+mod store;
+pub use store::{use_bridge_store, BridgeStore};
+mod streams;
+pub use streams::OutboundStream;
+mod synced;
+pub use synced::use_synced_signal;
+mod tagging;
+pub use tagging::EnumTagging;
+mod text_metrics;
+pub use text_metrics::{measure_text, use_fonts_ready, FontsReady, TextMetrics};
+mod throttle;
+pub use throttle::UpdatePolicy;
+mod trace;
+#[cfg(all(feature = "desktop", any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+mod tray;
+#[cfg(all(feature = "desktop", any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub use tray::{use_tray, Tray, TrayButton, TrayEvent, TrayImage, TrayMenuItem, TrayOptions};
+mod vibration;
+pub use vibration::{can_vibrate, vibrate};
+mod wake_lock;
+pub use wake_lock::{acquire_wake_lock, release_wake_lock};
+mod webrtc;
+pub use webrtc::{use_webrtc_peer, IceCandidate, RtcConfig, RtcEvent, RtcPeer, SessionDescription};
+mod watchdog;
+pub use watchdog::Watchdog;
+mod websocket;
+pub use websocket::{
+    use_js_websocket, use_js_websocket_text, JsWebSocket, WebSocketEvent, WebSocketState,
+};
+mod worker;
+pub use worker::{use_js_worker, JsWorker};
+
+/// Errors shared by the bridge's JS calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BridgeError {
+    /// The crate was built with the `stub` feature for a target without a webview.
+    Unsupported,
+}
+
+impl std::fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BridgeError::Unsupported => write!(f, "The JS bridge is not supported on this target"),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+impl From<BridgeError> for String {
+    fn from(error: BridgeError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Whether JS calls fail with [`BridgeError::Unsupported`] instead of reaching a webview.
+#[cfg(not(target_os = "android"))]
+const STUB: bool = cfg!(all(
+    feature = "stub",
+    not(any(target_arch = "wasm32", target_os = "android"))
+));
 
 pub trait FromJs: for<'de> Deserialize<'de> + 'static {}
 impl<T> FromJs for T where T: for<'de> Deserialize<'de> + 'static {}
 
+/// Decides from the raw JSON whether a message from JS is decoded at all.
+type InboundFilter = std::rc::Rc<dyn Fn(&serde_json::Value) -> bool>;
+
+/// A bridge receiving `T` from JS, with values encoded by the codec `C`.
 #[derive(Clone)]
-pub struct JsBridge<T: FromJs + Clone> {
+pub struct JsBridge<T: FromJs + Clone, C: Codec = JsonCodec> {
     pub data: Signal<Option<T>>,
     pub error: Signal<Option<String>>,
-    callback_id: Signal<String>,
+    /// The schema version JS announced in its handshake, if any.
+    pub peer_version: Signal<Option<u32>>,
+    peer_capabilities: Signal<Option<Capabilities>>,
+    /// Whether JS answered the watchdog's last ping. Stays `true` without a watchdog.
+    pub is_alive: Signal<bool>,
+    raw: RawBridge,
+    options: BridgeOptions,
+    /// The ID of the next message to JS, and of the last one accepted from JS.
+    message_ids: CopyValue<(u64, Option<u64>)>,
+    #[cfg(feature = "security")]
+    seal: Option<security::Seal>,
+    /// Proves that a message was wrapped by the listener for other frames.
+    origin_nonce: Option<String>,
+    /// Set for bridges to iframes, which messages to JS are posted to.
+    frame: Option<frame::Frame>,
+    handler: Option<Callback<T>>,
+    subscribers: CopyValue<Vec<UnboundedSender<T>>>,
+    observers: CopyValue<observe::Observers>,
+    filter: CopyValue<Option<InboundFilter>>,
+    /// The latest value not yet written to `data`, with a throttling update policy.
+    pending: CopyValue<Option<T>>,
+    /// The last value sent in delta mode, and the messages sent since the last snapshot.
+    deltas: CopyValue<(Option<serde_json::Value>, u32)>,
+    dead_letters: Signal<Vec<DeadLetter>>,
+    codec: std::marker::PhantomData<C>,
 }
 
-impl<T: FromJs + Clone> JsBridge<T> {
-    fn new(
-        data: Signal<Option<T>>,
-        error: Signal<Option<String>>,
-        callback_id: Signal<String>,
-    ) -> Self {
-        Self {
-            data,
-            error,
-            callback_id,
-        }
-    }
-
+impl<T: FromJs + Clone, C: Codec> JsBridge<T, C> {
     pub fn get_data(&self) -> Option<T> {
         self.data.read().clone()
     }
@@ -54,7 +288,7 @@ impl<T: FromJs + Clone> JsBridge<T> {
         self.error.read().clone()
     }
     pub fn callback_id(&self) -> String {
-        self.callback_id.read().clone()
+        self.raw.callback_id().to_string()
     }
     pub fn set_error(&mut self, error: Option<String>) {
         self.error.with_mut(|v| *v = error);
@@ -63,259 +297,765 @@ impl<T: FromJs + Clone> JsBridge<T> {
         self.data.with_mut(|v| *v = data);
     }
 
-    /// Rust → JS: Evaluate JS code (cross-platform via dioxus::html::document().eval)
-    pub async fn eval(&mut self, js_code: &str) -> Result<(), String> {
-        #[cfg(target_arch = "wasm32")]
-        {
-            dioxus::document::eval(js_code)
-                .await
-                .map(|_| ())
-                .map_err(|e| format!("JS eval error: {:?}", e))
+    /// The JS expression that delivers a value to this bridge; see [`RawBridge::js_callback`].
+    pub fn js_callback(&self) -> String {
+        self.raw.js_callback()
+    }
+
+    /// What JS announced it supports in its handshake, if it sent one.
+    pub fn peer_capabilities(&self) -> Option<Capabilities> {
+        self.peer_capabilities.read().clone()
+    }
+
+    /// What this side announces in the handshake.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities::local(&self.options, C::NAME)
+    }
+
+    /// Checks that JS answers and the bridge is still registered, returning the round
+    /// trip time. Waits as long as the watchdog's timeout, or 5 seconds without one.
+    pub async fn ping(&self) -> Result<std::time::Duration, String> {
+        let timeout = match &self.options.watchdog {
+            Some(watchdog) => watchdog.timeout,
+            None => watchdog::DEFAULT_PING_TIMEOUT,
+        };
+        self.raw.ping(timeout).await
+    }
+
+    /// Pings JS every interval of the watchdog, flipping `is_alive` as answers stop and
+    /// resume.
+    async fn watch(mut self, watchdog: Watchdog) {
+        loop {
+            watchdog::sleep(watchdog.interval).await;
+            let alive = match self.raw.probe(watchdog.timeout).await {
+                Ok((_, true)) => true,
+                // JS answered without the bridge: Android restarted the WebView renderer
+                #[cfg(target_os = "android")]
+                Ok(_) => {
+                    self.reconnect();
+                    true
+                }
+                _ => false,
+            };
+            if alive != *self.is_alive.peek() {
+                self.is_alive.set(alive);
+                if !alive {
+                    watchdog.disconnected();
+                }
+            }
         }
-        
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            // For non-WASM targets, we need to handle this differently
+    }
+
+    /// Runs the page-side setup of the bridge: envelope helpers, keys, and listeners for
+    /// other frames. The callback itself is installed by [`RawBridge::listen`].
+    fn install_scripts(&self) {
+        #[cfg(feature = "tracing")]
+        bootstrap::inject_once("trace", trace::JS_TRACE);
+        if self.options.delta_resync.is_some() {
+            bootstrap::inject_once("patch", patch::JS_PATCH);
+        }
+        if self.options.uses_envelope() {
+            bootstrap::inject_once("open", envelope::JS_OPEN);
+        }
+        if self.options.stamps_ids() {
+            bootstrap::inject_once("dedup", envelope::JS_DEDUP);
+        }
+        if self.options.enum_tagging != EnumTagging::External {
+            bootstrap::inject_once("enums", tagging::JS_ENUMS);
+            eval_detached(&self.options.enum_tagging.install_script(&self.callback_id()));
+        }
+        #[cfg(feature = "security")]
+        if let Some(seal) = &self.seal {
+            bootstrap::inject_once("security", security::JS_SECURITY);
+            eval_detached(&seal.install_script(&self.callback_id()));
+        }
+        if let Some(nonce) = &self.origin_nonce {
             #[cfg(target_os = "android")]
-            {
-                // For Android, we'll use the JNI bridge to evaluate JS
-                self.eval_android(js_code).await
-            }
-            
-            #[cfg(not(target_os = "android"))]
-            {
-                // For Desktop, we can use dioxus::document::eval
-                dioxus::document::eval(js_code)
-                    .await
-                    .map(|_| ())
-                    .map_err(|e| format!("JS eval error: {:?}", e))
-            }
+            android_bridge::check_origins(&self.callback_id());
+            let frames = self.frame.as_ref().map(|frame| frame.elements());
+            eval_detached(&origin::listener_script(
+                &self.callback_id(),
+                &self.js_callback(),
+                nonce,
+                frames.as_deref(),
+            ));
+        }
+        if let Some(frame) = &self.frame {
+            eval_detached(&frame.attach_script(&self.callback_id()));
         }
     }
 
+    /// Sets the bridge up again in a fresh JS context. JS starts over with message IDs
+    /// and has to repeat its handshake.
     #[cfg(target_os = "android")]
-    async fn eval_android(&mut self, js_code: &str) -> Result<(), String> {
-        use crate::android_bridge;
-        
-        // Send the JavaScript code to be evaluated on the Android side
-        android_bridge::eval_js(js_code).await
+    fn reconnect(&mut self) {
+        bootstrap::new_document();
+        self.raw.inject();
+        script::reinstall_all();
+        self.install_scripts();
+        self.message_ids.write().1 = None;
+        self.peer_version.set(None);
+        self.peer_capabilities.set(None);
     }
 
-    pub async fn send_to_js<S: Serialize>(&mut self, data: &S) -> Result<(), String> {
-        let json_data =
-            serde_json::to_string(data).map_err(|e| format!("Serialization error: {}", e))?;
-        
-        // Platform-specific implementations
-        #[cfg(target_arch = "wasm32")]
-        {
-            let js_code = format!(
-                "if (window.__dioxus_bridge_{}) {{ window.__dioxus_bridge_{}({}); }}",
-                self.callback_id(),
-                self.callback_id(),
-                json_data
-            );
-            self.eval(&js_code).await
-        }
-        
-        #[cfg(target_os = "android")]
-        {
-            // For Android, use the JNI bridge
-            self.send_to_js_android(&json_data).await
+    /// Stops accepting sends, waits up to [`SHUTDOWN_DEADLINE`] for sends in flight, then
+    /// unregisters the callback.
+    pub async fn shutdown(&self) {
+        self.raw.shutdown(SHUTDOWN_DEADLINE).await
+    }
+
+    /// Calls `observer` after every message sent to JS, e.g. for analytics. Observers are
+    /// shared by clones of the bridge, so register them once, in `use_hook`.
+    pub fn on_send(&self, observer: impl Fn(&MessageMeta) + 'static) {
+        let mut observers = self.observers;
+        observers.write().on_send.push(std::rc::Rc::new(observer));
+    }
+
+    /// Calls `observer` after every message from JS is decoded, or fails to decode.
+    pub fn on_receive(&self, observer: impl Fn(&MessageMeta) + 'static) {
+        let mut observers = self.observers;
+        observers.write().on_receive.push(std::rc::Rc::new(observer));
+    }
+
+    /// Drops messages from JS for which `filter` returns `false`, before they are decoded,
+    /// e.g. to sample a stream of `mousemove` events cheaply. Replaces any earlier filter;
+    /// clones of the bridge share it.
+    pub fn set_filter(&self, filter: impl Fn(&serde_json::Value) -> bool + 'static) {
+        let mut current = self.filter;
+        current.set(Some(std::rc::Rc::new(filter)));
+    }
+
+    /// Removes the filter from [`JsBridge::set_filter`].
+    pub fn clear_filter(&self) {
+        let mut current = self.filter;
+        current.set(None);
+    }
+
+    /// Messages to JS that failed, after any retries, with the reason. Reading them
+    /// subscribes the component to new ones.
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.read().clone()
+    }
+
+    /// Removes and returns the dead letters, e.g. to resend them.
+    pub fn take_dead_letters(&mut self) -> Vec<DeadLetter> {
+        std::mem::take(&mut *self.dead_letters.write())
+    }
+
+    fn dead_letter(&mut self, json_data: &str, reason: String) {
+        let mut dead_letters = self.dead_letters.write();
+        if dead_letters.len() >= MAX_DEAD_LETTERS {
+            dead_letters.remove(0);
         }
-        
-        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
-        {
-            // For Desktop
-            let js_code = format!(
-                "if (window.__dioxus_bridge_{}) {{ window.__dioxus_bridge_{}({}); }}",
-                self.callback_id(),
-                self.callback_id(),
-                json_data
-            );
-            self.eval(&js_code).await
+        dead_letters.push(DeadLetter::new(json_data, reason));
+    }
+
+    /// Sends without waiting, from places where no task can be spawned. Failures become
+    /// dead letters.
+    fn send_detached(&mut self, json_data: &str) {
+        use futures_util::FutureExt;
+        if let Some(Err(e)) = self.deliver_once(json_data).now_or_never() {
+            self.dead_letter(json_data, e);
         }
     }
 
-    #[cfg(target_os = "android")]
-    async fn send_to_js_android(&mut self, json_data: &str) -> Result<(), String> {
-        use crate::android_bridge;
-        
-        // Create a message that includes the callback ID and data
-        let message = format!(
-            "{{\"callback_id\":\"{}\",\"data\":{}}}",
-            self.callback_id(),
-            json_data
-        );
-        
-        // Send the message to Java/Kotlin via the JNI bridge
-        android_bridge::send_to_java(message).await
+    /// Binds the JS function at `path`, e.g. `"window.game.spawn"`, for typed calls without
+    /// formatting eval strings. Fails if it doesn't exist yet.
+    pub async fn bind_function<Args: Serialize, Ret: FromJs>(
+        &self,
+        path: &str,
+    ) -> Result<JsFunction<Args, Ret>, String> {
+        JsFunction::bind(path).await
     }
-}
 
-pub fn use_js_bridge<T>() -> JsBridge<T>
-where
-    T: FromJs + Clone + Debug + 'static,
-{
-    let data: Signal<Option<T>> = use_signal(|| None);
-    let error: Signal<Option<String>> = use_signal(|| None);
+    /// Makes `bytes` loadable by the page as a `blob:` URL; see [`create_object_url`].
+    pub async fn create_object_url(
+        &self,
+        bytes: &[u8],
+        mime_type: &str,
+    ) -> Result<ObjectUrl, String> {
+        create_object_url(bytes, mime_type).await
+    }
 
-    // Generate callback_id in a platform-specific way
-    let callback_id = use_signal(|| {
-        #[cfg(feature = "uuid")]
-        {
-            uuid::Uuid::new_v4().to_string().replace("-", "_")
+    /// Opens a stream of items to the JS consumer registered with
+    /// `window.dxStreams.consume(name, handler)`, encoded with this bridge's codec.
+    pub fn open_stream<S: Serialize>(&self, name: &str) -> OutboundStream<S, C> {
+        OutboundStream::open(name)
+    }
+
+    /// Installs a JS helper once per document; see [`install_script`].
+    pub async fn install_script(&self, source: &str) -> Result<(), String> {
+        install_script(source).await
+    }
+
+    /// The transport behind this bridge.
+    pub fn raw(&self) -> &RawBridge {
+        &self.raw
+    }
+
+    /// Returns a stream of every value received from JS after this call.
+    pub fn stream(&self) -> UnboundedReceiver<T> {
+        let (tx, rx) = futures_channel::mpsc::unbounded();
+        let mut subscribers = self.subscribers;
+        subscribers.write().push(tx);
+        rx
+    }
+
+    /// Stores a value received from JS and forwards it to the message handler and
+    /// streams, if any.
+    fn receive(&mut self, value: T) {
+        match self.options.update_policy {
+            UpdatePolicy::Immediate => {
+                self.set_data(Some(value.clone()));
+                self.set_error(None);
+            }
+            policy => self.coalesce(value.clone(), policy),
         }
-        #[cfg(all(target_arch = "wasm32", not(feature = "uuid")))]
-        {
-            // This code only compiles for WASM targets
-            let random_part: String = js_sys::Math::random().to_string().chars().skip(2).collect();
-            format!("callback_{}_{}", js_sys::Date::now(), random_part)
+        self.subscribers
+            .write()
+            .retain(|tx| tx.unbounded_send(value.clone()).is_ok());
+        if let Some(handler) = self.handler {
+            handler.call(value);
         }
-        #[cfg(not(any(target_arch = "wasm32", feature = "uuid")))]
-        {
-            // For non-WASM targets without uuid feature
-            use std::time::{SystemTime, UNIX_EPOCH};
-            let timestamp = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis();
-            format!("callback_{}", timestamp)
+    }
+
+    /// Keeps `value` as the latest one, and writes it to `data` once `policy` allows.
+    fn coalesce(&mut self, value: T, policy: UpdatePolicy) {
+        let mut pending = self.pending;
+        let scheduled = pending.write().replace(value).is_some();
+        if !scheduled {
+            let mut bridge = self.clone();
+            spawn(async move {
+                throttle::wait(policy).await;
+                if let Some(value) = pending.write().take() {
+                    bridge.set_data(Some(value));
+                    bridge.set_error(None);
+                }
+            });
         }
-    });
+    }
+
+    /// Decodes a JSON value received from JS with the bridge's codec, unwrapping the
+    /// envelope first when the options call for one.
+    fn receive_json_value(&mut self, value: serde_json::Value) {
+        let (value, source) = match (&self.options.origin_policy, &self.origin_nonce) {
+            (Some(policy), Some(nonce)) => match origin::unwrap(value, nonce) {
+                Ok((value, source)) if policy.allows(&source) => (value, source),
+                Ok((_, source)) => {
+                    return self.set_error(Some(format!("Rejected message from {}", source)))
+                }
+                Err(e) => return self.set_error(Some(e)),
+            },
+            _ => (value, origin::same_document()),
+        };
+        if !self.options.uses_envelope() {
+            return self.admit(value, &source, None, None);
+        }
+        let envelope = match envelope::Envelope::parse(value) {
+            Ok(envelope) if envelope.ack => return,
+            Ok(envelope) => envelope,
+            Err(e) => return self.set_error(Some(e)),
+        };
+        // Verify protected messages, handshakes included, before anything else about them
+        // is trusted
+        #[cfg(feature = "security")]
+        let text = match &self.seal {
+            Some(seal) => match seal.unseal(&envelope) {
+                Ok(text) => Some(text),
+                Err(e) => return self.set_error(Some(e)),
+            },
+            None => None,
+        };
+        #[cfg(not(feature = "security"))]
+        let text: Option<String> = None;
 
-    let bridge = JsBridge::new(data.clone(), error.clone(), callback_id.clone());
+        if self.options.stamps_ids() {
+            let mut message_ids = self.message_ids;
+            let admitted =
+                envelope::admit_id(&mut message_ids.write().1, envelope.id, text.is_some());
+            match admitted {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => return self.set_error(Some(e)),
+            }
+        }
 
-    // --- Web: Register JS callback ---
-    #[cfg(target_arch = "wasm32")]
-    {
-        let mut bridge_for_effect = bridge.clone();
-        use_effect(move || {
-            let callback_id_str = bridge_for_effect.callback_id();
-            let mut bridge_for_callback = bridge_for_effect.clone();
-            let callback = Closure::<dyn FnMut(JsValue)>::new(move |val: JsValue| {
-                // Try to deserialize directly using serde-wasm-bindgen
-                match val.into_serde() {
-                    Ok(parsed) => {
-                        bridge_for_callback.set_data(Some(parsed));
-                        bridge_for_callback.set_error(None);
-                        return;
-                    }
-                    Err(_) => {
-                        // If direct deserialization fails, try to convert to string first
-                        let js_string = js_sys::JsString::from(val);
-                        let rust_string = String::from(js_string);
-                        match serde_json::from_str::<T>(&rust_string) {
-                            Ok(parsed) => {
-                                bridge_for_callback.set_data(Some(parsed));
-                                bridge_for_callback.set_error(None);
-                                return;
-                            }
-                            Err(e) => bridge_for_callback
-                                .set_error(Some(format!("Deserialization error: {e}"))),
-                        }
-                    }
+        let mismatch = self
+            .options
+            .schema_version
+            .filter(|version| envelope.v != Some(*version))
+            .map(|version| envelope::mismatch_error(version, envelope.v));
+        if envelope.handshake {
+            // A protected handshake carries its capabilities as the sealed data
+            let capabilities = match &text {
+                Some(text) => match serde_json::from_str(text) {
+                    Ok(capabilities) => Some(capabilities),
+                    Err(e) => return self.set_error(Some(format!("Invalid capabilities: {e}"))),
+                },
+                None => envelope.capabilities.clone(),
+            };
+            self.peer_version.set(envelope.v);
+            self.peer_capabilities.set(capabilities);
+            let id = self.next_message_id();
+            let reply =
+                envelope::handshake(self.options.schema_version, id, &self.capabilities());
+            self.send_detached(&reply);
+        }
+        if let Some(mismatch) = &mismatch {
+            match self.options.version_policy {
+                VersionPolicy::Reject => return self.set_error(Some(mismatch.clone())),
+                VersionPolicy::Warn => eprintln!("{}", mismatch),
+                VersionPolicy::BestEffort => {}
+            }
+        }
+        if !envelope.handshake {
+            let id = envelope.id;
+            match envelope.into_data(text, self.options.max_message_size) {
+                Ok(data) => self.admit(data, &source, id, mismatch),
+                Err(e) => self.set_error(Some(e)),
+            }
+        }
+    }
+
+    /// Decodes a message once the bridge's permissions allow its topic.
+    fn admit(
+        &mut self,
+        value: serde_json::Value,
+        source: &MessageSource,
+        message_id: Option<u64>,
+        mismatch: Option<String>,
+    ) {
+        let filter = self.filter.read().clone();
+        if filter.is_some_and(|filter| !filter(&value)) {
+            return;
+        }
+        let started = rust_now_ms();
+        let observers = self.observers.read().on_receive.clone();
+        let size = if observers.is_empty() { 0 } else { value.to_string().len() };
+        let raw = self.raw.clone();
+        let ok = trace::in_span(trace::Stage::Receive, raw.callback_id(), message_id, || {
+            if let Some(permissions) = &self.options.permissions {
+                if let Err(e) = permissions.check(&value, source, &self.options.enum_tagging) {
+                    self.set_error(Some(e));
+                    return false;
                 }
-            });
-            let window = web_sys::window().expect("no global window");
-            let callback_name = format!("__dioxus_bridge_{}", callback_id_str);
-            js_sys::Reflect::set(&window, &callback_name.into(), callback.as_ref())
-                .expect("failed to set callback");
-            callback.forget();
-        });
-        let bridge_for_destroy = bridge.clone();
-        use_drop(move || {
-            if let Some(window) = web_sys::window() {
-                let callback_name = format!("__dioxus_bridge_{}", bridge_for_destroy.callback_id());
-                let _ = js_sys::Reflect::delete_property(&window, &callback_name.into());
             }
+            trace::in_span(trace::Stage::Deserialize, raw.callback_id(), message_id, || {
+                self.decode(value, mismatch)
+            })
         });
+        if !observers.is_empty() {
+            let meta = MessageMeta {
+                callback_id: self.callback_id(),
+                message_id,
+                size,
+                type_name: std::any::type_name::<T>(),
+                duration: observe::elapsed(started),
+                ok,
+            };
+            observers.iter().for_each(|observer| observer(&meta));
+        }
+    }
+
+    /// Takes the next outbound message ID, if the bridge stamps messages.
+    fn next_message_id(&self) -> Option<u64> {
+        if !self.options.stamps_ids() {
+            return None;
+        }
+        let mut message_ids = self.message_ids;
+        let mut ids = message_ids.write();
+        ids.0 += 1;
+        Some(ids.0)
+    }
+
+    /// Delivers a decoded value, returning whether decoding succeeded.
+    fn decode(&mut self, value: serde_json::Value, mismatch: Option<String>) -> bool {
+        let decoded = match &self.options.enum_tagging {
+            EnumTagging::External => C::decode::<T>(value),
+            tagging => C::decode::<serde_json::Value>(value).and_then(|v| tagging.from_value(v)),
+        };
+        match decoded {
+            Ok(parsed) => {
+                self.receive(parsed);
+                true
+            }
+            Err(e) => {
+                self.set_error(Some(match mismatch {
+                    Some(mismatch) => format!("{mismatch}: {e}"),
+                    None => e,
+                }));
+                false
+            }
+        }
+    }
+
+    /// Rust → JS: Evaluate JS code (cross-platform via dioxus::html::document().eval)
+    pub async fn eval(&mut self, js_code: &str) -> Result<(), String> {
+        retry::run(self.options.retry.as_ref(), || self.raw.eval(js_code)).await
+    }
+
+    pub async fn send_to_js<S: Serialize>(&mut self, data: &S) -> Result<(), String> {
+        let started = rust_now_ms();
+        let json_data = trace::in_span(trace::Stage::Serialize, self.raw.callback_id(), None, || {
+            match &self.options.enum_tagging {
+                EnumTagging::External => C::encode(data),
+                tagging => C::encode(&tagging.to_value(data)?),
+            }
+        })?;
+        let type_name = std::any::type_name::<S>();
+        let Some(resync) = self.options.delta_resync else {
+            return self.send_json(&json_data, Vec::new(), type_name, started).await;
+        };
+        let (delta_data, kind) = self.delta(&json_data, resync)?;
+        let fields = vec![("delta", js_string(kind))];
+        let result = self.send_json(&delta_data, fields, type_name, started).await;
+        if result.is_err() {
+            // JS may not have the last value, so the next message is a full snapshot
+            let mut deltas = self.deltas;
+            *deltas.write() = (None, 0);
+        }
+        result
+    }
+
+    /// Encodes a value for the delta mode: a JSON Patch against the last value sent, or a
+    /// full snapshot every `resync` messages.
+    fn delta(&self, json_data: &str, resync: u32) -> Result<(String, &'static str), String> {
+        let value: serde_json::Value =
+            serde_json::from_str(json_data).map_err(|e| format!("Serialization error: {}", e))?;
+        let mut deltas = self.deltas;
+        let mut state = deltas.write();
+        let encoded = match &state.0 {
+            Some(last) if state.1 < resync => {
+                let patch = serde_json::to_string(&json_diff(last, &value))
+                    .map_err(|e| format!("Serialization error: {}", e))?;
+                state.1 += 1;
+                (patch, "patch")
+            }
+            _ => {
+                state.1 = 1;
+                (json_data.to_string(), "snapshot")
+            }
+        };
+        state.0 = Some(value);
+        Ok(encoded)
+    }
+
+    /// Sends a payload that was encoded once for many bridges. It should be encoded with
+    /// this bridge's codec, and isn't retagged for its
+    /// [`enum_tagging`](BridgeOptions::enum_tagging).
+    pub async fn send_prepared(&mut self, message: &PreparedMessage) -> Result<(), String> {
+        let type_name = std::any::type_name::<PreparedMessage>();
+        self.send_json(message.as_json(), Vec::new(), type_name, rust_now_ms()).await
+    }
+
+    /// Forwards JSON that is already serialized, e.g. a server response held as a
+    /// `Box<RawValue>`, without parsing it. Like [`JsBridge::send_prepared`], it skips the
+    /// codec and enum tagging; envelopes are still applied.
+    pub async fn send_raw_json(
+        &mut self,
+        json: &serde_json::value::RawValue,
+    ) -> Result<(), String> {
+        let type_name = std::any::type_name::<serde_json::value::RawValue>();
+        self.send_json(json.get(), Vec::new(), type_name, rust_now_ms()).await
     }
 
-    // --- Desktop: Register JS callback (Wry) ---
-    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    /// Sends encoded JSON and tells the `on_send` observers about it.
+    async fn send_json(
+        &mut self,
+        json_data: &str,
+        fields: envelope::Fields,
+        type_name: &'static str,
+        started: f64,
+    ) -> Result<(), String> {
+        let result = self.transmit(json_data, fields).await;
+        if let Err(e) = &result {
+            self.dead_letter(json_data, e.clone());
+        }
+        let observers = self.observers.read().on_send.clone();
+        if !observers.is_empty() {
+            let meta = MessageMeta {
+                callback_id: self.callback_id(),
+                message_id: result.as_ref().ok().copied().flatten(),
+                size: json_data.len(),
+                type_name,
+                duration: observe::elapsed(started),
+                ok: result.is_ok(),
+            };
+            observers.iter().for_each(|observer| observer(&meta));
+        }
+        result.map(|_| ())
+    }
+
+    /// Wraps and delivers encoded JSON with the given envelope fields, returning the
+    /// message ID it was stamped with.
+    async fn transmit(
+        &mut self,
+        json_data: &str,
+        mut fields: envelope::Fields,
+    ) -> Result<Option<u64>, String> {
+        let limit = self
+            .peer_capabilities
+            .read()
+            .as_ref()
+            .and_then(|capabilities| capabilities.max_message_size);
+        if let Some(limit) = limit.filter(|limit| json_data.len() > *limit) {
+            return Err(format!(
+                "Message of {} bytes exceeds the {} bytes JS accepts",
+                json_data.len(),
+                limit
+            ));
+        }
+        if !self.options.uses_envelope() {
+            let send = self.deliver(json_data);
+            trace::instrument(trace::Stage::Send, self.raw.callback_id(), None, send).await?;
+            return Ok(None);
+        }
+        let id = self.next_message_id();
+        let (protection, data) = self.protect(json_data, id)?;
+        fields.extend(protection);
+        let wrapped = envelope::wrap(self.options.schema_version, id, &fields, &data);
+        trace::instrument(
+            trace::Stage::Send,
+            self.raw.callback_id(),
+            id,
+            self.deliver(&wrapped),
+        )
+        .await?;
+        Ok(id)
+    }
+
+    /// Hands a message to the page, or posts it to the bridge's iframes, retrying as the
+    /// options allow.
+    async fn deliver(&self, json_data: &str) -> Result<(), String> {
+        retry::run(self.options.retry.as_ref(), || self.deliver_once(json_data)).await
+    }
+
+    async fn deliver_once(&self, json_data: &str) -> Result<(), String> {
+        match &self.frame {
+            Some(frame) => {
+                let _in_flight = lifecycle::InFlight::start(self.raw.callback_id())?;
+                self.raw
+                    .eval(&frame.send_script(self.raw.callback_id(), json_data))
+                    .await
+            }
+            None => self.raw.send_json(json_data).await,
+        }
+    }
+
+    /// Compresses and signs or encrypts an outbound message as the options call for,
+    /// returning the envelope fields to add and the JSON to carry as `data`.
+    #[allow(unused_mut, unused_variables)]
+    fn protect(
+        &self,
+        json_data: &str,
+        id: Option<u64>,
+    ) -> Result<(envelope::Fields, String), String> {
+        let mut fields = Vec::new();
+        let mut text: Option<String> = None;
+        #[cfg(feature = "compression")]
+        if self.options.compress_above.is_some_and(|limit| json_data.len() > limit)
+            && Capabilities::decodes(self.peer_capabilities.read().as_ref(), "gzip")
+        {
+            text = Some(envelope::gzip(json_data)?);
+            fields.push(("z", js_string("gzip")));
+        }
+        #[cfg(feature = "security")]
+        if let Some(seal) = &self.seal {
+            let payload = text.as_deref().unwrap_or(json_data);
+            let (field, value, sealed) = seal.seal(payload, self.options.schema_version, id)?;
+            fields.push((field, js_string(&value)));
+            text = Some(sealed);
+        }
+        let data = match text {
+            Some(text) => js_string(&text),
+            None => json_data.to_string(),
+        };
+        Ok((fields, data))
+    }
+}
+
+/// Evaluates JS code and deserializes the value it returns.
+///
+/// The code runs as the body of an async function, so `return` and `await` work the same
+/// on every platform.
+pub async fn eval_as<R: FromJs>(js_code: &str) -> Result<R, String> {
+    #[cfg(target_os = "android")]
     {
-        let mut bridge_for_effect = bridge.clone();
-        use_effect(move || {
-            // For Dioxus Desktop, inject a JS callback in your HTML or via eval.
-            let callback_id_str = bridge_for_effect.callback_id();
-            let js_code = format!(
-                "window.__dioxus_bridge_{} = function(data) {{
-                    if (window.__dioxus_bridge_callback) {{
-                        window.__dioxus_bridge_callback('{}', JSON.stringify(data));
-                    }}
-                }}",
-                callback_id_str, callback_id_str
-            );
-            
-            // Clone the bridge before moving it into the closure
-            let mut bridge_clone = bridge_for_effect.clone();
-            spawn(async move {
-                if let Err(e) = bridge_clone.eval(&js_code).await {
-                    eprintln!("Failed to inject desktop bridge function: {}", e);
-                }
-            });
-        });
+        let value = android_bridge::eval_js_with_result(js_code).await?;
+        serde_json::from_value(value).map_err(|e| format!("Deserialization error: {}", e))
     }
 
-    // --- Android: Register JNI callback with channel to main thread ---
+    #[cfg(not(target_os = "android"))]
+    {
+        if cfg!(all(feature = "ssr", not(target_arch = "wasm32"))) {
+            return Err(SSR_UNAVAILABLE.to_string());
+        }
+        if STUB {
+            return Err(BridgeError::Unsupported.into());
+        }
+        dioxus::document::eval(js_code)
+            .join::<R>()
+            .await
+            .map_err(|e| format!("JS eval error: {:?}", e))
+    }
+}
+
+/// Runs JS without waiting for it to finish, for places like `use_drop` where no task
+/// can be spawned anymore.
+pub(crate) fn eval_detached(js_code: &str) {
     #[cfg(target_os = "android")]
     {
-        use crate::android_bridge::{register_callback, unregister_callback};
-        use std::sync::mpsc::channel;
+        android_bridge::eval_js_detached(js_code);
+    }
 
-        let (tx, rx) = channel::<String>();
-        let callback_id_str = bridge.callback_id();
+    #[cfg(not(target_os = "android"))]
+    {
+        if !STUB && !cfg!(all(feature = "ssr", not(target_arch = "wasm32"))) {
+            let _ = dioxus::document::eval(js_code);
+        }
+    }
+}
 
-        register_callback(
-            callback_id_str.clone(),
-            move |json: String| {
-                let _ = tx.send(json);
-            },
-        );
-
-        let mut data = data.clone();
-        let mut error = error.clone();
-        use_effect(move || {
-            while let Ok(json) = rx.try_recv() {
-                match serde_json::from_str::<T>(&json) {
-                    Ok(parsed) => {
-                        data.with_mut(|v| *v = Some(parsed));
-                        error.with_mut(|v| *v = None);
-                    }
-                    Err(e) => {
-                        error.with_mut(|v| *v = Some(format!("Deserialization error: {e}")));
-                    }
-                }
-            }
-        });
+/// Returned by calls that need a result from JS while rendering on the server.
+#[cfg(not(target_os = "android"))]
+const SSR_UNAVAILABLE: &str = "JS is not available during server rendering";
 
-        // Also inject a JS function for Android
-        let mut bridge_for_effect = bridge.clone();
-        use_effect(move || {
-            let callback_id_str = bridge_for_effect.callback_id();
-            let js_code = format!(
-                "window.__dioxus_bridge_{} = function(data) {{
-                    if (window.RustBridge) {{
-                        window.RustBridge.postMessage('{}', JSON.stringify(data));
-                    }}
-                }}",
-                callback_id_str, callback_id_str
-            );
-            
-            // Clone the bridge before moving it into the closure and make it mutable
-            let mut bridge_clone = bridge_for_effect.clone();
-            spawn(async move {
-                if let Err(e) = bridge_clone.eval(&js_code).await {
-                    eprintln!("Failed to inject android bridge function: {}", e);
+/// Encodes a Rust string as a JS string literal.
+pub(crate) fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+pub fn use_js_bridge<T>() -> JsBridge<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    use_js_bridge_inner(None, BridgeOptions::default(), None)
+}
+
+/// Like [`use_js_bridge`], with values encoded by the codec `C` instead of plain JSON.
+pub fn use_js_bridge_with_codec<T, C>() -> JsBridge<T, C>
+where
+    T: FromJs + Clone + Debug + 'static,
+    C: Codec,
+{
+    use_js_bridge_inner(None, BridgeOptions::default(), None)
+}
+
+/// Like [`use_js_bridge`], with the given options. They are read on the first render only.
+pub fn use_js_bridge_with_options<T>(options: BridgeOptions) -> JsBridge<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    use_js_bridge_inner(None, options, None)
+}
+
+/// Like [`use_js_bridge`], but also calls `handler` for every value received from JS.
+///
+/// `data` only holds the latest value, so several messages arriving before the next
+/// render would otherwise be coalesced. Use this hook for event streams.
+pub fn use_js_listener<T>(handler: impl FnMut(T) + 'static) -> JsBridge<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    let handler = use_callback(handler);
+    use_js_bridge_inner(Some(handler), BridgeOptions::default(), None)
+}
+
+pub(crate) fn use_js_bridge_inner<T, C>(
+    handler: Option<Callback<T>>,
+    options: BridgeOptions,
+    frame: Option<frame::Frame>,
+) -> JsBridge<T, C>
+where
+    T: FromJs + Clone + Debug + 'static,
+    C: Codec,
+{
+    let data: Signal<Option<T>> = use_signal(|| None);
+    let error: Signal<Option<String>> = use_signal(|| None);
+
+    let peer_version: Signal<Option<u32>> = use_signal(|| None);
+    let peer_capabilities: Signal<Option<Capabilities>> = use_signal(|| None);
+    let is_alive = use_signal(|| true);
+    let options = use_hook(|| options);
+    let raw = use_hook(|| RawBridge::with_strategy(&options.id_strategy));
+    let message_ids = use_hook(|| CopyValue::new((0, None)));
+    #[cfg(feature = "security")]
+    let seal = use_hook(|| {
+        options
+            .security
+            .and_then(|mode| match security::Seal::generate(mode) {
+                Ok(seal) => Some(seal),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    None
                 }
-            });
-        });
+            })
+    });
+    let origin_nonce = use_hook(|| options.origin_policy.as_ref().map(|_| ids::random_id()));
+    let frame = use_hook(|| frame);
+    let subscribers = use_hook(|| CopyValue::new(Vec::new()));
+    let observers = use_hook(|| CopyValue::new(observe::Observers::default()));
+    let filter = use_hook(|| CopyValue::new(None));
+    let pending = use_hook(|| CopyValue::new(None));
+    let deltas = use_hook(|| CopyValue::new((None, 0)));
+    let dead_letters = use_signal(Vec::new);
+    let bridge = JsBridge {
+        data,
+        error,
+        peer_version,
+        peer_capabilities,
+        is_alive,
+        raw,
+        options,
+        message_ids,
+        #[cfg(feature = "security")]
+        seal,
+        origin_nonce,
+        frame,
+        handler,
+        subscribers,
+        observers,
+        filter,
+        pending,
+        deltas,
+        dead_letters,
+        codec: std::marker::PhantomData,
+    };
 
-        let callback_id = bridge.callback_id();
-        use_drop(move || {
-            unregister_callback(&callback_id);
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        use futures_util::StreamExt;
+
+        let mut messages = bridge_for_effect.raw.listen();
+        bridge_for_effect.install_scripts();
+        if let Some(watchdog) = bridge_for_effect.options.watchdog.clone() {
+            spawn(bridge_for_effect.clone().watch(watchdog));
+        }
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            let yields = bridge.options.yield_between_messages;
+            loop {
+                if yields {
+                    watchdog::sleep(std::time::Duration::ZERO).await;
+                }
+                let Some(message) = messages.next().await else {
+                    break;
+                };
+                match message {
+                    Ok(value) => bridge.receive_json_value(value),
+                    Err(e) => bridge.set_error(Some(e)),
+                }
+            }
         });
-    }
+    });
+    let raw_for_drop = bridge.raw.clone();
+    let listens_to_frames = bridge.origin_nonce.is_some();
+    use_drop(move || {
+        if listens_to_frames {
+            eval_detached(&origin::remove_listener_script(raw_for_drop.callback_id()));
+        }
+        raw_for_drop.close();
+    });
 
     bridge
-}
\ No newline at end of file
+}
@@ -0,0 +1,116 @@
+use crate::RawBridge;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long [`shutdown_all`] and [`JsBridge::shutdown`](crate::JsBridge::shutdown) wait for
+/// sends in flight.
+pub const SHUTDOWN_DEADLINE: Duration = Duration::from_secs(2);
+
+/// Which bridges are listening, how many sends each has in flight, and which were shut down.
+struct Lifecycle {
+    listening: BTreeSet<String>,
+    in_flight: BTreeMap<String, usize>,
+    shut_down: BTreeSet<String>,
+    all_shut_down: bool,
+}
+
+static LIFECYCLE: Mutex<Lifecycle> = Mutex::new(Lifecycle {
+    listening: BTreeSet::new(),
+    in_flight: BTreeMap::new(),
+    shut_down: BTreeSet::new(),
+    all_shut_down: false,
+});
+
+fn with_lifecycle<R>(f: impl FnOnce(&mut Lifecycle) -> R) -> R {
+    let mut lifecycle = LIFECYCLE.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut lifecycle)
+}
+
+pub(crate) fn is_shut_down(callback_id: &str) -> bool {
+    with_lifecycle(|lifecycle| lifecycle.all_shut_down || lifecycle.shut_down.contains(callback_id))
+}
+
+pub(crate) fn listening(callback_id: &str) {
+    with_lifecycle(|lifecycle| lifecycle.listening.insert(callback_id.to_string()));
+}
+
+/// Forgets the bridge, so a later bridge given the freed ID doesn't start out shut down.
+pub(crate) fn closed(callback_id: &str) {
+    with_lifecycle(|lifecycle| {
+        lifecycle.listening.remove(callback_id);
+        lifecycle.shut_down.remove(callback_id);
+    });
+}
+
+/// Counts a send as in flight until dropped.
+pub(crate) struct InFlight(String);
+
+impl InFlight {
+    /// Fails once the bridge was shut down.
+    pub fn start(callback_id: &str) -> Result<Self, String> {
+        with_lifecycle(|lifecycle| {
+            if lifecycle.all_shut_down || lifecycle.shut_down.contains(callback_id) {
+                return Err("The bridge was shut down".to_string());
+            }
+            *lifecycle
+                .in_flight
+                .entry(callback_id.to_string())
+                .or_default() += 1;
+            Ok(Self(callback_id.to_string()))
+        })
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        with_lifecycle(|lifecycle| {
+            if let Some(count) = lifecycle.in_flight.get_mut(&self.0) {
+                *count -= 1;
+                if *count == 0 {
+                    lifecycle.in_flight.remove(&self.0);
+                }
+            }
+        });
+    }
+}
+
+/// Rejects new sends on the bridge and waits up to `deadline` for sends in flight.
+pub(crate) async fn drain(callback_id: &str, deadline: Duration) {
+    with_lifecycle(|lifecycle| lifecycle.shut_down.insert(callback_id.to_string()));
+    let drained = async {
+        while with_lifecycle(|lifecycle| lifecycle.in_flight.contains_key(callback_id)) {
+            crate::watchdog::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    if crate::watchdog::with_timeout(drained, deadline)
+        .await
+        .is_err()
+    {
+        eprintln!(
+            "Bridge {} shut down with sends still in flight",
+            callback_id
+        );
+    }
+}
+
+/// Shuts down every listening bridge, for a clean exit on desktop or in Android's
+/// `onDestroy`: new sends on any bridge fail, sends in flight get up to
+/// [`SHUTDOWN_DEADLINE`] to finish, and all callbacks are unregistered.
+pub async fn shutdown_all() {
+    let bridges = with_lifecycle(|lifecycle| {
+        lifecycle.all_shut_down = true;
+        lifecycle.listening.clone()
+    });
+    futures_util::future::join_all(
+        bridges
+            .into_iter()
+            .map(|id| async move { RawBridge::with_id(id).shutdown(SHUTDOWN_DEADLINE).await }),
+    )
+    .await;
+    #[cfg(target_os = "android")]
+    {
+        crate::android_bridge::flush().await;
+        crate::android_bridge::shutdown_thread();
+    }
+}
@@ -0,0 +1,80 @@
+use crate::eval_as;
+use serde::{Deserialize, Serialize};
+
+/// The user's language, time zone and formatting conventions as the WebView sees them, so
+/// text formatted in Rust matches what `Intl` would produce on the page.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LocaleInfo {
+    /// The locale `Intl` formats with, e.g. `"en-US"`.
+    pub locale: String,
+    /// `navigator.languages`, most preferred first.
+    pub languages: Vec<String>,
+    /// The IANA time zone, e.g. `"Europe/Berlin"`.
+    pub time_zone: String,
+    /// Minutes to add to UTC for local time right now, e.g. 120 in Berlin in summer.
+    pub utc_offset_minutes: i32,
+    /// `"h12"` or `"h23"` clocks, and the rarer `"h11"` and `"h24"`.
+    pub hour_cycle: String,
+    /// 1 for Monday through 7 for Sunday, when the WebView reports week data.
+    pub first_day_of_week: Option<u8>,
+    pub decimal_separator: String,
+    /// Empty for locales that don't group digits.
+    pub grouping_separator: String,
+    /// The short date pattern with `yyyy`, `MM`/`M` and `dd`/`d` fields, e.g.
+    /// `"M/d/yyyy"` or `"dd.MM.yyyy"`.
+    pub date_pattern: String,
+    /// The language tag of the system `java.util.Locale`, on Android only. Apps with a
+    /// per-app language may have the WebView format differently.
+    pub system_locale: Option<String>,
+}
+
+/// Reads the locale from `Intl` and `navigator.languages`, plus the system locale through
+/// JNI on Android.
+pub async fn get_locale_info() -> Result<LocaleInfo, String> {
+    let js_code = r#"
+        const options = new Intl.DateTimeFormat().resolvedOptions();
+        const locale = options.locale;
+        const hourCycle = new Intl.DateTimeFormat(locale, { hour: "numeric" })
+            .resolvedOptions().hourCycle;
+        let firstDay = null;
+        try {
+            const info = new Intl.Locale(locale);
+            const week = info.getWeekInfo ? info.getWeekInfo() : info.weekInfo;
+            firstDay = week ? week.firstDay : null;
+        } catch (e) {}
+        const number = new Intl.NumberFormat(locale).formatToParts(1234567.5);
+        const part = (type) => (number.find((p) => p.type === type) || { value: "" }).value;
+        // Single-digit day and month show whether the locale pads them
+        const date = new Intl.DateTimeFormat(locale).formatToParts(new Date(2033, 0, 5));
+        const pattern = date.map((p) => {
+            if (p.type === "year") return "yyyy";
+            if (p.type === "month") return p.value.length > 1 ? "MM" : "M";
+            if (p.type === "day") return p.value.length > 1 ? "dd" : "d";
+            return p.value;
+        }).join("");
+        return {
+            locale,
+            languages: Array.from(navigator.languages || [navigator.language]),
+            time_zone: options.timeZone || "UTC",
+            utc_offset_minutes: -new Date().getTimezoneOffset(),
+            hour_cycle: hourCycle || "h23",
+            first_day_of_week: firstDay,
+            decimal_separator: part("decimal"),
+            grouping_separator: part("group"),
+            date_pattern: pattern,
+            system_locale: null,
+        };
+    "#;
+    #[allow(unused_mut)]
+    let mut info = eval_as::<LocaleInfo>(js_code).await?;
+
+    #[cfg(target_os = "android")]
+    {
+        let (locale, time_zone) = crate::android_bridge::system_locale()?;
+        info.system_locale = Some(locale);
+        // The WebView may not follow time zone changes until it restarts
+        info.time_zone = time_zone;
+    }
+
+    Ok(info)
+}
@@ -0,0 +1,445 @@
+use crate::binary::JS_BASE64_HELPERS;
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Number of raw bytes per chunk message when streaming frames to Rust.
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// Which camera to prefer on devices with several.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacingMode {
+    /// The front camera.
+    User,
+    /// The back camera.
+    Environment,
+}
+
+/// What [`MediaCapture::start`] asks `getUserMedia` for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MediaConstraints {
+    pub video: bool,
+    pub audio: bool,
+    pub facing_mode: Option<FacingMode>,
+    /// Ideal video size in pixels.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl Default for MediaConstraints {
+    fn default() -> Self {
+        Self {
+            video: true,
+            audio: false,
+            facing_mode: None,
+            width: None,
+            height: None,
+        }
+    }
+}
+
+/// Whether frame bytes belong to a snapshot or a recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameKind {
+    Snapshot,
+    Recording,
+}
+
+/// A typed event sent from the capture stream in JS.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MediaEvent {
+    Started {
+        video: bool,
+        audio: bool,
+    },
+    Stopped,
+    RecordingStarted,
+    /// Bytes of the current snapshot or recording starting at `offset`.
+    Chunk {
+        kind: FrameKind,
+        offset: u64,
+        #[serde(with = "crate::binary::base64_bytes")]
+        data: Vec<u8>,
+    },
+    /// Every chunk of the snapshot or recording has been sent.
+    FrameEnd {
+        kind: FrameKind,
+        mime_type: String,
+        width: Option<u32>,
+        height: Option<u32>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// An encoded snapshot image or recording, assembled from its chunks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaFrame {
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+    /// Image size in pixels, for snapshots.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// A camera/microphone stream living in the page, created by [`use_media_capture`].
+#[derive(Clone)]
+pub struct MediaCapture {
+    pub active: Signal<bool>,
+    pub recording: Signal<bool>,
+    /// The most recent snapshot taken with [`MediaCapture::take_snapshot`].
+    pub snapshot: Signal<Option<MediaFrame>>,
+    /// The most recent recording, set once [`MediaCapture::stop_recording`] completes.
+    pub last_recording: Signal<Option<MediaFrame>>,
+    /// Errors reported by the recorder.
+    pub error: Signal<Option<String>>,
+    pub bridge: JsBridge<MediaEvent>,
+}
+
+impl MediaCapture {
+    /// Returns a stream of every capture event received after this call, including the
+    /// raw chunks.
+    pub fn events(&self) -> UnboundedReceiver<MediaEvent> {
+        self.bridge.stream()
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self.active.read()
+    }
+    pub fn is_recording(&self) -> bool {
+        *self.recording.read()
+    }
+    pub fn get_snapshot(&self) -> Option<MediaFrame> {
+        self.snapshot.read().clone()
+    }
+    pub fn get_recording(&self) -> Option<MediaFrame> {
+        self.last_recording.read().clone()
+    }
+    pub fn get_error(&self) -> Option<String> {
+        self.error
+            .read()
+            .clone()
+            .or_else(|| self.bridge.get_error())
+    }
+
+    /// Requests camera and/or microphone access and starts capturing, replacing any
+    /// running stream.
+    ///
+    /// On Android the runtime permissions are requested first with
+    /// `RustBridge.requestMediaPermissions({ video, audio })`, which answers with whether
+    /// they were granted.
+    pub async fn start(&self, constraints: &MediaConstraints) -> Result<(), String> {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({
+                "video": constraints.video,
+                "audio": constraints.audio,
+            });
+            let granted =
+                crate::android_bridge::call_native_async("requestMediaPermissions", args).await?;
+            if granted.as_bool() != Some(true) {
+                return Err("Camera/microphone permission denied".to_string());
+            }
+        }
+
+        let constraints = serde_json::to_string(constraints)
+            .map_err(|e| format!("Serialization error: {}", e))?;
+        self.run(&format!(
+            r#"
+            if (!navigator.mediaDevices || !navigator.mediaDevices.getUserMedia) {{
+                throw new Error("Media capture is not supported");
+            }}
+            const c = {constraints};
+            const video = c.video
+                ? {{
+                    facingMode: c.facing_mode ?? undefined,
+                    width: c.width ?? undefined,
+                    height: c.height ?? undefined,
+                }}
+                : false;
+            const stream = await navigator.mediaDevices.getUserMedia({{ video, audio: c.audio }});
+            media.stop();
+            media.stream = stream;
+            media.video.srcObject = stream;
+            for (const preview of media.previews) {{
+                preview.srcObject = stream;
+            }}
+            if (c.video) {{
+                await media.video.play();
+            }}
+            media.emit({{ event: "started", video: c.video, audio: c.audio }});
+            "#
+        ))
+        .await
+    }
+
+    /// Stops capturing and releases the devices.
+    pub async fn stop(&self) -> Result<(), String> {
+        self.run("media.stop();").await
+    }
+
+    /// Shows the live camera feed in the `<video>` element matching `selector`.
+    pub async fn attach_preview(&self, selector: &str) -> Result<(), String> {
+        self.run(&format!(
+            r#"
+            const preview = document.querySelector({selector});
+            if (!preview) {{
+                throw new Error("attach_preview: no element matches " + {selector});
+            }}
+            preview.muted = true;
+            preview.playsInline = true;
+            preview.srcObject = media.stream;
+            media.previews.push(preview);
+            "#,
+            selector = js_string(selector)
+        ))
+        .await
+    }
+
+    /// Captures the current video frame encoded as `mime_type` (e.g. `"image/png"` or
+    /// `"image/jpeg"`). The image is streamed to Rust in chunks and lands in `snapshot`.
+    pub async fn take_snapshot(&self, mime_type: &str) -> Result<(), String> {
+        self.run(&format!(
+            r#"
+            if (!media.stream || media.video.videoWidth === 0) {{
+                throw new Error("No video is being captured");
+            }}
+            const canvas = document.createElement("canvas");
+            canvas.width = media.video.videoWidth;
+            canvas.height = media.video.videoHeight;
+            canvas.getContext("2d").drawImage(media.video, 0, 0);
+            const blob = await new Promise((resolve) => canvas.toBlob(resolve, {mime_type}));
+            if (!blob) {{
+                throw new Error("Failed to encode snapshot");
+            }}
+            await media.emitBlob("snapshot", blob, 0);
+            media.emit({{
+                event: "frame_end",
+                kind: "snapshot",
+                mime_type: blob.type,
+                width: canvas.width,
+                height: canvas.height,
+            }});
+            "#,
+            mime_type = js_string(mime_type)
+        ))
+        .await
+    }
+
+    /// Starts recording the stream with `MediaRecorder`. Data is streamed to Rust in
+    /// chunks while recording.
+    pub async fn start_recording(&self, mime_type: Option<&str>) -> Result<(), String> {
+        self.run(&format!(
+            r#"
+            if (!media.stream) {{
+                throw new Error("Nothing is being captured");
+            }}
+            if (media.recorder) {{
+                throw new Error("Already recording");
+            }}
+            const mimeType = {mime_type};
+            const recorder = new MediaRecorder(media.stream, mimeType ? {{ mimeType }} : {{}});
+            let offset = 0;
+            let pending = Promise.resolve();
+            recorder.ondataavailable = (e) => {{
+                const blob = e.data;
+                const start = offset;
+                offset += blob.size;
+                pending = pending.then(() => media.emitBlob("recording", blob, start));
+            }};
+            recorder.onstop = () => {{
+                pending.then(() => media.emit({{
+                    event: "frame_end",
+                    kind: "recording",
+                    mime_type: recorder.mimeType,
+                    width: null,
+                    height: null,
+                }}));
+                media.recorder = null;
+            }};
+            recorder.onerror = (e) => media.emit({{ event: "error", message: String(e.error || e) }});
+            recorder.start(1000);
+            media.recorder = recorder;
+            media.emit({{ event: "recording_started" }});
+            "#,
+            mime_type = serde_json::to_string(&mime_type)
+                .map_err(|e| format!("Serialization error: {}", e))?
+        ))
+        .await
+    }
+
+    /// Stops recording; the complete recording lands in `last_recording`.
+    pub async fn stop_recording(&self) -> Result<(), String> {
+        self.run(
+            r#"
+            if (media.recorder) {
+                media.recorder.stop();
+            }
+            "#,
+        )
+        .await
+    }
+
+    /// Runs `body` with `media` in scope.
+    async fn run(&self, body: &str) -> Result<(), String> {
+        let js_code = format!(
+            r#"
+            const media = window.__dioxus_media && window.__dioxus_media[{id}];
+            if (!media) {{
+                throw new Error("Media capture is not installed");
+            }}
+            {body}
+            "#,
+            id = js_string(&self.bridge.callback_id()),
+        );
+        eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+    }
+}
+
+fn close_script(id: &str) -> String {
+    format!(
+        r#"
+        const media = window.__dioxus_media && window.__dioxus_media[{id}];
+        if (media) {{
+            media.stop();
+            delete window.__dioxus_media[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Sets up camera/microphone capture. Nothing is requested until [`MediaCapture::start`];
+/// the devices are released when the component unmounts.
+pub fn use_media_capture() -> MediaCapture {
+    let mut active = use_signal(|| false);
+    let mut recording = use_signal(|| false);
+    let mut snapshot = use_signal(|| None::<MediaFrame>);
+    let mut last_recording = use_signal(|| None::<MediaFrame>);
+    let mut error = use_signal(|| None::<String>);
+    let mut snapshot_bytes = use_hook(|| CopyValue::new(Vec::<u8>::new()));
+    let mut recording_bytes = use_hook(|| CopyValue::new(Vec::<u8>::new()));
+
+    let bridge = use_js_listener::<MediaEvent>(move |event| match event {
+        MediaEvent::Started { .. } => active.set(true),
+        MediaEvent::Stopped => {
+            active.set(false);
+            recording.set(false);
+        }
+        MediaEvent::RecordingStarted => {
+            recording_bytes.write().clear();
+            recording.set(true);
+        }
+        MediaEvent::Chunk { kind, offset, data } => {
+            let mut buffer = match kind {
+                FrameKind::Snapshot => snapshot_bytes.write(),
+                FrameKind::Recording => recording_bytes.write(),
+            };
+            if offset == 0 {
+                buffer.clear();
+            }
+            buffer.extend_from_slice(&data);
+        }
+        MediaEvent::FrameEnd {
+            kind,
+            mime_type,
+            width,
+            height,
+        } => match kind {
+            FrameKind::Snapshot => snapshot.set(Some(MediaFrame {
+                mime_type,
+                bytes: std::mem::take(&mut *snapshot_bytes.write()),
+                width,
+                height,
+            })),
+            FrameKind::Recording => {
+                recording.set(false);
+                last_recording.set(Some(MediaFrame {
+                    mime_type,
+                    bytes: std::mem::take(&mut *recording_bytes.write()),
+                    width,
+                    height,
+                }));
+            }
+        },
+        MediaEvent::Error { message } => error.set(Some(message)),
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            {JS_BASE64_HELPERS}
+            window.__dioxus_media = window.__dioxus_media || {{}};
+            const id = {id};
+            if (!window.__dioxus_media[id]) {{
+                const media = {{ stream: null, recorder: null, previews: [] }};
+                media.emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                media.emitBlob = async (kind, blob, start) => {{
+                    for (let offset = 0; offset < blob.size; offset += {CHUNK_SIZE}) {{
+                        const bytes = new Uint8Array(
+                            await blob.slice(offset, offset + {CHUNK_SIZE}).arrayBuffer()
+                        );
+                        media.emit({{
+                            event: "chunk",
+                            kind,
+                            offset: start + offset,
+                            data: __dxToBase64(bytes),
+                        }});
+                    }}
+                }};
+                media.stop = () => {{
+                    if (media.recorder) {{
+                        media.recorder.stop();
+                    }}
+                    if (media.stream) {{
+                        media.stream.getTracks().forEach((track) => track.stop());
+                        media.stream = null;
+                        media.video.srcObject = null;
+                        for (const preview of media.previews) {{
+                            preview.srcObject = null;
+                        }}
+                        media.emit({{ event: "stopped" }});
+                    }}
+                }};
+                media.video = document.createElement("video");
+                media.video.muted = true;
+                media.video.playsInline = true;
+                window.__dioxus_media[id] = media;
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&close_script(&callback_id));
+    });
+
+    MediaCapture {
+        active,
+        recording,
+        snapshot,
+        last_recording,
+        error,
+        bridge,
+    }
+}
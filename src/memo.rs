@@ -0,0 +1,41 @@
+use crate::{eval_as, FromJs};
+use dioxus::prelude::*;
+use dioxus_signals::{CopyValue, Readable, Writable};
+
+/// Evaluates the JS expression `js_expr` and caches its result in a signal, evaluating it
+/// again whenever `deps` or the expression change between renders.
+///
+/// The signal is `None` until the first evaluation finishes, and keeps the previous value
+/// while a new one is computed. Results of outdated evaluations are discarded.
+pub fn use_js_memo<T, D>(js_expr: &str, deps: D) -> Signal<Option<T>>
+where
+    T: FromJs + Clone + 'static,
+    D: PartialEq + 'static,
+{
+    let mut value = use_signal(|| None::<T>);
+    // The expression and dependencies of the latest evaluation, and its number
+    let mut latest = use_hook(|| CopyValue::new((None::<(String, D)>, 0u64)));
+
+    let current = (js_expr.to_string(), deps);
+    if latest.peek().0.as_ref() != Some(&current) {
+        let script = format!("return ({});", current.0);
+        let generation = {
+            let mut latest = latest.write();
+            latest.0 = Some(current);
+            latest.1 += 1;
+            latest.1
+        };
+        spawn(async move {
+            let result = eval_as::<T>(&script).await;
+            if latest.peek().1 != generation {
+                return;
+            }
+            match result {
+                Ok(result) => value.set(Some(result)),
+                Err(e) => eprintln!("Failed to evaluate JS memo: {}", e),
+            }
+        });
+    }
+
+    value
+}
@@ -0,0 +1,158 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, eval_detached, js_string};
+use crate::{use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Connectivity as reported by `navigator.onLine` and the Network Information API, or by
+/// `ConnectivityManager` on Android. Fields the platform doesn't report are `None`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    pub online: bool,
+    /// Physical connection, e.g. `"wifi"`, `"cellular"` or `"ethernet"`.
+    pub connection_type: Option<String>,
+    /// Effective connection quality: `"slow-2g"`, `"2g"`, `"3g"` or `"4g"`.
+    pub effective_type: Option<String>,
+    /// Estimated bandwidth in megabits per second.
+    pub downlink_mbps: Option<f64>,
+    /// Estimated round-trip time in milliseconds.
+    pub rtt_ms: Option<f64>,
+    /// Whether the user asked for reduced data usage, or the connection is metered.
+    pub save_data: Option<bool>,
+}
+
+impl Default for NetworkInfo {
+    fn default() -> Self {
+        Self {
+            online: true,
+            connection_type: None,
+            effective_type: None,
+            downlink_mbps: None,
+            rtt_ms: None,
+            save_data: None,
+        }
+    }
+}
+
+/// Live connectivity, created by [`use_network_status`].
+#[derive(Clone)]
+pub struct NetworkStatus {
+    pub info: Signal<NetworkInfo>,
+    pub bridge: JsBridge<NetworkInfo>,
+}
+
+impl NetworkStatus {
+    pub fn is_online(&self) -> bool {
+        self.info.read().online
+    }
+
+    pub fn get_info(&self) -> NetworkInfo {
+        self.info.read().clone()
+    }
+
+    /// Returns a stream of every connectivity change received after this call.
+    pub fn changes(&self) -> UnboundedReceiver<NetworkInfo> {
+        self.bridge.stream()
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn unwatch_script(id: &str) -> String {
+    format!(
+        r#"
+        const watch = window.__dioxus_network && window.__dioxus_network[{id}];
+        if (watch) {{
+            window.removeEventListener("online", watch);
+            window.removeEventListener("offline", watch);
+            if (navigator.connection) {{
+                navigator.connection.removeEventListener("change", watch);
+            }}
+            delete window.__dioxus_network[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Tracks connectivity until the component unmounts.
+///
+/// Uses `online`/`offline` events and `navigator.connection` on web and desktop, and
+/// `ConnectivityManager` callbacks on Android: `RustBridge.watchNetwork({ callback_id })`
+/// posts a [`NetworkInfo`] to `callback_id` on every change until
+/// `unwatchNetwork({ callback_id })`.
+pub fn use_network_status() -> NetworkStatus {
+    let mut info = use_signal(NetworkInfo::default);
+    let bridge = use_js_listener::<NetworkInfo>(move |update| info.set(update));
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let callback_id = bridge_for_effect.callback_id();
+
+        #[cfg(target_os = "android")]
+        {
+            // The app posts the current state and every change to this bridge's callback ID
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("watchNetwork", &args) {
+                bridge_for_effect.clone().set_error(Some(e));
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let js_code = format!(
+                r#"
+                window.__dioxus_network = window.__dioxus_network || {{}};
+                const id = {id};
+                if (!window.__dioxus_network[id]) {{
+                    const report = () => {{
+                        const c = navigator.connection;
+                        if ({callback}) {{
+                            {callback}({{
+                                online: navigator.onLine,
+                                connection_type: c && c.type !== undefined ? c.type : null,
+                                effective_type: c && c.effectiveType !== undefined ? c.effectiveType : null,
+                                downlink_mbps: c && c.downlink !== undefined ? c.downlink : null,
+                                rtt_ms: c && c.rtt !== undefined ? c.rtt : null,
+                                save_data: c && c.saveData !== undefined ? c.saveData : null,
+                            }});
+                        }}
+                    }};
+                    window.addEventListener("online", report);
+                    window.addEventListener("offline", report);
+                    if (navigator.connection) {{
+                        navigator.connection.addEventListener("change", report);
+                    }}
+                    window.__dioxus_network[id] = report;
+                    report();
+                }}
+                "#,
+                id = js_string(&callback_id),
+                callback = bridge_for_effect.js_callback(),
+            );
+            let mut bridge = bridge_for_effect.clone();
+            spawn(async move {
+                if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                    bridge.set_error(Some(e));
+                }
+            });
+        }
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("unwatchNetwork", &args) {
+                eprintln!("Failed to stop network watch: {}", e);
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        eval_detached(&unwatch_script(&callback_id));
+    });
+
+    NetworkStatus { info, bridge }
+}
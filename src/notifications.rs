@@ -0,0 +1,166 @@
+use crate::{use_js_bridge, JsBridge};
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Whether the app may show notifications.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationPermission {
+    Granted,
+    Denied,
+    /// The user hasn't decided yet.
+    Default,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationOptions {
+    /// Notifications with the same tag replace each other.
+    pub tag: Option<String>,
+    pub icon: Option<String>,
+    pub silent: bool,
+    pub require_interaction: bool,
+    /// Passed back in [`NotificationEvent::Click`].
+    pub data: Option<serde_json::Value>,
+}
+
+/// An interaction with a notification shown through [`Notifications::notify`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    Click {
+        tag: Option<String>,
+        data: Option<serde_json::Value>,
+    },
+    Close {
+        tag: Option<String>,
+    },
+    Error {
+        tag: Option<String>,
+        message: String,
+    },
+}
+
+/// Asks the user for permission to show notifications.
+///
+/// On Android, `RustBridge.requestNotificationPermission({})` answers with `"granted"`,
+/// `"denied"` or `"default"`.
+pub async fn request_notification_permission() -> Result<NotificationPermission, String> {
+    #[cfg(target_os = "android")]
+    {
+        let permission = crate::android_bridge::call_native_async(
+            "requestNotificationPermission",
+            serde_json::json!({}),
+        )
+        .await?;
+        serde_json::from_value(permission).map_err(|e| format!("Deserialization error: {}", e))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        crate::eval_as(
+            r#"
+            if (!("Notification" in window)) {
+                throw new Error("Notifications are not supported");
+            }
+            return await Notification.requestPermission();
+            "#,
+        )
+        .await
+    }
+}
+
+/// Shows notifications and delivers their clicks, created by [`use_notifications`].
+#[derive(Clone)]
+pub struct Notifications {
+    pub bridge: JsBridge<NotificationEvent>,
+}
+
+impl Notifications {
+    /// Returns a stream of every notification event received after this call.
+    pub fn events(&self) -> UnboundedReceiver<NotificationEvent> {
+        self.bridge.stream()
+    }
+
+    pub fn last_event(&self) -> Option<NotificationEvent> {
+        self.bridge.get_data()
+    }
+
+    /// Shows a notification. Permission must have been granted, see
+    /// [`request_notification_permission`].
+    ///
+    /// Uses the Web Notification API on web and desktop, and `NotificationManager` on
+    /// Android: `RustBridge.showNotification({ title, body, ...options, callback_id })`
+    /// posts a [`NotificationEvent`] to `callback_id` for each click, close or error.
+    pub async fn notify(
+        &self,
+        title: &str,
+        body: &str,
+        options: &NotificationOptions,
+    ) -> Result<(), String> {
+        #[cfg(target_os = "android")]
+        {
+            // Clicks are posted by the app straight to this bridge's callback ID
+            let mut args =
+                serde_json::to_value(options).map_err(|e| format!("Serialization error: {}", e))?;
+            if let Some(args) = args.as_object_mut() {
+                args.insert("title".to_string(), title.into());
+                args.insert("body".to_string(), body.into());
+                args.insert("callback_id".to_string(), self.bridge.callback_id().into());
+            }
+            crate::android_bridge::call_native("showNotification", &args).map(|_| ())
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let options = serde_json::to_string(options)
+                .map_err(|e| format!("Serialization error: {}", e))?;
+            let js_code = format!(
+                r#"
+                if (!("Notification" in window)) {{
+                    throw new Error("Notifications are not supported");
+                }}
+                if (Notification.permission !== "granted") {{
+                    throw new Error("Notification permission has not been granted");
+                }}
+                const options = {options};
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const notification = new Notification({title}, {{
+                    body: {body},
+                    tag: options.tag ?? undefined,
+                    icon: options.icon ?? undefined,
+                    silent: options.silent,
+                    requireInteraction: options.require_interaction,
+                    data: options.data,
+                }});
+                notification.onclick = () => emit({{
+                    event: "click",
+                    tag: options.tag,
+                    data: options.data,
+                }});
+                notification.onclose = () => emit({{ event: "close", tag: options.tag }});
+                notification.onerror = () => emit({{
+                    event: "error",
+                    tag: options.tag,
+                    message: "Failed to show notification",
+                }});
+                "#,
+                title = crate::js_string(title),
+                body = crate::js_string(body),
+                callback = self.bridge.js_callback(),
+            );
+            crate::eval_as::<serde_json::Value>(&js_code)
+                .await
+                .map(|_| ())
+        }
+    }
+}
+
+/// Creates a [`Notifications`] handle whose notifications report clicks to this component.
+pub fn use_notifications() -> Notifications {
+    let bridge = use_js_bridge::<NotificationEvent>();
+    Notifications { bridge }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+/// A `blob:` URL of bytes from Rust, for `<img>`, `<audio>` or download links; see
+/// [`create_object_url`]. The URL is revoked when this guard is dropped, so keep it alive,
+/// e.g. in a signal, for as long as the page uses it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ObjectUrl {
+    url: String,
+}
+
+impl ObjectUrl {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl fmt::Display for ObjectUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url)
+    }
+}
+
+impl Drop for ObjectUrl {
+    fn drop(&mut self) {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = web_sys::Url::revoke_object_url(&self.url);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            crate::eval_detached(&format!(
+                "URL.revokeObjectURL({});",
+                crate::js_string(&self.url)
+            ));
+        }
+    }
+}
+
+/// Materializes `bytes` as a Blob of type `mime_type` in the page and returns its URL.
+///
+/// On web the Blob is made from the bytes directly; elsewhere they cross the bridge as
+/// base64, so use an [`Attachment`](crate::Attachment) for large files on desktop and
+/// Android.
+pub async fn create_object_url(bytes: &[u8], mime_type: &str) -> Result<ObjectUrl, String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime_type);
+        let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+            .map_err(|e| format!("Failed to create Blob: {:?}", e))?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)
+            .map_err(|e| format!("Failed to create object URL: {:?}", e))?;
+        Ok(ObjectUrl { url })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let js_code = format!(
+            r#"
+            {}
+            const blob = new Blob([__dxFromBase64({})], {{ type: {} }});
+            return URL.createObjectURL(blob);
+            "#,
+            crate::binary::JS_BASE64_HELPERS,
+            crate::js_string(&crate::binary::encode(bytes)),
+            crate::js_string(mime_type)
+        );
+        let url = crate::eval_as::<String>(&js_code).await?;
+        Ok(ObjectUrl { url })
+    }
+}
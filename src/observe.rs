@@ -0,0 +1,32 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+/// What [`JsBridge::on_send`](crate::JsBridge::on_send) and
+/// [`JsBridge::on_receive`](crate::JsBridge::on_receive) observers learn about a message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageMeta {
+    pub callback_id: String,
+    /// The envelope's message ID, on bridges that stamp messages.
+    pub message_id: Option<u64>,
+    /// The length of the message's JSON, before any envelope or compression.
+    pub size: usize,
+    /// The Rust type sent or received.
+    pub type_name: &'static str,
+    /// How long serializing and sending, or decoding and delivering, took.
+    pub duration: Duration,
+    /// Whether the message was delivered.
+    pub ok: bool,
+}
+
+pub(crate) type Observer = Rc<dyn Fn(&MessageMeta)>;
+
+#[derive(Clone, Default)]
+pub(crate) struct Observers {
+    pub on_send: Vec<Observer>,
+    pub on_receive: Vec<Observer>,
+}
+
+/// The time since `started`, a [`rust_now_ms`](crate::rust_now_ms) timestamp.
+pub(crate) fn elapsed(started: f64) -> Duration {
+    Duration::from_secs_f64((crate::rust_now_ms() - started).max(0.0) / 1000.0)
+}
@@ -0,0 +1,160 @@
+use crate::Attachment;
+use dioxus::prelude::*;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// Files the OS opened with the app, created by [`use_opened_files`].
+#[derive(Clone, Copy)]
+pub struct OpenedFiles {
+    /// Every file opened while the component was mounted, oldest first.
+    pub files: Signal<Vec<Attachment>>,
+    pub error: Signal<Option<String>>,
+    subscribers: CopyValue<Vec<UnboundedSender<Attachment>>>,
+}
+
+impl OpenedFiles {
+    pub fn last_file(&self) -> Option<Attachment> {
+        self.files.read().last().cloned()
+    }
+
+    /// Returns a stream of every file opened after this call.
+    pub fn stream(&self) -> UnboundedReceiver<Attachment> {
+        let (tx, rx) = futures_channel::mpsc::unbounded();
+        let mut subscribers = self.subscribers;
+        subscribers.write().push(tx);
+        rx
+    }
+
+    #[cfg(any(
+        target_os = "android",
+        all(feature = "desktop", not(target_arch = "wasm32"))
+    ))]
+    fn receive(&mut self, file: Result<Attachment, String>) {
+        match file {
+            Ok(file) => {
+                self.files.write().push(file.clone());
+                self.subscribers
+                    .write()
+                    .retain(|tx| tx.unbounded_send(file.clone()).is_ok());
+            }
+            Err(e) => self.error.set(Some(e)),
+        }
+    }
+}
+
+/// A file Android copied out of a `content://` URI for the app.
+#[cfg(target_os = "android")]
+#[derive(Clone, Debug, serde::Deserialize)]
+struct OpenedPath {
+    path: String,
+    mime_type: Option<String>,
+}
+
+/// The arguments that name existing files, resolved against `cwd`.
+#[cfg(all(
+    feature = "desktop",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+fn file_args(args: &[String], cwd: Option<&std::path::Path>) -> Vec<std::path::PathBuf> {
+    args.iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| match cwd {
+            Some(cwd) => cwd.join(arg),
+            None => std::path::PathBuf::from(arg),
+        })
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Delivers the files the app is asked to open, as [`Attachment`]s readable from Rust and
+/// loadable by the page.
+///
+/// On desktop these are the files in the command line, once per process, those of later
+/// launches forwarded by [`single_instance`](crate::single_instance), and files opened
+/// through Finder on macOS. On Android the app reports `ACTION_VIEW` and `ACTION_SEND`
+/// intents, copying `content://` URIs to its cache; its
+/// `RustBridge.watchOpenedFiles({ callback_id })` posts `{ path, mime_type }` for the
+/// launch intent's file and every later one until `unwatchOpenedFiles({ callback_id })`.
+/// The web has no file associations, so nothing is delivered there.
+pub fn use_opened_files() -> OpenedFiles {
+    let files = use_signal(Vec::new);
+    let error = use_signal(|| None);
+    let subscribers = use_hook(|| CopyValue::new(Vec::new()));
+    let opened = OpenedFiles {
+        files,
+        error,
+        subscribers,
+    };
+
+    #[cfg(target_os = "android")]
+    {
+        use dioxus::core::use_drop;
+
+        let mut receiver = opened;
+        let bridge = crate::use_js_listener::<OpenedPath>(move |opened: OpenedPath| {
+            receiver.receive(Attachment::from_path(
+                opened.path,
+                opened.mime_type.as_deref(),
+            ));
+        });
+
+        let bridge_for_effect = bridge.clone();
+        use_effect(move || {
+            // The app posts the file of the launch intent, and of every later one
+            let args = serde_json::json!({ "callback_id": bridge_for_effect.callback_id() });
+            if let Err(e) = crate::android_bridge::call_native("watchOpenedFiles", &args) {
+                let mut error = error;
+                error.set(Some(e));
+            }
+        });
+
+        let callback_id = bridge.callback_id();
+        use_drop(move || {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("unwatchOpenedFiles", &args) {
+                eprintln!("Failed to stop watching opened files: {}", e);
+            }
+        });
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        use futures_util::StreamExt;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        static ARGS_TAKEN: AtomicBool = AtomicBool::new(false);
+
+        let mut receiver = opened;
+        use_hook(move || {
+            if !ARGS_TAKEN.swap(true, Ordering::SeqCst) {
+                let args: Vec<String> = std::env::args().skip(1).collect();
+                for path in file_args(&args, None) {
+                    receiver.receive(Attachment::from_path(path, None));
+                }
+            }
+            let mut launches = crate::single_instance::subscribe();
+            spawn(async move {
+                while let Some(launch) = launches.next().await {
+                    for path in file_args(&launch.args, launch.cwd.as_deref()) {
+                        receiver.receive(Attachment::from_path(path, None));
+                    }
+                }
+            });
+        });
+
+        let mut receiver = opened;
+        dioxus_desktop::use_wry_event_handler(move |event, _| {
+            if let dioxus_desktop::tao::event::Event::Opened { urls } = event {
+                for url in urls {
+                    if let Ok(path) = url.to_file_path() {
+                        receiver.receive(Attachment::from_path(path, None));
+                    }
+                }
+            }
+        });
+    }
+
+    opened
+}
@@ -0,0 +1,160 @@
+use crate::js_string;
+use std::fmt;
+use std::sync::Arc;
+
+/// Where a message from JS came from, as checked by an [`OriginPolicy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageSource {
+    /// The sender's origin for messages posted from another frame, as reported by the
+    /// browser engine. `None` for calls made directly in the bridge's own document.
+    pub origin: Option<String>,
+    /// Whether the sender is the top-level window.
+    pub top_frame: bool,
+}
+
+impl fmt::Display for MessageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.origin {
+            Some(origin) => write!(f, "{}", origin),
+            None => write!(f, "the bridge's own document"),
+        }
+    }
+}
+
+/// Decides which senders a bridge accepts messages from; see
+/// [`BridgeOptions::origin_policy`](crate::BridgeOptions::origin_policy).
+///
+/// With a policy set, frames can also reach the bridge with
+/// `target.postMessage({ dxBridge: callbackId, data }, "*")`, and the browser-reported
+/// `event.origin` is checked. On Android the Kotlin bridge must deliver page messages
+/// through a WebMessageListener (`RustBridge.onMessageFromFrame`), which reports the
+/// sending frame; messages through the `JavascriptInterface`, which every frame can call,
+/// are rejected.
+#[derive(Clone)]
+pub struct OriginPolicy(Arc<dyn Fn(&MessageSource) -> bool + Send + Sync>);
+
+impl OriginPolicy {
+    pub fn new(allows: impl Fn(&MessageSource) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(allows))
+    }
+
+    /// Accepts the bridge's own document and frames from `origins`.
+    pub fn allow(origins: &[&str]) -> Self {
+        let origins: Vec<String> = origins.iter().map(|origin| origin.to_string()).collect();
+        Self::new(move |source| match &source.origin {
+            Some(origin) => origins.contains(origin),
+            None => true,
+        })
+    }
+
+    pub fn allows(&self, source: &MessageSource) -> bool {
+        (self.0)(source)
+    }
+}
+
+impl fmt::Debug for OriginPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OriginPolicy")
+    }
+}
+
+impl PartialEq for OriginPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Splits the sender off a message from JS. Messages from other frames are wrapped by the
+/// listener from [`listener_script`], whose `nonce` can't be forged by page scripts.
+pub(crate) fn unwrap(
+    value: serde_json::Value,
+    nonce: &str,
+) -> Result<(serde_json::Value, MessageSource), String> {
+    let serde_json::Value::Object(mut object) = value else {
+        return Ok((value, same_document()));
+    };
+    let Some(source) = object.remove("__dx_source") else {
+        return Ok((serde_json::Value::Object(object), same_document()));
+    };
+    let tagged_by = object.get("__dx_nonce").and_then(|n| n.as_str());
+    #[cfg(target_os = "android")]
+    let native = tagged_by == Some(crate::android_bridge::frame_nonce());
+    #[cfg(not(target_os = "android"))]
+    let native = false;
+    if tagged_by != Some(nonce) && !native {
+        return Err("Rejected message with a forged source".to_string());
+    }
+    if source.get("unverified").is_some() {
+        return Err("Rejected message from a frame without a verified origin".to_string());
+    }
+    let source = MessageSource {
+        origin: source
+            .get("origin")
+            .and_then(|origin| origin.as_str())
+            .map(str::to_string),
+        top_frame: source
+            .get("top_frame")
+            .and_then(|top| top.as_bool())
+            .unwrap_or(false),
+    };
+    Ok((object.remove("data").unwrap_or_default(), source))
+}
+
+pub(crate) fn same_document() -> MessageSource {
+    MessageSource {
+        origin: None,
+        top_frame: true,
+    }
+}
+
+/// Listens for `{ dxBridge: id, data }` posted from other frames and forwards them to
+/// `callback` along with the sender.
+/// `frames` is a JS expression for the iframe elements to accept messages from, if only
+/// some frames are allowed.
+pub(crate) fn listener_script(
+    callback_id: &str,
+    callback: &str,
+    nonce: &str,
+    frames: Option<&str>,
+) -> String {
+    format!(
+        r#"
+        window.__dioxus_origin = window.__dioxus_origin || {{}};
+        const id = {id};
+        if (!window.__dioxus_origin[id]) {{
+            const nonce = {nonce};
+            const listener = (e) => {{
+                if (!e.data || e.data.dxBridge !== id) return;
+                const frames = {frames};
+                if (frames && !frames.some((frame) => frame.contentWindow === e.source)) return;
+                const callback = {callback};
+                if (callback) {{
+                    callback({{
+                        __dx_source: {{ origin: e.origin, top_frame: e.source === window.top }},
+                        __dx_nonce: nonce,
+                        data: e.data.data,
+                    }});
+                }}
+            }};
+            window.addEventListener("message", listener);
+            window.__dioxus_origin[id] = listener;
+        }}
+        "#,
+        id = js_string(callback_id),
+        nonce = js_string(nonce),
+        frames = frames.unwrap_or("null"),
+    )
+}
+
+pub(crate) fn remove_listener_script(callback_id: &str) -> String {
+    format!(
+        r#"
+        const listener = window.__dioxus_origin && window.__dioxus_origin[{id}];
+        if (listener) {{
+            window.removeEventListener("message", listener);
+            delete window.__dioxus_origin[{id}];
+        }}
+        "#,
+        id = js_string(callback_id)
+    )
+}
@@ -0,0 +1,160 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, eval_detached, js_string};
+use crate::{use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Where the page is in its lifecycle, following the states of the Page Lifecycle API.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageState {
+    /// Visible and focused; `onResume` on Android.
+    #[default]
+    Active,
+    /// Visible but not focused, e.g. behind another window; `onPause` on Android.
+    Passive,
+    /// Not visible: a background tab, a minimized window, or `onStop` on Android.
+    Hidden,
+    /// Suspended by the browser, or kept in the back/forward cache. Timers don't run.
+    Frozen,
+    /// Being unloaded.
+    Terminated,
+}
+
+impl PageState {
+    pub fn is_visible(self) -> bool {
+        matches!(self, PageState::Active | PageState::Passive)
+    }
+}
+
+/// The page's lifecycle state, created by [`use_page_lifecycle`].
+#[derive(Clone)]
+pub struct PageLifecycle {
+    pub state: Signal<PageState>,
+    pub bridge: JsBridge<PageState>,
+}
+
+impl PageLifecycle {
+    pub fn get_state(&self) -> PageState {
+        *self.state.read()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.get_state() == PageState::Active
+    }
+
+    /// Whether animations and timers should keep running.
+    pub fn is_visible(&self) -> bool {
+        self.get_state().is_visible()
+    }
+
+    /// Returns a stream of every state change received after this call.
+    pub fn changes(&self) -> UnboundedReceiver<PageState> {
+        self.bridge.stream()
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+const EVENTS: &str = r#"[
+    [window, "focus"], [window, "blur"], [window, "pageshow"], [window, "pagehide"],
+    [document, "visibilitychange"], [document, "freeze"], [document, "resume"],
+]"#;
+
+/// Tracks whether the page is active, visible or suspended until the component unmounts,
+/// so apps can pause timers and animations the same way on every platform.
+///
+/// Combines `visibilitychange`, `focus`/`blur`, `freeze`/`resume` and
+/// `pagehide`/`pageshow` on web and desktop. On Android the activity's `onResume`,
+/// `onPause` and `onStop` are reported instead: `RustBridge.watchLifecycle({ callback_id })`
+/// posts the current [`PageState`] and every change, in lowercase such as `"hidden"`,
+/// until `unwatchLifecycle({ callback_id })`.
+pub fn use_page_lifecycle() -> PageLifecycle {
+    let mut state = use_signal(PageState::default);
+    let bridge = use_js_listener::<PageState>(move |update| {
+        if *state.peek() != update {
+            state.set(update);
+        }
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let callback_id = bridge_for_effect.callback_id();
+
+        #[cfg(target_os = "android")]
+        {
+            // The app posts the current state and every change to this bridge's callback ID
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("watchLifecycle", &args) {
+                bridge_for_effect.clone().set_error(Some(e));
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let js_code = format!(
+                r#"
+                window.__dioxus_lifecycle = window.__dioxus_lifecycle || {{}};
+                const id = {id};
+                if (!window.__dioxus_lifecycle[id]) {{
+                    const current = () => {{
+                        if (document.visibilityState === "hidden") return "hidden";
+                        return document.hasFocus() ? "active" : "passive";
+                    }};
+                    const report = (e) => {{
+                        let state = current();
+                        if (e && e.type === "freeze") state = "frozen";
+                        if (e && e.type === "pagehide") state = e.persisted ? "frozen" : "terminated";
+                        if ({callback}) {{
+                            {callback}(state);
+                        }}
+                    }};
+                    for (const [target, name] of {events}) {{
+                        target.addEventListener(name, report, {{ capture: true }});
+                    }}
+                    window.__dioxus_lifecycle[id] = report;
+                    report();
+                }}
+                "#,
+                id = js_string(&callback_id),
+                callback = bridge_for_effect.js_callback(),
+                events = EVENTS,
+            );
+            let mut bridge = bridge_for_effect.clone();
+            spawn(async move {
+                if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                    bridge.set_error(Some(e));
+                }
+            });
+        }
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("unwatchLifecycle", &args) {
+                eprintln!("Failed to stop lifecycle watch: {}", e);
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        eval_detached(&format!(
+            r#"
+            const report = window.__dioxus_lifecycle && window.__dioxus_lifecycle[{id}];
+            if (report) {{
+                for (const [target, name] of {events}) {{
+                    target.removeEventListener(name, report, {{ capture: true }});
+                }}
+                delete window.__dioxus_lifecycle[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id),
+            events = EVENTS,
+        ));
+    });
+
+    PageLifecycle { state, bridge }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+/// One operation of a JSON Patch (RFC 6902), as produced by [`json_diff`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add {
+        path: String,
+        value: serde_json::Value,
+    },
+    Remove {
+        path: String,
+    },
+    Replace {
+        path: String,
+        value: serde_json::Value,
+    },
+}
+
+/// The operations that turn `old` into `new`, applied in order. Objects are compared key
+/// by key and arrays index by index, with elements added or removed at the end.
+pub fn json_diff(old: &serde_json::Value, new: &serde_json::Value) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    diff_at(&mut String::new(), old, new, &mut ops);
+    ops
+}
+
+fn diff_at(
+    path: &mut String,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    ops: &mut Vec<PatchOp>,
+) {
+    use serde_json::Value;
+    if old == new {
+        return;
+    }
+    let len = path.len();
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                push_segment(path, key);
+                match new.get(key) {
+                    Some(new_value) => diff_at(path, old_value, new_value, ops),
+                    None => ops.push(PatchOp::Remove { path: path.clone() }),
+                }
+                path.truncate(len);
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    push_segment(path, key);
+                    ops.push(PatchOp::Add {
+                        path: path.clone(),
+                        value: new_value.clone(),
+                    });
+                    path.truncate(len);
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (index, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                push_segment(path, &index.to_string());
+                diff_at(path, old_value, new_value, ops);
+                path.truncate(len);
+            }
+            for (index, new_value) in new.iter().enumerate().skip(old.len()) {
+                push_segment(path, &index.to_string());
+                ops.push(PatchOp::Add {
+                    path: path.clone(),
+                    value: new_value.clone(),
+                });
+                path.truncate(len);
+            }
+            // From the end, so earlier removals don't shift later indices
+            for index in (new.len()..old.len()).rev() {
+                push_segment(path, &index.to_string());
+                ops.push(PatchOp::Remove { path: path.clone() });
+                path.truncate(len);
+            }
+        }
+        _ => ops.push(PatchOp::Replace {
+            path: path.clone(),
+            value: new.clone(),
+        }),
+    }
+}
+
+/// Appends a JSON Pointer segment, escaping `~` and `/`.
+fn push_segment(path: &mut String, segment: &str) {
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+}
+
+/// Defines `window.dxApplyPatch(doc, ops)`, which returns a patched copy of `doc`.
+pub(crate) const JS_PATCH: &str = r#"
+window.dxApplyPatch = window.dxApplyPatch || ((doc, ops) => {
+    let root = typeof structuredClone === "function" ? structuredClone(doc) : JSON.parse(JSON.stringify(doc ?? null));
+    for (const op of ops) {
+        if (op.path === "") {
+            root = op.op === "remove" ? null : op.value;
+            continue;
+        }
+        const keys = op.path.slice(1).split("/").map((k) => k.replace(/~1/g, "/").replace(/~0/g, "~"));
+        const last = keys.pop();
+        let parent = root;
+        for (const key of keys) parent = parent[key];
+        if (Array.isArray(parent)) {
+            const index = last === "-" ? parent.length : Number(last);
+            if (op.op === "add") parent.splice(index, 0, op.value);
+            else if (op.op === "remove") parent.splice(index, 1);
+            else parent[index] = op.value;
+        } else if (op.op === "remove") {
+            delete parent[last];
+        } else {
+            parent[last] = op.value;
+        }
+    }
+    return root;
+});
+"#;
@@ -0,0 +1,129 @@
+use crate::{eval_as, js_string};
+use serde::{Deserialize, Serialize};
+
+/// Page load timings from the `PerformanceNavigationTiming` entry, in milliseconds since
+/// navigation start.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NavigationTiming {
+    pub dns_ms: f64,
+    pub connect_ms: f64,
+    /// Time to first byte, from sending the request to the first response byte.
+    pub ttfb_ms: f64,
+    pub response_end: f64,
+    pub dom_interactive: f64,
+    pub dom_content_loaded: f64,
+    pub load_event_end: f64,
+    /// Bytes transferred for the document, 0 when served from cache.
+    pub transfer_size: f64,
+}
+
+/// The offset between the Rust clock and the page's `performance.now()` clock, measured
+/// by [`sync_clock`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClockSync {
+    /// Add to a Rust timestamp (ms since the Unix epoch) to get `performance.now()` time.
+    pub offset_ms: f64,
+    /// Round trip of the measuring eval; the offset is accurate to about half of it.
+    pub round_trip_ms: f64,
+}
+
+impl ClockSync {
+    /// Converts a Rust timestamp, as returned by [`rust_now_ms`], to `performance.now()` time.
+    pub fn to_js_time(&self, rust_ms: f64) -> f64 {
+        rust_ms + self.offset_ms
+    }
+
+    /// Converts a `performance.now()` timestamp to Rust time.
+    pub fn to_rust_time(&self, js_ms: f64) -> f64 {
+        js_ms - self.offset_ms
+    }
+}
+
+/// Milliseconds since the Unix epoch on the Rust side, with microsecond precision.
+pub fn rust_now_ms() -> f64 {
+    chrono::Utc::now().timestamp_micros() as f64 / 1000.0
+}
+
+/// Samples `performance.now()`.
+pub async fn performance_now() -> Result<f64, String> {
+    eval_as("return performance.now();").await
+}
+
+/// Measures the clock offset between Rust and JS so timestamps taken on either side, e.g.
+/// when a bridge message is sent and when it's handled, can be compared.
+pub async fn sync_clock() -> Result<ClockSync, String> {
+    let sent = rust_now_ms();
+    let js_now = eval_as::<f64>("return performance.now();").await?;
+    let received = rust_now_ms();
+    Ok(ClockSync {
+        offset_ms: js_now - (sent + received) / 2.0,
+        round_trip_ms: received - sent,
+    })
+}
+
+/// Returns the page's navigation timing, or `None` if the platform doesn't record it.
+pub async fn navigation_timing() -> Result<Option<NavigationTiming>, String> {
+    eval_as(
+        r#"
+        const entries = performance.getEntriesByType
+            ? performance.getEntriesByType("navigation")
+            : [];
+        const n = entries[0];
+        if (!n) {
+            return null;
+        }
+        return {
+            dns_ms: n.domainLookupEnd - n.domainLookupStart,
+            connect_ms: n.connectEnd - n.connectStart,
+            ttfb_ms: n.responseStart - n.requestStart,
+            response_end: n.responseEnd,
+            dom_interactive: n.domInteractive,
+            dom_content_loaded: n.domContentLoadedEventEnd,
+            load_event_end: n.loadEventEnd,
+            transfer_size: n.transferSize || 0,
+        };
+        "#,
+    )
+    .await
+}
+
+/// Records a user-timing mark and returns its `performance.now()` time.
+pub async fn mark(name: &str) -> Result<f64, String> {
+    eval_as(&format!(
+        "return performance.mark({}).startTime;",
+        js_string(name)
+    ))
+    .await
+}
+
+/// Records a user-timing measure between two marks and returns its duration in
+/// milliseconds. A missing `start` means navigation start, a missing `end` means now.
+pub async fn measure(name: &str, start: Option<&str>, end: Option<&str>) -> Result<f64, String> {
+    let js_code = format!(
+        r#"
+        const start = {start};
+        const end = {end};
+        const options = {{}};
+        if (start !== null) options.start = start;
+        if (end !== null) options.end = end;
+        return performance.measure({name}, options).duration;
+        "#,
+        name = js_string(name),
+        start = serde_json::to_string(&start).map_err(|e| format!("Serialization error: {}", e))?,
+        end = serde_json::to_string(&end).map_err(|e| format!("Serialization error: {}", e))?,
+    );
+    eval_as(&js_code).await
+}
+
+/// Removes user-timing marks and measures named `name`, or all of them when `None`.
+pub async fn clear_marks(name: Option<&str>) -> Result<(), String> {
+    let name = match name {
+        Some(name) => js_string(name),
+        None => "undefined".to_string(),
+    };
+    eval_as::<serde_json::Value>(&format!(
+        "performance.clearMarks({name}); performance.clearMeasures({name});"
+    ))
+    .await
+    .map(|_| ())
+}
@@ -0,0 +1,172 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, eval_detached, js_string};
+use crate::{use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// Whether a capability such as the camera or location may be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// Using the capability will ask the user first.
+    Prompt,
+}
+
+/// Checks a permission by its Permissions API name, e.g. `"camera"`, `"microphone"`,
+/// `"geolocation"` or `"notifications"`, without asking the user.
+///
+/// Uses `navigator.permissions` on web and desktop. On Android the app maps the name to
+/// its runtime permissions and checks them with `checkSelfPermission`:
+/// `RustBridge.checkPermission({ name })` answers with `"granted"`, `"denied"` or
+/// `"prompt"`.
+pub async fn query_permission(name: &str) -> Result<PermissionState, String> {
+    #[cfg(target_os = "android")]
+    {
+        let state = crate::android_bridge::call_native_async(
+            "checkPermission",
+            serde_json::json!({ "name": name }),
+        )
+        .await?;
+        serde_json::from_value(state).map_err(|e| format!("Deserialization error: {}", e))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        eval_as(&format!(
+            r#"
+            if (!navigator.permissions) {{
+                throw new Error("The Permissions API is not supported");
+            }}
+            const status = await navigator.permissions.query({{ name: {name} }});
+            return status.state;
+            "#,
+            name = js_string(name)
+        ))
+        .await
+    }
+}
+
+/// A permission's current state, created by [`use_permission`].
+#[derive(Clone)]
+pub struct PermissionStatus {
+    /// `None` until the first check completes.
+    pub state: Signal<Option<PermissionState>>,
+    pub bridge: JsBridge<PermissionState>,
+}
+
+impl PermissionStatus {
+    pub fn get_state(&self) -> Option<PermissionState> {
+        *self.state.read()
+    }
+
+    pub fn is_granted(&self) -> bool {
+        self.get_state() == Some(PermissionState::Granted)
+    }
+
+    /// Returns a stream of every state change received after this call.
+    pub fn changes(&self) -> UnboundedReceiver<PermissionState> {
+        self.bridge.stream()
+    }
+}
+
+/// Follows a permission until the component unmounts, so features gated on it react
+/// when the user grants or revokes it mid-session. The name is read on the first render.
+///
+/// Listens to the `change` event of the `navigator.permissions` status on web and
+/// desktop. On Android the app checks again whenever the activity resumes, e.g. on return
+/// from the system settings: `RustBridge.watchPermission({ name, callback_id })` posts the
+/// state like [`query_permission`] does, now and on every change, until
+/// `unwatchPermission({ callback_id })`.
+pub fn use_permission(name: &str) -> PermissionStatus {
+    let name = use_hook(|| name.to_string());
+    let mut state = use_signal(|| None);
+    let bridge = use_js_listener::<PermissionState>(move |update| {
+        if *state.peek() != Some(update) {
+            state.set(Some(update));
+        }
+    });
+
+    let bridge_for_effect = bridge.clone();
+    let name_for_effect = name.clone();
+    use_effect(move || {
+        let callback_id = bridge_for_effect.callback_id();
+
+        #[cfg(target_os = "android")]
+        {
+            // The app posts the current state and every change to this bridge's callback ID
+            let args = serde_json::json!({ "callback_id": callback_id, "name": name_for_effect });
+            if let Err(e) = crate::android_bridge::call_native("watchPermission", &args) {
+                bridge_for_effect.clone().set_error(Some(e));
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        {
+            let js_code = format!(
+                r#"
+                window.__dioxus_permissions = window.__dioxus_permissions || {{}};
+                const id = {id};
+                if (!window.__dioxus_permissions[id]) {{
+                    if (!navigator.permissions) {{
+                        throw new Error("The Permissions API is not supported");
+                    }}
+                    const watch = {{}};
+                    window.__dioxus_permissions[id] = watch;
+                    const status = await navigator.permissions.query({{ name: {name} }});
+                    const report = () => {{
+                        if ({callback}) {{
+                            {callback}(status.state);
+                        }}
+                    }};
+                    if (window.__dioxus_permissions[id] !== watch) {{
+                        return;
+                    }}
+                    status.addEventListener("change", report);
+                    Object.assign(watch, {{ status, report }});
+                    report();
+                }}
+                "#,
+                id = js_string(&callback_id),
+                name = js_string(&name_for_effect),
+                callback = bridge_for_effect.js_callback(),
+            );
+            let mut bridge = bridge_for_effect.clone();
+            spawn(async move {
+                if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                    bridge.set_error(Some(e));
+                }
+            });
+        }
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("unwatchPermission", &args) {
+                eprintln!("Failed to stop permission watch: {}", e);
+            }
+        }
+
+        #[cfg(not(target_os = "android"))]
+        eval_detached(&format!(
+            r#"
+            const watch = window.__dioxus_permissions && window.__dioxus_permissions[{id}];
+            if (watch) {{
+                if (watch.status) {{
+                    watch.status.removeEventListener("change", watch.report);
+                }}
+                delete window.__dioxus_permissions[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    PermissionStatus { state, bridge }
+}
@@ -0,0 +1,76 @@
+use crate::origin::{MessageSource, OriginPolicy};
+use crate::EnumTagging;
+use std::collections::HashMap;
+
+/// Which topics page JS may send to a bridge, and from where; see
+/// [`BridgeOptions::permissions`](crate::BridgeOptions::permissions).
+///
+/// A message's topic is its `"topic"` field, or its variant name when it is a serde enum:
+/// the single key of an externally tagged object like `{ "WriteFile": { ... } }`, or the
+/// string of a unit variant, or the tag of an object when the bridge has an
+/// [`EnumTagging`](crate::EnumTagging). Messages without a topic, or with one that isn't listed, are
+/// rejected, so a compromised JS dependency can only reach the handlers it was granted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Permissions {
+    topics: HashMap<String, Option<OriginPolicy>>,
+}
+
+impl Permissions {
+    /// Denies every topic until some are allowed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows `topic` from any sender the bridge accepts.
+    pub fn allow(mut self, topic: impl Into<String>) -> Self {
+        self.topics.insert(topic.into(), None);
+        self
+    }
+
+    /// Allows `topic` only from senders that `policy` accepts, e.g. the bridge's own
+    /// document but not embedded frames.
+    pub fn allow_from(mut self, topic: impl Into<String>, policy: OriginPolicy) -> Self {
+        self.topics.insert(topic.into(), Some(policy));
+        self
+    }
+
+    pub fn allows(&self, topic: &str, source: &MessageSource) -> bool {
+        match self.topics.get(topic) {
+            Some(Some(policy)) => policy.allows(source),
+            Some(None) => true,
+            None => false,
+        }
+    }
+
+    /// The allowed topics, sorted.
+    pub fn topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self.topics.keys().cloned().collect();
+        topics.sort();
+        topics
+    }
+
+    pub(crate) fn check(
+        &self,
+        message: &serde_json::Value,
+        source: &MessageSource,
+        tagging: &EnumTagging,
+    ) -> Result<(), String> {
+        let topic = topic(message, tagging).ok_or("Rejected message without a topic")?;
+        if self.allows(topic, source) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Rejected message for topic \"{}\" from {}",
+                topic, source
+            ))
+        }
+    }
+}
+
+fn topic<'v>(message: &'v serde_json::Value, tagging: &EnumTagging) -> Option<&'v str> {
+    let field = message.get("topic").map(serde_json::Value::as_str);
+    match tagging {
+        EnumTagging::External => field.unwrap_or_else(|| tagging.variant_name(message)),
+        _ => tagging.variant_name(message).or(field.flatten()),
+    }
+}
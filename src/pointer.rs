@@ -0,0 +1,290 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerKind {
+    Mouse,
+    Pen,
+    Touch,
+}
+
+/// Where a pointer was at one moment, in CSS pixels relative to the target element.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PointerSample {
+    pub x: f64,
+    pub y: f64,
+    /// From 0.0 to 1.0; 0.5 while a button is held on hardware without pressure.
+    pub pressure: f64,
+    /// Pen tilt in degrees, -90 to 90.
+    pub tilt_x: f64,
+    pub tilt_y: f64,
+    /// Contact size of a touch, in CSS pixels.
+    pub width: f64,
+    pub height: f64,
+    /// Milliseconds, from the page's clock.
+    pub timestamp: f64,
+}
+
+/// A typed pointer event from the target element. Touches on multi-touch screens each get
+/// their own `pointer_id` for as long as they stay down.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PointerInput {
+    Down {
+        pointer_id: i32,
+        kind: PointerKind,
+        /// The first touch of a multi-touch gesture, or the mouse.
+        primary: bool,
+        /// The button that changed: 0 main, 1 middle, 2 secondary, 5 pen eraser.
+        button: i16,
+        sample: PointerSample,
+    },
+    /// Moves since the last message, oldest first, including the intermediate positions
+    /// the browser coalesced into one event.
+    Move {
+        pointer_id: i32,
+        kind: PointerKind,
+        /// Bit mask of held buttons.
+        buttons: u16,
+        samples: Vec<PointerSample>,
+    },
+    Up {
+        pointer_id: i32,
+        sample: PointerSample,
+    },
+    /// The platform took over the pointer, e.g. for a system gesture.
+    Cancel { pointer_id: i32 },
+}
+
+/// How moves are delivered by [`use_pointer_events_with_options`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PointerOptions {
+    /// Sends each pointer's moves at most once per animation frame, bounding the message
+    /// rate on high-frequency digitizers. Otherwise every `pointermove` is sent at once.
+    pub coalesce: bool,
+    /// Most samples kept per pointer and message; older ones are dropped first.
+    pub max_samples: usize,
+    /// Also reports moves while no button is held, e.g. for hover effects.
+    pub hover: bool,
+    /// Sets `touch-action: none` on the target so touches draw instead of scrolling, and
+    /// captures pointers so strokes continue outside it.
+    pub capture: bool,
+}
+
+impl Default for PointerOptions {
+    fn default() -> Self {
+        Self {
+            coalesce: true,
+            max_samples: 64,
+            hover: false,
+            capture: true,
+        }
+    }
+}
+
+/// A pointer that is down on the target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActivePointer {
+    pub pointer_id: i32,
+    pub kind: PointerKind,
+    pub sample: PointerSample,
+}
+
+/// Pointer input of an element, created by [`use_pointer_events`].
+#[derive(Clone)]
+pub struct PointerEvents {
+    /// Pointers that are currently down, in the order they went down.
+    pub active: Signal<Vec<ActivePointer>>,
+    pub bridge: JsBridge<PointerInput>,
+}
+
+impl PointerEvents {
+    pub fn get_active(&self) -> Vec<ActivePointer> {
+        self.active.read().clone()
+    }
+
+    /// Returns a stream of every event received after this call, e.g. for a drawing loop
+    /// that renders strokes to a canvas.
+    pub fn events(&self) -> UnboundedReceiver<PointerInput> {
+        self.bridge.stream()
+    }
+}
+
+/// Streams pointer, mouse, pen and touch input on the first element matching `selector`
+/// until the component unmounts, with moves coalesced per animation frame.
+///
+/// Android's WebView reports pressure and a separate `pointer_id` for every touch too.
+pub fn use_pointer_events(selector: &str) -> PointerEvents {
+    use_pointer_events_with_options(selector, PointerOptions::default())
+}
+
+pub fn use_pointer_events_with_options(selector: &str, options: PointerOptions) -> PointerEvents {
+    let mut active = use_signal(Vec::<ActivePointer>::new);
+    let bridge = use_js_listener::<PointerInput>(move |input| match input {
+        PointerInput::Down {
+            pointer_id,
+            kind,
+            sample,
+            ..
+        } => active.with_mut(|active| {
+            active.retain(|pointer| pointer.pointer_id != pointer_id);
+            active.push(ActivePointer {
+                pointer_id,
+                kind,
+                sample,
+            });
+        }),
+        PointerInput::Move {
+            pointer_id,
+            samples,
+            ..
+        } => {
+            let last = samples.last().copied();
+            let mut active = active.write();
+            let pointer = active.iter_mut().find(|p| p.pointer_id == pointer_id);
+            if let (Some(pointer), Some(last)) = (pointer, last) {
+                pointer.sample = last;
+            }
+        }
+        PointerInput::Up { pointer_id, .. } | PointerInput::Cancel { pointer_id } => {
+            active.with_mut(|active| active.retain(|pointer| pointer.pointer_id != pointer_id))
+        }
+    });
+
+    let selector = use_hook(|| selector.to_string());
+    let options = use_hook(move || options);
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let mut bridge = bridge_for_effect.clone();
+        let js_code = format!(
+            r#"
+            window.__dioxus_pointers = window.__dioxus_pointers || {{}};
+            const id = {id};
+            if (!window.__dioxus_pointers[id]) {{
+                const target = document.querySelector({selector});
+                if (!target) {{
+                    throw new Error("use_pointer_events: no element matches " + {selector});
+                }}
+                const options = {options};
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const sample = (e) => {{
+                    const rect = target.getBoundingClientRect();
+                    return {{
+                        x: e.clientX - rect.left,
+                        y: e.clientY - rect.top,
+                        pressure: e.pressure,
+                        tilt_x: e.tiltX || 0,
+                        tilt_y: e.tiltY || 0,
+                        width: e.width || 1,
+                        height: e.height || 1,
+                        timestamp: e.timeStamp,
+                    }};
+                }};
+                // Moves waiting for the next frame, by pointer ID
+                const pending = new Map();
+                let frame = null;
+                const flush = (pointerId) => {{
+                    for (const [key, move] of pending) {{
+                        if (pointerId !== undefined && key !== pointerId) continue;
+                        pending.delete(key);
+                        emit(move);
+                    }}
+                }};
+                const handlers = {{
+                    pointerdown: (e) => {{
+                        if (options.capture) target.setPointerCapture(e.pointerId);
+                        flush(e.pointerId);
+                        emit({{
+                            event: "down",
+                            pointer_id: e.pointerId,
+                            kind: e.pointerType || "mouse",
+                            primary: e.isPrimary,
+                            button: e.button,
+                            sample: sample(e),
+                        }});
+                    }},
+                    pointermove: (e) => {{
+                        if (!e.buttons && !options.hover) return;
+                        const events = e.getCoalescedEvents ? e.getCoalescedEvents() : [];
+                        const samples = (events.length ? events : [e]).map(sample);
+                        let move = pending.get(e.pointerId);
+                        if (!move) {{
+                            move = {{ event: "move", pointer_id: e.pointerId, kind: e.pointerType || "mouse", buttons: 0, samples: [] }};
+                            pending.set(e.pointerId, move);
+                        }}
+                        move.buttons = e.buttons;
+                        move.samples.push(...samples);
+                        if (move.samples.length > options.max_samples) {{
+                            move.samples.splice(0, move.samples.length - options.max_samples);
+                        }}
+                        if (!options.coalesce) {{
+                            flush(e.pointerId);
+                        }} else if (frame === null) {{
+                            frame = requestAnimationFrame(() => {{
+                                frame = null;
+                                flush();
+                            }});
+                        }}
+                    }},
+                    pointerup: (e) => {{
+                        flush(e.pointerId);
+                        emit({{ event: "up", pointer_id: e.pointerId, sample: sample(e) }});
+                    }},
+                    pointercancel: (e) => {{
+                        pending.delete(e.pointerId);
+                        emit({{ event: "cancel", pointer_id: e.pointerId }});
+                    }},
+                }};
+                const touchAction = target.style.touchAction;
+                if (options.capture) target.style.touchAction = "none";
+                for (const [name, handler] of Object.entries(handlers)) {{
+                    target.addEventListener(name, handler);
+                }}
+                window.__dioxus_pointers[id] = {{
+                    target,
+                    handlers,
+                    touchAction,
+                    stop: () => frame !== null && cancelAnimationFrame(frame),
+                }};
+            }}
+            "#,
+            id = js_string(&bridge.callback_id()),
+            selector = js_string(&selector),
+            options = serde_json::to_string(&options).unwrap_or_else(|_| "{}".to_string()),
+            callback = bridge.js_callback(),
+        );
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const state = window.__dioxus_pointers && window.__dioxus_pointers[{id}];
+            if (state) {{
+                state.stop();
+                for (const [name, handler] of Object.entries(state.handlers)) {{
+                    state.target.removeEventListener(name, handler);
+                }}
+                state.target.style.touchAction = state.touchAction;
+                delete window.__dioxus_pointers[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    PointerEvents { active, bridge }
+}
@@ -0,0 +1,141 @@
+use crate::core::web_shim;
+use crate::{use_js_bridge, FromJs, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use gloo_utils::format::JsValueSerdeExt;
+use serde::Serialize;
+use std::fmt::Debug;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::{MessageChannel, MessageEvent, MessagePort};
+
+/// Rust's end of the channel. The closure must live as long as the port delivers to it.
+struct PortState {
+    port: MessagePort,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+/// A `MessageChannel` between Rust and JS, created by [`use_js_port`]. Web only.
+///
+/// Unlike the window-global callback used by [`JsBridge`], each port is an ordered,
+/// isolated channel, messages in either direction skip `eval`, and `ArrayBuffer`s are
+/// transferred instead of copied through JSON.
+///
+/// JS gets the other end as `window.dxBridge.port(id)` (see [`JsPort::js_port`]):
+/// `port.postMessage(value)` delivers JSON-compatible values to the bridge, and
+/// `port.postMessage(buffer, [buffer])` delivers bytes to [`JsPort::binary`]. Messages
+/// from Rust arrive through `port.onmessage`.
+#[derive(Clone)]
+pub struct JsPort<T: FromJs + Clone + 'static> {
+    pub bridge: JsBridge<T>,
+    state: CopyValue<Option<PortState>>,
+    binary_subscribers: CopyValue<Vec<UnboundedSender<Vec<u8>>>>,
+}
+
+impl<T: FromJs + Clone + 'static> JsPort<T> {
+    /// The JS expression for the JS end of the channel.
+    pub fn js_port(&self) -> String {
+        format!(
+            "window.dxBridge.port({})",
+            crate::js_string(&self.bridge.callback_id())
+        )
+    }
+
+    /// Returns a stream of every byte message received after this call.
+    pub fn binary(&self) -> UnboundedReceiver<Vec<u8>> {
+        let (tx, rx) = futures_channel::mpsc::unbounded();
+        let mut binary_subscribers = self.binary_subscribers;
+        binary_subscribers.write().push(tx);
+        rx
+    }
+
+    /// Posts `data` to JS as a structured-clone object.
+    pub fn send<S: Serialize>(&self, data: &S) -> Result<(), String> {
+        let value = JsValue::from_serde(data).map_err(|e| format!("Serialization error: {}", e))?;
+        self.post(&value, None)
+    }
+
+    /// Posts `bytes` to JS as an `ArrayBuffer`, transferring it to the JS side.
+    pub fn send_bytes(&self, bytes: &[u8]) -> Result<(), String> {
+        let buffer = js_sys::Uint8Array::from(bytes).buffer();
+        self.post(&buffer, Some(js_sys::Array::of1(&buffer)))
+    }
+
+    fn post(&self, value: &JsValue, transfer: Option<js_sys::Array>) -> Result<(), String> {
+        let state = self.state.read();
+        let port = &state.as_ref().ok_or("Port is closed")?.port;
+        match transfer {
+            Some(transfer) => port.post_message_with_transferable(value, &transfer),
+            None => port.post_message(value),
+        }
+        .map_err(|e| format!("postMessage error: {:?}", e))
+    }
+}
+
+/// Opens a `MessageChannel` to JS for the lifetime of the component. Web only.
+pub fn use_js_port<T>() -> JsPort<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    let bridge = use_js_bridge::<T>();
+    let mut state = use_hook(|| CopyValue::new(None::<PortState>));
+    let binary_subscribers = use_hook(|| CopyValue::new(Vec::<UnboundedSender<Vec<u8>>>::new()));
+
+    let bridge_for_hook = bridge.clone();
+    use_hook(move || {
+        let mut bridge = bridge_for_hook;
+        let channel = match MessageChannel::new() {
+            Ok(channel) => channel,
+            Err(e) => {
+                bridge.set_error(Some(format!("Failed to create MessageChannel: {:?}", e)));
+                return;
+            }
+        };
+
+        let mut bridge_for_port = bridge.clone();
+        let mut subscribers = binary_subscribers;
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let data = event.data();
+            let bytes = if let Some(buffer) = data.dyn_ref::<js_sys::ArrayBuffer>() {
+                Some(js_sys::Uint8Array::new(buffer).to_vec())
+            } else {
+                data.dyn_ref::<js_sys::Uint8Array>()
+                    .map(|view| view.to_vec())
+            };
+            match bytes {
+                Some(bytes) => subscribers
+                    .write()
+                    .retain(|tx| tx.unbounded_send(bytes.clone()).is_ok()),
+                None => match data.into_serde::<serde_json::Value>() {
+                    Ok(value) => bridge_for_port.receive_json_value(value),
+                    Err(e) => {
+                        bridge_for_port.set_error(Some(format!("Deserialization error: {e}")))
+                    }
+                },
+            }
+        });
+
+        let port = channel.port1();
+        port.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        web_shim::register_port(&bridge.callback_id(), &channel.port2());
+        state.set(Some(PortState {
+            port,
+            _on_message: on_message,
+        }));
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        if let Some(state) = state.write().take() {
+            state.port.set_onmessage(None);
+            state.port.close();
+        }
+        web_shim::unregister_port(&callback_id);
+    });
+
+    JsPort {
+        bridge,
+        state,
+        binary_subscribers,
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(not(target_os = "android"))]
+use crate::{eval_as, js_string};
+use serde::{Deserialize, Serialize};
+
+/// Page setup used by [`print_html`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrintOptions {
+    /// The document title, which browsers print in the header and Android uses as the
+    /// print job name.
+    pub title: Option<String>,
+    pub landscape: bool,
+    /// Extra CSS applied only while printing.
+    pub css: Option<String>,
+}
+
+// Resolves once the print dialog of `win` is closed.
+#[cfg(not(target_os = "android"))]
+const JS_PRINT_WINDOW: &str = r#"
+const printWindow = (win) => new Promise((resolve) => {
+    win.addEventListener("afterprint", () => resolve(null), { once: true });
+    win.print();
+});
+"#;
+
+/// Opens the print dialog for the current page, and resolves when it closes.
+///
+/// Uses `window.print` on web and desktop, and `PrintManager` with the WebView's print
+/// adapter on Android: `RustBridge.printPage({})` answers with `null` once the dialog
+/// closes.
+pub async fn print_page() -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android_bridge::call_native_async("printPage", serde_json::json!({}))
+            .await
+            .map(|_| ())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        eval_as::<serde_json::Value>(&format!(
+            r#"
+            {helpers}
+            return await printWindow(window);
+            "#,
+            helpers = JS_PRINT_WINDOW
+        ))
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Prints an HTML document instead of the current page, e.g. a receipt or a report, and
+/// resolves when the print dialog closes.
+///
+/// The document is loaded in a hidden frame on web and desktop. On Android the app
+/// renders it in an offscreen WebView and prints that through `PrintManager`:
+/// `RustBridge.printHtml({ html, title, landscape, css })` answers with `null` once the
+/// dialog closes.
+pub async fn print_html(html: &str, options: &PrintOptions) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let mut args =
+            serde_json::to_value(options).map_err(|e| format!("Serialization error: {}", e))?;
+        if let Some(args) = args.as_object_mut() {
+            args.insert("html".to_string(), html.into());
+        }
+        crate::android_bridge::call_native_async("printHtml", args)
+            .await
+            .map(|_| ())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let options =
+            serde_json::to_string(options).map_err(|e| format!("Serialization error: {}", e))?;
+        eval_as::<serde_json::Value>(&format!(
+            r#"
+            {helpers}
+            const options = {options};
+            const frame = document.createElement("iframe");
+            frame.setAttribute("aria-hidden", "true");
+            frame.style.cssText = "position:fixed;width:0;height:0;border:0;visibility:hidden";
+            const loaded = new Promise((resolve, reject) => {{
+                frame.onload = resolve;
+                frame.onerror = () => reject(new Error("Failed to load the document to print"));
+            }});
+            frame.srcdoc = {html};
+            document.body.appendChild(frame);
+            try {{
+                await loaded;
+                const doc = frame.contentDocument;
+                if (options.title !== null) {{
+                    doc.title = options.title;
+                }}
+                const style = doc.createElement("style");
+                style.textContent = "@media print {{ " +
+                    (options.landscape ? "@page {{ size: landscape; }} " : "") +
+                    (options.css ?? "") + " }}";
+                doc.head.appendChild(style);
+                return await printWindow(frame.contentWindow);
+            }} finally {{
+                frame.remove();
+            }}
+            "#,
+            helpers = JS_PRINT_WINDOW,
+            html = js_string(html),
+        ))
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Retries sends and evals that fail for transient reasons, with exponential backoff; see
+/// [`BridgeOptions::retry`](crate::BridgeOptions::retry).
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first.
+    pub max_attempts: u32,
+    /// The wait before the second attempt, doubled for each one after.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    retryable: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, 50 ms apart at first, retrying [`is_transient`] errors.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            retryable: Arc::new(is_transient),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Replaces the classification of which errors are worth retrying.
+    pub fn retry_if(mut self, retryable: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    /// The wait after failed attempt number `attempt`, counting from 1.
+    fn backoff_after(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RetryPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_attempts == other.max_attempts
+            && self.initial_backoff == other.initial_backoff
+            && self.max_backoff == other.max_backoff
+            && Arc::ptr_eq(&self.retryable, &other.retryable)
+    }
+}
+
+/// Errors from a JVM that isn't attached yet or a WebView that is busy or still loading.
+/// Serialization errors and shut down bridges aren't transient.
+pub fn is_transient(error: &str) -> bool {
+    const TRANSIENT: &[&str] = &[
+        "Failed to get JavaVM",
+        "Failed to attach to JVM",
+        "Failed to call evalJs",
+        "Failed to call onMessageFromRust",
+        "threw an exception",
+        // The desktop and liveview eval channel dropped or isn't connected yet
+        "Communication(",
+        "Finished",
+    ];
+    TRANSIENT.iter().any(|transient| error.contains(transient))
+}
+
+/// Runs `attempt` until it succeeds, fails with an error the policy doesn't retry, or runs
+/// out of attempts. Without a policy it runs once.
+pub(crate) async fn run<T, F, Fut>(
+    policy: Option<&RetryPolicy>,
+    mut attempt: F,
+) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let Some(policy) = policy else {
+        return attempt().await;
+    };
+    let mut attempts = 1;
+    loop {
+        match attempt().await {
+            Err(e) if attempts < policy.max_attempts && (policy.retryable)(&e) => {
+                crate::watchdog::sleep(policy.backoff_after(attempts)).await;
+                attempts += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+
+    #[test]
+    fn backoff_doubles_up_to_the_max() {
+        let policy =
+            RetryPolicy::default().backoff(Duration::from_millis(50), Duration::from_millis(300));
+        let waits: Vec<_> = (1..=5)
+            .map(|attempt| policy.backoff_after(attempt))
+            .collect();
+        let millis = [50, 100, 200, 300, 300].map(Duration::from_millis);
+        assert_eq!(waits, millis);
+        assert_eq!(policy.backoff_after(u32::MAX), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn transient_errors_are_recognized() {
+        assert!(is_transient("Failed to attach to JVM: not attached"));
+        assert!(is_transient(
+            "Failed to call evalJs: java.lang.IllegalStateException"
+        ));
+        assert!(is_transient("Communication(\"channel closed\")"));
+        assert!(!is_transient("Serialization error: key must be a string"));
+        assert!(!is_transient("Bridge is shut down"));
+    }
+
+    #[test]
+    fn permanent_errors_fail_at_once() {
+        let policy = RetryPolicy::default().max_attempts(5);
+        let mut attempts = 0;
+        let result = run::<(), _, _>(Some(&policy), || {
+            attempts += 1;
+            async { Err("Serialization error".to_string()) }
+        })
+        .now_or_never();
+        assert_eq!(result, Some(Err("Serialization error".to_string())));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn custom_classification_replaces_the_default() {
+        let policy = RetryPolicy::default().retry_if(|error| error == "busy");
+        assert!((policy.retryable)("busy"));
+        assert!(!(policy.retryable)("Failed to call evalJs"));
+    }
+}
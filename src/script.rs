@@ -0,0 +1,67 @@
+use crate::{eval_as, js_string};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Scripts installed through [`install_script`], by content hash, to install again in a
+/// fresh JS context.
+static SCRIPTS: Mutex<BTreeMap<String, Arc<str>>> = Mutex::new(BTreeMap::new());
+
+fn content_hash(source: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Runs `source` in the page's global scope, as a `<script>` element, unless it is marked
+/// installed for its content hash.
+fn guarded_install(hash: &str, source: &str) -> String {
+    format!(
+        r#"
+        window.__dioxus_scripts = window.__dioxus_scripts || {{}};
+        if (!window.__dioxus_scripts[{hash}]) {{
+            const script = document.createElement("script");
+            script.textContent = {source};
+            document.head.appendChild(script);
+            script.remove();
+            window.__dioxus_scripts[{hash}] = true;
+        }}
+        "#,
+        hash = js_string(hash),
+        source = js_string(source),
+    )
+}
+
+/// Installs a JS helper, e.g. `include_str!("helper.js")`, once per document.
+///
+/// The script runs in the global scope, so its top-level functions become globals. Calls
+/// for a script the document already has only check a guard keyed by the content hash,
+/// without sending the script again. After a navigation or an Android renderer restart,
+/// the next call (or the bridge watchdog) installs it again.
+pub async fn install_script(source: &str) -> Result<(), String> {
+    let hash = content_hash(source);
+    SCRIPTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(hash.clone())
+        .or_insert_with(|| source.into());
+    let installed = eval_as::<bool>(&format!(
+        "return !!(window.__dioxus_scripts && window.__dioxus_scripts[{}]);",
+        js_string(&hash)
+    ))
+    .await?;
+    if !installed {
+        eval_as::<serde_json::Value>(&guarded_install(&hash, source)).await?;
+    }
+    Ok(())
+}
+
+/// Installs every script from [`install_script`] again, after JS lost them.
+#[cfg(target_os = "android")]
+pub(crate) fn reinstall_all() {
+    let scripts = SCRIPTS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    for (hash, source) in scripts {
+        crate::eval_detached(&guarded_install(&hash, &source));
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(any(
+    target_arch = "wasm32",
+    not(any(target_os = "android", feature = "secure-storage", feature = "desktop"))
+))]
+use crate::{eval_as, js_string};
+
+// Keys are namespaced in localStorage, and a warning is logged once per page.
+#[cfg(any(
+    target_arch = "wasm32",
+    not(any(target_os = "android", feature = "secure-storage", feature = "desktop"))
+))]
+const JS_LOCAL_FALLBACK: &str = r#"
+if (!window.__dxSecureWarned) {
+    window.__dxSecureWarned = true;
+    console.warn("secure storage is not available here; values are kept in plain localStorage");
+}
+const storageKey = "dx-secure:" + key;
+"#;
+
+/// Desktop apps without a credential store fail rather than keep secrets in the webview's
+/// localStorage.
+#[cfg(all(
+    feature = "desktop",
+    not(any(
+        target_arch = "wasm32",
+        target_os = "android",
+        feature = "secure-storage"
+    ))
+))]
+const NO_DESKTOP_BACKEND: &str =
+    "Secure storage on desktop needs the secure-storage feature for the OS credential store";
+
+/// The keyring service the desktop entries are stored under: the executable's name.
+#[cfg(all(
+    feature = "secure-storage",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+fn entry(key: &str) -> Result<keyring::Entry, String> {
+    let service = std::env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "dx_use_js_bridge".to_string());
+    keyring::Entry::new(&service, key).map_err(|e| format!("Keyring error: {}", e))
+}
+
+/// Stores a secret such as an auth token with the best facility the platform has.
+///
+/// Android keeps it in `EncryptedSharedPreferences` backed by the Keystore, through
+/// `RustBridge.secureSet({ key, value })`, which answers with `null`. Desktop uses the OS
+/// credential store (Keychain, Credential Manager, kernel keyring) with the
+/// `secure-storage` feature, and fails without it. On the web the value goes to
+/// localStorage, and a warning is logged in the console.
+pub async fn secure_set(key: &str, value: &str) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android_bridge::call_native_async(
+            "secureSet",
+            serde_json::json!({ "key": key, "value": value }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    #[cfg(all(
+        feature = "secure-storage",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        entry(key)?
+            .set_password(value)
+            .map_err(|e| format!("Keyring error: {}", e))
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(
+            target_arch = "wasm32",
+            target_os = "android",
+            feature = "secure-storage"
+        ))
+    ))]
+    {
+        let _ = (key, value);
+        Err(NO_DESKTOP_BACKEND.to_string())
+    }
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        not(any(target_os = "android", feature = "secure-storage", feature = "desktop"))
+    ))]
+    {
+        eval_as::<serde_json::Value>(&format!(
+            r#"
+            const key = {key};
+            {fallback}
+            localStorage.setItem(storageKey, {value});
+            return null;
+            "#,
+            key = js_string(key),
+            value = js_string(value),
+            fallback = JS_LOCAL_FALLBACK,
+        ))
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Reads a secret stored by [`secure_set`], or `None` if there isn't one.
+///
+/// On Android, `RustBridge.secureGet({ key })` answers with the value or `null`.
+pub async fn secure_get(key: &str) -> Result<Option<String>, String> {
+    #[cfg(target_os = "android")]
+    {
+        let value = crate::android_bridge::call_native_async(
+            "secureGet",
+            serde_json::json!({ "key": key }),
+        )
+        .await?;
+        serde_json::from_value(value).map_err(|e| format!("Deserialization error: {}", e))
+    }
+
+    #[cfg(all(
+        feature = "secure-storage",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        match entry(key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Keyring error: {}", e)),
+        }
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(
+            target_arch = "wasm32",
+            target_os = "android",
+            feature = "secure-storage"
+        ))
+    ))]
+    {
+        let _ = key;
+        Err(NO_DESKTOP_BACKEND.to_string())
+    }
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        not(any(target_os = "android", feature = "secure-storage", feature = "desktop"))
+    ))]
+    {
+        eval_as(&format!(
+            r#"
+            const key = {key};
+            {fallback}
+            return localStorage.getItem(storageKey);
+            "#,
+            key = js_string(key),
+            fallback = JS_LOCAL_FALLBACK,
+        ))
+        .await
+    }
+}
+
+/// Removes a secret stored by [`secure_set`]. Removing a missing key succeeds.
+///
+/// On Android, `RustBridge.secureDelete({ key })` answers with `null`.
+pub async fn secure_delete(key: &str) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android_bridge::call_native_async("secureDelete", serde_json::json!({ "key": key }))
+            .await
+            .map(|_| ())
+    }
+
+    #[cfg(all(
+        feature = "secure-storage",
+        not(any(target_arch = "wasm32", target_os = "android"))
+    ))]
+    {
+        match entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Keyring error: {}", e)),
+        }
+    }
+
+    #[cfg(all(
+        feature = "desktop",
+        not(any(
+            target_arch = "wasm32",
+            target_os = "android",
+            feature = "secure-storage"
+        ))
+    ))]
+    {
+        let _ = key;
+        Err(NO_DESKTOP_BACKEND.to_string())
+    }
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        not(any(target_os = "android", feature = "secure-storage", feature = "desktop"))
+    ))]
+    {
+        eval_as::<serde_json::Value>(&format!(
+            r#"
+            const key = {key};
+            {fallback}
+            localStorage.removeItem(storageKey);
+            return null;
+            "#,
+            key = js_string(key),
+            fallback = JS_LOCAL_FALLBACK,
+        ))
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,210 @@
+use crate::envelope::Envelope;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+
+/// How messages are protected by [`BridgeOptions::security`](crate::BridgeOptions::security).
+///
+/// Each bridge generates a random key and hands it to JS once, as
+/// `window.dxSecurity.takeKey(callbackId)`. The app's JS should take it on startup, before
+/// untrusted scripts run; without it, scripts can't produce messages Rust accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Security {
+    /// Messages carry their JSON as text plus an HMAC-SHA256 `"sig"` of it and of the
+    /// envelope's `"v"` and `"id"`.
+    Sign,
+    /// Messages carry their JSON encrypted with AES-256-GCM, plus the `"iv"` used. The
+    /// envelope's `"v"` and `"id"` are authenticated as associated data.
+    Encrypt,
+}
+
+/// A bridge's key and the protection it's used for.
+#[derive(Clone)]
+pub(crate) struct Seal {
+    mode: Security,
+    key: Arc<[u8; 32]>,
+}
+
+impl Seal {
+    pub fn generate(mode: Security) -> Result<Self, String> {
+        let mut key = [0u8; 32];
+        getrandom::getrandom(&mut key).map_err(|e| format!("Failed to generate key: {}", e))?;
+        Ok(Self {
+            mode,
+            key: Arc::new(key),
+        })
+    }
+
+    /// Protects `payload` sent as message `id` of schema `version`, returning the envelope
+    /// field to add with its value, and the text to carry as `data`.
+    pub fn seal(
+        &self,
+        payload: &str,
+        version: Option<u32>,
+        id: Option<u64>,
+    ) -> Result<(&'static str, String, String), String> {
+        let header = associated_data(version, id);
+        match self.mode {
+            Security::Sign => Ok(("sig", self.sign(&header, payload)?, payload.to_string())),
+            Security::Encrypt => {
+                let mut iv = [0u8; 12];
+                getrandom::getrandom(&mut iv)
+                    .map_err(|e| format!("Failed to generate IV: {}", e))?;
+                let ciphertext = self
+                    .cipher()
+                    .encrypt(
+                        Nonce::from_slice(&iv),
+                        Payload {
+                            msg: payload.as_bytes(),
+                            aad: header.as_bytes(),
+                        },
+                    )
+                    .map_err(|_| "Encryption failed".to_string())?;
+                Ok((
+                    "iv",
+                    crate::binary::encode(&iv),
+                    crate::binary::encode(&ciphertext),
+                ))
+            }
+        }
+    }
+
+    /// Verifies or decrypts a message from JS, returning the text it carries. Messages
+    /// without a signature or IV, or whose `"v"` or `"id"` were changed, are rejected.
+    pub fn unseal(&self, envelope: &Envelope) -> Result<String, String> {
+        let data = envelope
+            .data
+            .as_str()
+            .ok_or("Rejected message: protected data must be a string")?;
+        let header = associated_data(envelope.v, envelope.id);
+        match self.mode {
+            Security::Sign => {
+                let sig = envelope.sig.as_deref().ok_or("Rejected unsigned message")?;
+                self.mac()?
+                    .chain_update(header.as_bytes())
+                    .chain_update(data.as_bytes())
+                    .verify_slice(&crate::binary::decode(sig)?)
+                    .map_err(|_| "Rejected message with an invalid signature".to_string())?;
+                Ok(data.to_string())
+            }
+            Security::Encrypt => {
+                let iv = envelope
+                    .iv
+                    .as_deref()
+                    .ok_or("Rejected unencrypted message")?;
+                let iv = crate::binary::decode(iv)?;
+                if iv.len() != 12 {
+                    return Err("Rejected message with an invalid IV".to_string());
+                }
+                let plaintext = self
+                    .cipher()
+                    .decrypt(
+                        Nonce::from_slice(&iv),
+                        Payload {
+                            msg: crate::binary::decode(data)?.as_slice(),
+                            aad: header.as_bytes(),
+                        },
+                    )
+                    .map_err(|_| "Rejected message that failed to decrypt".to_string())?;
+                String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8: {}", e))
+            }
+        }
+    }
+
+    /// A script leaving the key for `window.dxSecurity.takeKey(callback_id)`.
+    pub fn install_script(&self, callback_id: &str) -> String {
+        format!(
+            "window.__dioxus_keys = window.__dioxus_keys || {{}}; window.__dioxus_keys[{}] = {{ mode: {}, key: {} }};",
+            crate::js_string(callback_id),
+            crate::js_string(match self.mode {
+                Security::Sign => "sign",
+                Security::Encrypt => "encrypt",
+            }),
+            crate::js_string(&crate::binary::encode(self.key.as_slice())),
+        )
+    }
+
+    fn sign(&self, header: &str, payload: &str) -> Result<String, String> {
+        let sig = self
+            .mac()?
+            .chain_update(header.as_bytes())
+            .chain_update(payload.as_bytes())
+            .finalize();
+        Ok(crate::binary::encode(&sig.into_bytes()))
+    }
+
+    fn mac(&self) -> Result<Hmac<Sha256>, String> {
+        <Hmac<Sha256> as Mac>::new_from_slice(self.key.as_slice())
+            .map_err(|e| format!("Invalid key: {}", e))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(self.key.as_slice().into())
+    }
+}
+
+/// The envelope fields a seal covers besides `data`, as `"<v>|<id>|"` with absent fields
+/// left empty, so a message can't be replayed under another ID or version.
+fn associated_data(version: Option<u32>, id: Option<u64>) -> String {
+    let field = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or_default();
+    format!("{}|{}|", field(version.map(u64::from)), field(id))
+}
+
+/// Defines `window.dxSecurity` with `takeKey(callbackId)`, `seal(key, value, { v, id })`,
+/// which resolves to the envelope fields for a message to Rust with that version and ID,
+/// and `unseal(key, message)`, which resolves to the JSON text of a message from Rust.
+/// Uses WebCrypto.
+pub(crate) const JS_SECURITY: &str = r#"
+window.__dioxus_keys = window.__dioxus_keys || {};
+window.dxSecurity = window.dxSecurity || (() => {
+    const toBase64 = (buffer) => {
+        const bytes = new Uint8Array(buffer);
+        let binary = "";
+        for (let i = 0; i < bytes.length; i += 0x8000) {
+            binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+        }
+        return btoa(binary);
+    };
+    const fromBase64 = (text) => Uint8Array.from(atob(text), (c) => c.charCodeAt(0));
+    const header = (message) => new TextEncoder().encode(`${message.v ?? ""}|${message.id ?? ""}|`);
+    const concat = (a, b) => {
+        const joined = new Uint8Array(a.length + b.length);
+        joined.set(a);
+        joined.set(b, a.length);
+        return joined;
+    };
+    const importKey = (key) => key.mode === "sign"
+        ? crypto.subtle.importKey("raw", fromBase64(key.key), { name: "HMAC", hash: "SHA-256" }, false, ["sign", "verify"])
+        : crypto.subtle.importKey("raw", fromBase64(key.key), "AES-GCM", false, ["encrypt", "decrypt"]);
+    return {
+        takeKey: (callbackId) => {
+            const key = window.__dioxus_keys[callbackId];
+            delete window.__dioxus_keys[callbackId];
+            return key;
+        },
+        seal: async (key, value, fields = {}) => {
+            const text = JSON.stringify(value);
+            const cryptoKey = await importKey(key);
+            if (key.mode === "sign") {
+                const sig = await crypto.subtle.sign("HMAC", cryptoKey, concat(header(fields), new TextEncoder().encode(text)));
+                return { data: text, sig: toBase64(sig) };
+            }
+            const iv = crypto.getRandomValues(new Uint8Array(12));
+            const data = await crypto.subtle.encrypt({ name: "AES-GCM", iv, additionalData: header(fields) }, cryptoKey, new TextEncoder().encode(text));
+            return { data: toBase64(data), iv: toBase64(iv) };
+        },
+        unseal: async (key, message) => {
+            const cryptoKey = await importKey(key);
+            if (key.mode === "sign") {
+                const valid = await crypto.subtle.verify("HMAC", cryptoKey, fromBase64(message.sig), concat(header(message), new TextEncoder().encode(message.data)));
+                if (!valid) throw new Error("Invalid signature");
+                return message.data;
+            }
+            const data = await crypto.subtle.decrypt({ name: "AES-GCM", iv: fromBase64(message.iv), additionalData: header(message) }, cryptoKey, fromBase64(message.data));
+            return new TextDecoder().decode(data);
+        },
+    };
+})();
+"#;
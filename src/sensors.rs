@@ -0,0 +1,189 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, FromJs, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Device orientation in degrees, in the shape of the Web `DeviceOrientationEvent`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeviceOrientation {
+    /// Rotation around the z axis, 0 to 360.
+    pub alpha: Option<f64>,
+    /// Front-to-back tilt, -180 to 180.
+    pub beta: Option<f64>,
+    /// Left-to-right tilt, -90 to 90.
+    pub gamma: Option<f64>,
+    /// Whether `alpha` is relative to magnetic north rather than an arbitrary frame.
+    pub absolute: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Rotation rates in degrees per second.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RotationRate {
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+}
+
+/// Device motion, in the shape of the Web `DeviceMotionEvent`. Accelerations are in m/s².
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeviceMotion {
+    pub acceleration: Option<Vector3>,
+    pub acceleration_including_gravity: Option<Vector3>,
+    pub rotation_rate: Option<RotationRate>,
+    /// Milliseconds between samples.
+    pub interval: f64,
+}
+
+/// Readings from a device sensor, created by [`use_device_orientation`] or
+/// [`use_device_motion`].
+#[derive(Clone)]
+pub struct Sensor<T: FromJs + Clone + 'static> {
+    pub reading: Signal<Option<T>>,
+    pub bridge: JsBridge<T>,
+}
+
+impl<T: FromJs + Clone + 'static> Sensor<T> {
+    pub fn get_reading(&self) -> Option<T> {
+        self.reading.read().clone()
+    }
+
+    /// Returns a stream of every reading received after this call.
+    pub fn readings(&self) -> UnboundedReceiver<T> {
+        self.bridge.stream()
+    }
+}
+
+/// Streams `deviceorientation` events until the component unmounts.
+///
+/// On Android the app's `SensorManager` is used when the WebView doesn't deliver the
+/// event: `RustBridge.startSensor({ sensor: "orientation", callback_id })` posts
+/// [`DeviceOrientation`] readings to `callback_id` until `stopSensor({ callback_id })`.
+pub fn use_device_orientation() -> Sensor<DeviceOrientation> {
+    use_sensor(
+        "deviceorientation",
+        "orientation",
+        r#"(e) => ({
+            alpha: e.alpha,
+            beta: e.beta,
+            gamma: e.gamma,
+            absolute: !!e.absolute,
+        })"#,
+    )
+}
+
+/// Streams `devicemotion` events until the component unmounts.
+///
+/// On Android the app's `SensorManager` is used when the WebView doesn't deliver the
+/// event: `RustBridge.startSensor({ sensor: "motion", callback_id })` posts
+/// [`DeviceMotion`] readings to `callback_id` until `stopSensor({ callback_id })`.
+pub fn use_device_motion() -> Sensor<DeviceMotion> {
+    use_sensor(
+        "devicemotion",
+        "motion",
+        r#"(e) => {
+            const vec = (v) => (v && v.x !== null ? { x: v.x, y: v.y, z: v.z } : null);
+            const r = e.rotationRate;
+            return {
+                acceleration: vec(e.acceleration),
+                acceleration_including_gravity: vec(e.accelerationIncludingGravity),
+                rotation_rate: r && r.alpha !== null
+                    ? { alpha: r.alpha, beta: r.beta, gamma: r.gamma }
+                    : null,
+                interval: e.interval || 0,
+            };
+        }"#,
+    )
+}
+
+fn remove_listener_script(id: &str) -> String {
+    format!(
+        r#"
+        const sensor = window.__dioxus_sensors && window.__dioxus_sensors[{id}];
+        if (sensor) {{
+            window.removeEventListener(sensor.event, sensor.handler);
+            delete window.__dioxus_sensors[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Listens for `event` on `window`, mapping each event to `T` with the JS function `map`.
+/// `sensor` names the Android fallback sensor.
+fn use_sensor<T>(event: &'static str, sensor: &'static str, map: &'static str) -> Sensor<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    let mut reading = use_signal(|| None::<T>);
+    let bridge = use_js_listener::<T>(move |value| reading.set(Some(value)));
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let callback_id = bridge_for_effect.callback_id();
+        // Resolves with whether an event arrived within a second, so Android can tell a
+        // WebView that never delivers it apart from one that does.
+        let js_code = format!(
+            r#"
+            window.__dioxus_sensors = window.__dioxus_sensors || {{}};
+            const id = {id};
+            if (window.__dioxus_sensors[id]) {{
+                return true;
+            }}
+            const map = {map};
+            let delivered = false;
+            const handler = (e) => {{
+                delivered = true;
+                if ({callback}) {{
+                    {callback}(map(e));
+                }}
+            }};
+            window.addEventListener({event}, handler);
+            window.__dioxus_sensors[id] = {{ event: {event}, handler }};
+            return await new Promise((resolve) => setTimeout(() => resolve(delivered), 1000));
+            "#,
+            id = js_string(&callback_id),
+            callback = bridge_for_effect.js_callback(),
+            event = js_string(event),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            match eval_as::<bool>(&js_code).await {
+                #[cfg(target_os = "android")]
+                Ok(false) => {
+                    eval_detached(&remove_listener_script(&callback_id));
+                    let args = serde_json::json!({ "sensor": sensor, "callback_id": callback_id });
+                    if let Err(e) = crate::android_bridge::call_native("startSensor", &args) {
+                        bridge.set_error(Some(e));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => bridge.set_error(Some(e)),
+            }
+        });
+        #[cfg(not(target_os = "android"))]
+        let _ = sensor;
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        #[cfg(target_os = "android")]
+        {
+            let args = serde_json::json!({ "callback_id": callback_id });
+            if let Err(e) = crate::android_bridge::call_native("stopSensor", &args) {
+                eprintln!("Failed to stop sensor: {}", e);
+            }
+        }
+        eval_detached(&remove_listener_script(&callback_id));
+    });
+
+    Sensor { reading, bridge }
+}
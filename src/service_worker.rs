@@ -0,0 +1,229 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, FromJs, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// `ServiceWorker.state` of the newest worker of the registration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceWorkerState {
+    Parsed,
+    Installing,
+    Installed,
+    Activating,
+    Activated,
+    Redundant,
+}
+
+/// A lifecycle event or message from the service worker.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ServiceWorkerEvent<T> {
+    Registered {
+        scope: String,
+    },
+    /// A new worker script was found and is installing.
+    UpdateFound,
+    StateChange {
+        state: ServiceWorkerState,
+    },
+    /// A new worker installed and is waiting for the old one to go away; see
+    /// [`ServiceWorker::skip_waiting`].
+    UpdateReady,
+    /// A different worker now controls the page.
+    ControllerChange,
+    Message {
+        data: T,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// A service worker registration, created by [`use_service_worker`].
+#[derive(Clone)]
+pub struct ServiceWorker<T: FromJs + Clone> {
+    pub state: Signal<Option<ServiceWorkerState>>,
+    /// Whether an updated worker is waiting to activate.
+    pub update_ready: Signal<bool>,
+    pub bridge: JsBridge<ServiceWorkerEvent<T>>,
+}
+
+impl<T: FromJs + Clone> ServiceWorker<T> {
+    pub fn get_state(&self) -> Option<ServiceWorkerState> {
+        *self.state.read()
+    }
+    pub fn is_update_ready(&self) -> bool {
+        *self.update_ready.read()
+    }
+
+    /// The most recent message posted by the worker.
+    pub fn last_message(&self) -> Option<T> {
+        match self.bridge.get_data() {
+            Some(ServiceWorkerEvent::Message { data }) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns a stream of every lifecycle event and message received after this call.
+    pub fn events(&self) -> UnboundedReceiver<ServiceWorkerEvent<T>> {
+        self.bridge.stream()
+    }
+
+    /// Posts `data` to the worker controlling the page, or the active one if the page
+    /// isn't controlled yet.
+    pub async fn post_message<S: Serialize>(&self, data: &S) -> Result<(), String> {
+        let data =
+            serde_json::to_string(data).map_err(|e| format!("Serialization error: {}", e))?;
+        self.run(&format!(
+            r#"
+            const target = navigator.serviceWorker.controller || sw.reg.active;
+            if (!target) {{
+                throw new Error("No active service worker");
+            }}
+            target.postMessage({data});
+            "#
+        ))
+        .await
+    }
+
+    /// Asks the waiting worker to activate by posting `{ "type": "SKIP_WAITING" }`; the
+    /// worker script is expected to call `self.skipWaiting()` in response.
+    pub async fn skip_waiting(&self) -> Result<(), String> {
+        self.run(
+            r#"
+            if (sw.reg.waiting) {
+                sw.reg.waiting.postMessage({ type: "SKIP_WAITING" });
+            }
+            "#,
+        )
+        .await
+    }
+
+    /// Checks the server for an updated worker script.
+    pub async fn update(&self) -> Result<(), String> {
+        self.run("await sw.reg.update();").await
+    }
+
+    pub async fn unregister(&self) -> Result<(), String> {
+        self.run("await sw.reg.unregister();").await
+    }
+
+    /// Runs `body` with `sw` in scope.
+    async fn run(&self, body: &str) -> Result<(), String> {
+        let js_code = format!(
+            r#"
+            const sw = window.__dioxus_sw && window.__dioxus_sw[{id}];
+            if (!sw) {{
+                throw new Error("Service worker is not registered");
+            }}
+            {body}
+            "#,
+            id = js_string(&self.bridge.callback_id()),
+        );
+        eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+    }
+}
+
+/// Registers the service worker at `script_url` (with an optional `scope`) and delivers
+/// its lifecycle events and messages until the component unmounts. The registration
+/// itself outlives the component.
+pub fn use_service_worker<T>(script_url: &str, scope: Option<&str>) -> ServiceWorker<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    let mut state = use_signal(|| None::<ServiceWorkerState>);
+    let mut update_ready = use_signal(|| false);
+    let bridge = use_js_listener::<ServiceWorkerEvent<T>>(move |event| match event {
+        ServiceWorkerEvent::StateChange { state: new_state } => {
+            if new_state == ServiceWorkerState::Activated {
+                update_ready.set(false);
+            }
+            state.set(Some(new_state));
+        }
+        ServiceWorkerEvent::UpdateReady => update_ready.set(true),
+        _ => {}
+    });
+
+    let script_url = use_hook(|| script_url.to_string());
+    let scope = use_hook(|| scope.map(str::to_string));
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            if (!("serviceWorker" in navigator)) {{
+                throw new Error("Service workers are not supported");
+            }}
+            window.__dioxus_sw = window.__dioxus_sw || {{}};
+            const id = {id};
+            if (!window.__dioxus_sw[id]) {{
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const scope = {scope};
+                const reg = await navigator.serviceWorker.register(
+                    {url},
+                    scope === null ? undefined : {{ scope }}
+                );
+                const track = (worker) => {{
+                    if (!worker) return;
+                    const report = () => {{
+                        emit({{ event: "state_change", state: worker.state }});
+                        if (worker.state === "installed" && navigator.serviceWorker.controller) {{
+                            emit({{ event: "update_ready" }});
+                        }}
+                    }};
+                    worker.addEventListener("statechange", report);
+                    report();
+                }};
+                const onMessage = (e) => emit({{ event: "message", data: e.data }});
+                const onControllerChange = () => emit({{ event: "controller_change" }});
+                navigator.serviceWorker.addEventListener("message", onMessage);
+                navigator.serviceWorker.addEventListener("controllerchange", onControllerChange);
+                reg.addEventListener("updatefound", () => {{
+                    emit({{ event: "update_found" }});
+                    track(reg.installing);
+                }});
+                window.__dioxus_sw[id] = {{ reg, onMessage, onControllerChange }};
+                emit({{ event: "registered", scope: reg.scope }});
+                track(reg.installing || reg.waiting || reg.active);
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+            url = js_string(&script_url),
+            scope = serde_json::to_string(&scope).unwrap_or_else(|_| "null".to_string()),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const sw = window.__dioxus_sw && window.__dioxus_sw[{id}];
+            if (sw) {{
+                navigator.serviceWorker.removeEventListener("message", sw.onMessage);
+                navigator.serviceWorker.removeEventListener("controllerchange", sw.onControllerChange);
+                delete window.__dioxus_sw[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    ServiceWorker {
+        state,
+        update_ready,
+        bridge,
+    }
+}
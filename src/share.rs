@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// A file attached to a share.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ShareFile {
+    pub name: String,
+    pub mime_type: String,
+    #[serde(with = "crate::binary::base64_bytes")]
+    pub bytes: Vec<u8>,
+}
+
+/// What to share; empty fields are left out.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ShareData {
+    pub title: Option<String>,
+    pub text: Option<String>,
+    pub url: Option<String>,
+    pub files: Vec<ShareFile>,
+}
+
+/// Opens the platform share sheet and returns whether the share completed, `false`
+/// meaning the user dismissed it.
+///
+/// Uses `navigator.share` on web and desktop, and an `ACTION_SEND` intent on Android:
+/// `RustBridge.share({ title, text, url, files })`, with files as
+/// `{ name, mime_type, bytes }` in base64, answers with whether the share completed.
+pub async fn share(data: &ShareData) -> Result<bool, String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::to_value(data).map_err(|e| format!("Serialization error: {}", e))?;
+        let completed = crate::android_bridge::call_native_async("share", args).await?;
+        Ok(completed.as_bool().unwrap_or(false))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let data =
+            serde_json::to_string(data).map_err(|e| format!("Serialization error: {}", e))?;
+        let js_code = format!(
+            r#"
+            {helpers}
+            if (!navigator.share) {{
+                throw new Error("Web Share is not supported");
+            }}
+            const data = {data};
+            const payload = {{}};
+            if (data.title !== null) payload.title = data.title;
+            if (data.text !== null) payload.text = data.text;
+            if (data.url !== null) payload.url = data.url;
+            if (data.files.length > 0) {{
+                const files = data.files.map(
+                    (f) => new File([__dxFromBase64(f.bytes)], f.name, {{ type: f.mime_type }})
+                );
+                if (navigator.canShare && !navigator.canShare({{ files }})) {{
+                    throw new Error("Sharing these files is not supported");
+                }}
+                payload.files = files;
+            }}
+            try {{
+                await navigator.share(payload);
+                return true;
+            }} catch (e) {{
+                if (e.name === "AbortError") {{
+                    return false;
+                }}
+                throw e;
+            }}
+            "#,
+            helpers = crate::binary::JS_BASE64_HELPERS,
+        );
+        crate::eval_as::<bool>(&js_code).await
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{use_js_bridge, JsBridge};
+use dioxus::prelude::*;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The command line of a later launch of the app, forwarded to the running instance.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InstanceLaunch {
+    /// The arguments after the program name: deep links, files to open.
+    pub args: Vec<String>,
+    /// The working directory of the launch, for resolving relative paths in `args`.
+    pub cwd: Option<PathBuf>,
+}
+
+impl InstanceLaunch {
+    fn current() -> Self {
+        InstanceLaunch {
+            args: std::env::args().skip(1).collect(),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Forwarded {
+    token: String,
+    launch: InstanceLaunch,
+}
+
+/// Subscribed [`use_instance_launches`] hooks, and the launches received before any was
+/// mounted.
+type Launches = (Vec<UnboundedSender<InstanceLaunch>>, Vec<InstanceLaunch>);
+
+static LAUNCHES: Mutex<Launches> = Mutex::new((Vec::new(), Vec::new()));
+
+fn launches() -> std::sync::MutexGuard<'static, Launches> {
+    LAUNCHES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Returns a stream of forwarded launches, starting with any that arrived before the
+/// first subscriber.
+pub(crate) fn subscribe() -> UnboundedReceiver<InstanceLaunch> {
+    let (tx, rx) = futures_channel::mpsc::unbounded();
+    let mut launches = launches();
+    for launch in launches.1.drain(..) {
+        let _ = tx.unbounded_send(launch);
+    }
+    launches.0.push(tx);
+    rx
+}
+
+fn dispatch(launch: InstanceLaunch) {
+    let mut launches = launches();
+    launches.0.retain(|tx| !tx.is_closed());
+    if launches.0.is_empty() {
+        launches.1.push(launch);
+    } else {
+        launches
+            .0
+            .retain(|tx| tx.unbounded_send(launch.clone()).is_ok());
+    }
+}
+
+/// Marks this process as the running instance. Keep it for the app's lifetime; dropping it
+/// lets the next launch start its own instance.
+pub struct SingleInstance {
+    _lock: File,
+    port_file: PathBuf,
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.port_file);
+    }
+}
+
+/// Makes the app single-instance. Call it at the start of `main`, before launching.
+///
+/// The first launch gets `Some`, and receives later launches through
+/// [`use_instance_launches`]. A later launch forwards its arguments to the running
+/// instance and gets `None`, and should exit. `app_id` names the lock and port files in
+/// the temp directory, so it must be unique to the app, e.g. `"com.example.editor"`.
+pub fn single_instance(app_id: &str) -> Result<Option<SingleInstance>, String> {
+    let dir = std::env::temp_dir();
+    let lock_path = dir.join(format!("{}.instance-lock", app_id));
+    let port_file = dir.join(format!("{}.instance-port", app_id));
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open {}: {}", lock_path.display(), e))?;
+
+    if lock.try_lock().is_err() {
+        forward(&port_file, &InstanceLaunch::current())?;
+        return Ok(None);
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to listen for later launches: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    // Only launches that could read the port file may forward to this instance
+    let token = format!(
+        "{:016x}",
+        std::collections::hash_map::RandomState::new().hash_one(port)
+    );
+    std::fs::write(&port_file, format!("{}\n{}\n", port, token))
+        .map_err(|e| format!("Failed to write {}: {}", port_file.display(), e))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut line = String::new();
+            if BufReader::new(stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            match serde_json::from_str::<Forwarded>(&line) {
+                Ok(forwarded) if forwarded.token == token => dispatch(forwarded.launch),
+                Ok(_) => eprintln!("Ignored a launch with the wrong token"),
+                Err(e) => eprintln!("Invalid forwarded launch: {}", e),
+            }
+        }
+    });
+
+    Ok(Some(SingleInstance {
+        _lock: lock,
+        port_file,
+    }))
+}
+
+/// Sends `launch` to the running instance. The port file may not be written yet if it is
+/// still starting, so reading it is retried for a moment.
+fn forward(port_file: &PathBuf, launch: &InstanceLaunch) -> Result<(), String> {
+    let mut attempts = 0;
+    let contents = loop {
+        match std::fs::read_to_string(port_file) {
+            Ok(contents) if contents.lines().count() == 2 => break contents,
+            _ if attempts < 20 => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            _ => return Err("The running instance didn't publish its port".to_string()),
+        }
+    };
+    let mut lines = contents.lines();
+    let port: u16 = lines
+        .next()
+        .and_then(|port| port.parse().ok())
+        .ok_or("Invalid instance port file")?;
+    let token = lines.next().unwrap_or_default().to_string();
+
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| format!("Failed to reach the running instance: {}", e))?;
+    let mut message = serde_json::to_string(&Forwarded {
+        token,
+        launch: launch.clone(),
+    })
+    .map_err(|e| format!("Serialization error: {}", e))?;
+    message.push('\n');
+    stream
+        .write_all(message.as_bytes())
+        .map_err(|e| format!("Failed to forward the launch: {}", e))
+}
+
+/// Later launches forwarded by [`single_instance`], created by [`use_instance_launches`].
+#[derive(Clone)]
+pub struct InstanceLaunches {
+    pub bridge: JsBridge<InstanceLaunch>,
+}
+
+impl InstanceLaunches {
+    /// Returns a stream of every launch received after this call.
+    pub fn launches(&self) -> UnboundedReceiver<InstanceLaunch> {
+        self.bridge.stream()
+    }
+
+    pub fn last_launch(&self) -> Option<InstanceLaunch> {
+        self.bridge.get_data()
+    }
+}
+
+/// Receives the launches forwarded to this instance while the component is mounted,
+/// including any that arrived before it mounted, and brings the window to the front for
+/// each. Launches are delivered like messages from JS, through the returned bridge.
+pub fn use_instance_launches() -> InstanceLaunches {
+    let bridge = use_js_bridge::<InstanceLaunch>();
+
+    let mut receiver = bridge.clone();
+    use_hook(move || {
+        let mut rx = subscribe();
+        spawn(async move {
+            while let Some(launch) = rx.next().await {
+                if let Ok(window) = crate::desktop_window::desktop_context() {
+                    window.set_minimized(false);
+                    window.set_visible(true);
+                    window.set_focus();
+                }
+                receiver.receive(launch);
+            }
+        });
+    });
+
+    InstanceLaunches { bridge }
+}
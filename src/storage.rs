@@ -0,0 +1,171 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, FromJs};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// A Web Storage area of the page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageArea {
+    Local,
+    Session,
+}
+
+impl StorageArea {
+    fn js_object(self) -> &'static str {
+        match self {
+            StorageArea::Local => "window.localStorage",
+            StorageArea::Session => "window.sessionStorage",
+        }
+    }
+}
+
+/// Sent by the `storage` event listener when another document changes the key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StorageChange {
+    value: Option<String>,
+}
+
+/// A signal synchronized with `localStorage[key]`, stored as JSON.
+///
+/// The value is read on mount, written back whenever the signal is set (`None` removes the
+/// key), and updated when another tab or window changes it.
+pub fn use_local_storage<T>(key: &str) -> Signal<Option<T>>
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    use_storage(StorageArea::Local, key)
+}
+
+/// Like [`use_local_storage`], backed by `sessionStorage`.
+pub fn use_session_storage<T>(key: &str) -> Signal<Option<T>>
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    use_storage(StorageArea::Session, key)
+}
+
+/// A signal synchronized with `key` in the given storage area.
+pub fn use_storage<T>(area: StorageArea, key: &str) -> Signal<Option<T>>
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    use_storage_with_error(area, key).0
+}
+
+/// Like [`use_storage`], with a second signal holding the last error reading, parsing or
+/// writing the value. A stored value that doesn't parse as `T` leaves the signal `None` and
+/// stays in storage until the signal is set.
+pub fn use_storage_with_error<T>(
+    area: StorageArea,
+    key: &str,
+) -> (Signal<Option<T>>, Signal<Option<String>>)
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    let mut value = use_signal(|| None::<T>);
+    let mut error = use_signal(|| None::<String>);
+    // JSON last read from or written to storage, `None` until the initial read finished.
+    // Writes matching it are skipped so loading a value doesn't write it straight back.
+    let mut synced = use_signal(|| None::<Option<String>>);
+    let key = use_hook(|| key.to_string());
+
+    let mut apply = move |json: Option<String>| {
+        let parsed = match json.as_deref().map(serde_json::from_str::<T>).transpose() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // Counts as synced with the empty signal, so the unreadable value isn't
+                // removed until the signal is set
+                error.set(Some(format!("Failed to parse stored value: {}", e)));
+                synced.set(Some(None));
+                value.set(None);
+                return;
+            }
+        };
+        error.set(None);
+        synced.set(Some(json));
+        value.set(parsed);
+    };
+
+    let listener = use_js_listener::<StorageChange>(move |change| apply(change.value));
+
+    let key_for_load = key.clone();
+    let listener_for_load = listener.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            window.__dioxus_storage = window.__dioxus_storage || {{}};
+            const id = {id};
+            if (!window.__dioxus_storage[id]) {{
+                const changed = (e) => {{
+                    if (e.storageArea === {area} && (e.key === {key} || e.key === null)) {{
+                        if ({callback}) {{
+                            {callback}({{ value: e.newValue }});
+                        }}
+                    }}
+                }};
+                window.addEventListener("storage", changed);
+                window.__dioxus_storage[id] = changed;
+            }}
+            return {area}.getItem({key});
+            "#,
+            id = js_string(&listener_for_load.callback_id()),
+            area = area.js_object(),
+            key = js_string(&key_for_load),
+            callback = listener_for_load.js_callback(),
+        );
+        spawn(async move {
+            match eval_as::<Option<String>>(&js_code).await {
+                Ok(json) => apply(json),
+                Err(e) => error.set(Some(format!("Failed to read storage: {}", e))),
+            }
+        });
+    });
+
+    use_effect(move || {
+        let json = match value.read().as_ref().map(serde_json::to_string).transpose() {
+            Ok(json) => json,
+            Err(e) => {
+                error.set(Some(format!("Failed to serialize stored value: {}", e)));
+                return;
+            }
+        };
+        match synced.peek().as_ref() {
+            Some(last) if *last != json => {}
+            _ => return,
+        }
+        synced.set(Some(json.clone()));
+
+        let js_code = match json {
+            Some(json) => format!(
+                "{}.setItem({}, {});",
+                area.js_object(),
+                js_string(&key),
+                js_string(&json)
+            ),
+            None => format!("{}.removeItem({});", area.js_object(), js_string(&key)),
+        };
+        spawn(async move {
+            match eval_as::<serde_json::Value>(&js_code).await {
+                Ok(_) => error.set(None),
+                Err(e) => error.set(Some(format!("Failed to write storage: {}", e))),
+            }
+        });
+    });
+
+    let callback_id = listener.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const changed = window.__dioxus_storage && window.__dioxus_storage[{id}];
+            if (changed) {{
+                window.removeEventListener("storage", changed);
+                delete window.__dioxus_storage[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    (value, error)
+}
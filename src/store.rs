@@ -0,0 +1,133 @@
+use crate::patch::{json_diff, JS_PATCH};
+use crate::{bootstrap, eval_detached, js_string, use_js_listener, FromJs};
+use dioxus::prelude::*;
+use dioxus_signals::{CopyValue, Readable, Writable};
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Defines `window.dxStore(name)`, the JS side of a [`BridgeStore`].
+const JS_STORE: &str = r#"
+window.dxStore = window.dxStore || ((name) => {
+    const stores = (window.__dioxus_stores = window.__dioxus_stores || {});
+    if (!stores[name]) {
+        const listeners = new Set();
+        const store = {
+            state: undefined,
+            version: -1,
+            dispatcher: null,
+            subscribe: (listener) => {
+                listeners.add(listener);
+                if (store.version >= 0) listener(store.state, null);
+                return () => listeners.delete(listener);
+            },
+            dispatch: (action) => {
+                if (!store.dispatcher) throw new Error("Store " + name + " isn't connected to Rust");
+                store.dispatcher(action);
+            },
+            apply: (message) => {
+                store.state = "snapshot" in message
+                    ? message.snapshot
+                    : window.dxApplyPatch(store.state, message.patch);
+                store.version = message.version;
+                for (const listener of listeners) listener(store.state, message.patch || null);
+            },
+        };
+        stores[name] = store;
+    }
+    return stores[name];
+});
+"#;
+
+/// State owned by Rust and mirrored to JS as `window.dxStore(name)`, created by
+/// [`use_bridge_store`]. Changes reach JS as JSON Patch diffs instead of full snapshots.
+pub struct BridgeStore<T: 'static> {
+    state: Signal<T>,
+}
+
+impl<T: 'static> Clone for BridgeStore<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for BridgeStore<T> {}
+
+impl<T: Clone + 'static> BridgeStore<T> {
+    /// The current state. Reading it subscribes the component to changes.
+    pub fn get(&self) -> T {
+        self.state.read().clone()
+    }
+
+    /// Changes the state; the diff is pushed to JS after the next render.
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) {
+        self.state.with_mut(f);
+    }
+
+    pub fn signal(&self) -> Signal<T> {
+        self.state
+    }
+}
+
+/// A [`BridgeStore`] named `name`, starting from `initial`.
+///
+/// JS reads the state with `dxStore(name).state`, watches it with
+/// `dxStore(name).subscribe((state, patch) => ...)`, where `patch` is `null` for full
+/// snapshots, and sends typed actions with `dxStore(name).dispatch(action)`. Actions are
+/// decoded as `A` and passed to `reducer`, whose changes are pushed back like any other.
+pub fn use_bridge_store<T, A>(
+    name: &str,
+    initial: impl FnOnce() -> T,
+    mut reducer: impl FnMut(&mut T, A) + 'static,
+) -> BridgeStore<T>
+where
+    T: Serialize + Clone + 'static,
+    A: FromJs + Clone + Debug + 'static,
+{
+    let mut state = use_signal(initial);
+    let name = use_hook(|| name.to_string());
+    // The JSON and version last pushed to JS
+    let mut published = use_hook(|| CopyValue::new((None::<serde_json::Value>, 0u64)));
+
+    let actions =
+        use_js_listener::<A>(move |action| state.with_mut(|state| reducer(state, action)));
+
+    let name_for_install = name.clone();
+    use_effect(move || {
+        bootstrap::inject_once("patch", JS_PATCH);
+        bootstrap::inject_once("store", JS_STORE);
+        eval_detached(&format!(
+            "window.dxStore({}).dispatcher = (action) => {{ const callback = {}; if (callback) callback(action); }};",
+            js_string(&name_for_install),
+            actions.js_callback()
+        ));
+    });
+
+    use_effect(move || {
+        let json = match serde_json::to_value(&*state.read()) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("Failed to serialize store {}: {}", name, e);
+                return;
+            }
+        };
+        let (last, version) = published.peek().clone();
+        let message = match &last {
+            Some(last) => {
+                let patch = json_diff(last, &json);
+                if patch.is_empty() {
+                    return;
+                }
+                serde_json::json!({ "patch": patch, "version": version + 1 })
+            }
+            None => serde_json::json!({ "snapshot": json, "version": version + 1 }),
+        };
+        published.set((Some(json), version + 1));
+        eval_detached(&format!(
+            "window.dxStore({}).apply({});",
+            js_string(&name),
+            message
+        ));
+    });
+
+    BridgeStore { state }
+}
@@ -0,0 +1,250 @@
+use crate::{eval_as, eval_detached, js_string, Codec, JsonCodec};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// Defines `window.dxStreams`, which holds the iterators of streams Rust pulls from and
+/// the consumers of streams Rust pushes to.
+///
+/// - `start` accepts an async iterator, a `ReadableStream`, or any sync or async iterable.
+/// - `consume(name, handler, { credit, onEnd })` receives the items of the outbound stream
+///   `name`, awaiting `handler` for each in order. At most `credit` items (16 by default)
+///   are queued or being handled; Rust waits for credit before sending more. Returns a
+///   function that stops consuming.
+pub(crate) const JS_STREAMS: &str = r#"
+window.dxStreams = window.dxStreams || {
+    nextId: 1,
+    sources: {},
+    start(source) {
+        let iterator;
+        if (source && typeof source.getReader === "function") {
+            const reader = source.getReader();
+            iterator = { next: () => reader.read(), return: () => reader.cancel() };
+        } else if (source && typeof source[Symbol.asyncIterator] === "function") {
+            iterator = source[Symbol.asyncIterator]();
+        } else if (source && typeof source[Symbol.iterator] === "function") {
+            iterator = source[Symbol.iterator]();
+        } else if (source && typeof source.next === "function") {
+            iterator = source;
+        } else {
+            throw new Error("Expected an async iterator, iterable or ReadableStream");
+        }
+        const id = this.nextId++;
+        this.sources[id] = iterator;
+        return id;
+    },
+    async pull(id) {
+        const iterator = this.sources[id];
+        if (!iterator) return { done: true };
+        try {
+            const { done, value } = await iterator.next();
+            if (done) delete this.sources[id];
+            return done ? { done: true } : { done: false, value: value === undefined ? null : value };
+        } catch (e) {
+            delete this.sources[id];
+            throw e;
+        }
+    },
+    cancel(id) {
+        const iterator = this.sources[id];
+        delete this.sources[id];
+        if (iterator && typeof iterator.return === "function") {
+            Promise.resolve(iterator.return()).catch(() => {});
+        }
+    },
+    sinks: {},
+    sink(name) {
+        return this.sinks[name] || (this.sinks[name] = { queue: [], busy: false, granted: 0, waiters: [] });
+    },
+    consume(name, handler, { credit = 16, onEnd } = {}) {
+        const sink = this.sink(name);
+        Object.assign(sink, { handler, credit, onEnd });
+        this.drain(name);
+        return () => {
+            if (sink.handler === handler) sink.handler = undefined;
+        };
+    },
+    room(sink) {
+        if (!sink.handler) return 0;
+        return sink.credit - sink.queue.length - sink.granted - (sink.busy ? 1 : 0);
+    },
+    grant(name) {
+        const sink = this.sink(name);
+        while (sink.waiters.length && this.room(sink) > 0) {
+            const credit = this.room(sink);
+            sink.granted += credit;
+            sink.waiters.shift()(credit);
+        }
+    },
+    credit(name) {
+        return new Promise((resolve) => {
+            this.sink(name).waiters.push(resolve);
+            this.grant(name);
+        });
+    },
+    push(name, item) {
+        const sink = this.sink(name);
+        sink.granted = Math.max(0, sink.granted - 1);
+        sink.queue.push({ item });
+        this.drain(name);
+    },
+    end(name) {
+        this.sink(name).queue.push({ end: true });
+        this.drain(name);
+    },
+    async drain(name) {
+        const sink = this.sink(name);
+        if (sink.busy) return;
+        sink.busy = true;
+        while (sink.handler && sink.queue.length) {
+            const next = sink.queue.shift();
+            try {
+                if (next.end) {
+                    delete this.sinks[name];
+                    if (sink.onEnd) await sink.onEnd();
+                } else {
+                    await sink.handler(next.item);
+                }
+            } catch (e) {
+                console.error("dxStreams consumer of " + name + " failed:", e);
+            }
+            if (next.end) return;
+            this.grant(name);
+        }
+        sink.busy = false;
+        this.grant(name);
+    },
+};
+"#;
+
+/// One step of a JS iterator.
+#[derive(Deserialize)]
+struct Step {
+    done: bool,
+    #[serde(default)]
+    value: serde_json::Value,
+}
+
+/// A JS iterator being pulled. Dropping it before the end cancels the iterator.
+struct Source {
+    id: u64,
+    finished: bool,
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        if !self.finished {
+            eval_detached(&format!("window.dxStreams.cancel({});", self.id));
+        }
+    }
+}
+
+/// Pulls the items of the iterator `id` one at a time, each when the stream is polled. A
+/// failed pull or an item that doesn't decode ends the stream after its error.
+pub(crate) fn pull<T: crate::FromJs>(id: u64) -> impl Stream<Item = Result<T, String>> {
+    let source = Source {
+        id,
+        finished: false,
+    };
+    futures_util::stream::unfold(Some(source), |source| async move {
+        let mut source = source?;
+        let step = eval_as::<Step>(&format!(
+            "return await window.dxStreams.pull({});",
+            source.id
+        ))
+        .await;
+        match step {
+            Ok(Step { done: true, .. }) => {
+                source.finished = true;
+                None
+            }
+            Ok(Step { value, .. }) => match serde_json::from_value(value) {
+                Ok(item) => Some((Ok(item), Some(source))),
+                Err(e) => Some((Err(format!("Deserialization error: {}", e)), None)),
+            },
+            Err(e) => {
+                // JS dropped the iterator when it threw
+                source.finished = true;
+                Some((Err(e), None))
+            }
+        }
+    })
+}
+
+/// A stream of items Rust pushes to a JS consumer; see
+/// [`JsBridge::open_stream`](crate::JsBridge::open_stream).
+///
+/// The consumer grants credit for as many items as it has room for, and
+/// [`OutboundStream::send`] waits for credit when it has none left, so a slow consumer
+/// slows the sender down instead of letting items pile up. Items wait in Rust until a
+/// consumer for the stream's name exists. Dropping the stream ends it like
+/// [`OutboundStream::close`].
+pub struct OutboundStream<S, C: Codec = JsonCodec> {
+    name: String,
+    credit: u32,
+    closed: bool,
+    types: PhantomData<fn(&S) -> C>,
+}
+
+impl<S: Serialize, C: Codec> OutboundStream<S, C> {
+    pub(crate) fn open(name: &str) -> Self {
+        crate::bootstrap::inject_once("streams", JS_STREAMS);
+        Self {
+            name: name.to_string(),
+            credit: 0,
+            closed: false,
+            types: PhantomData,
+        }
+    }
+
+    /// Sends one item once the consumer has room for it.
+    pub async fn send(&mut self, item: &S) -> Result<(), String> {
+        if self.closed {
+            return Err(format!("Stream {} is closed", self.name));
+        }
+        let json_data = C::encode(item)?;
+        if self.credit == 0 {
+            self.credit = eval_as::<u32>(&format!(
+                "return await window.dxStreams.credit({});",
+                js_string(&self.name)
+            ))
+            .await?;
+        }
+        eval_as::<()>(&format!(
+            "window.dxStreams.push({}, {}); return null;",
+            js_string(&self.name),
+            json_data
+        ))
+        .await?;
+        self.credit = self.credit.saturating_sub(1);
+        Ok(())
+    }
+
+    /// Ends the stream after the items already sent; the consumer's `onEnd` runs once it
+    /// has handled them.
+    pub async fn close(mut self) -> Result<(), String> {
+        self.closed = true;
+        eval_as::<()>(&format!(
+            "window.dxStreams.end({}); return null;",
+            js_string(&self.name)
+        ))
+        .await
+    }
+
+    /// Items that may be sent without waiting for the consumer.
+    pub fn credit(&self) -> u32 {
+        self.credit
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<S, C: Codec> Drop for OutboundStream<S, C> {
+    fn drop(&mut self) {
+        if !self.closed {
+            eval_detached(&format!("window.dxStreams.end({});", js_string(&self.name)));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{bootstrap, eval_as, eval_detached, js_string, use_js_listener, FromJs};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use dioxus_signals::{CopyValue, Readable, Writable};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// A write to a synced signal, as reported by the JS store.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SignalWrite {
+    value: Option<serde_json::Value>,
+    version: u64,
+    writer: String,
+}
+
+/// Defines `window.dxSignals`, the JS side of [`use_synced_signal`]. Every write carries a
+/// version and a writer ID; the highest version wins, and ties go to the greater writer ID,
+/// so both sides settle on the same value.
+const JS_SIGNALS: &str = r#"
+window.dxSignals = window.dxSignals || (() => {
+    const entries = {};
+    const entry = (name) =>
+        entries[name] || (entries[name] = { value: undefined, version: 0, writer: "", listeners: new Set() });
+    const store = {
+        get: (name) => entry(name).value,
+        state: (name) => {
+            const e = entry(name);
+            return { value: e.value === undefined ? null : e.value, version: e.version, writer: e.writer };
+        },
+        write: (name, value, version, writer) => {
+            const e = entry(name);
+            if (version < e.version || (version === e.version && writer <= e.writer)) return false;
+            e.value = value;
+            e.version = version;
+            e.writer = writer;
+            for (const listener of e.listeners) listener({ value, version, writer });
+            return true;
+        },
+        set: (name, value) => store.write(name, value, entry(name).version + 1, "js"),
+        subscribe: (name, listener) => {
+            entry(name).listeners.add(listener);
+            return () => entry(name).listeners.delete(listener);
+        },
+    };
+    return store;
+})();
+window.__dioxus_signal_subs = window.__dioxus_signal_subs || {};
+"#;
+
+/// A signal mirrored to `window.dxSignals` under `name`, for state shared with JS code
+/// such as a charting library.
+///
+/// JS reads it with `dxSignals.get(name)`, writes with `dxSignals.set(name, value)` and
+/// watches it with `dxSignals.subscribe(name, ({ value }) => ...)`. Writes on either side
+/// reach the other; concurrent writes are resolved last-write-wins by version counter.
+/// A value already in the store when the component mounts wins over the Rust default.
+pub fn use_synced_signal<T>(name: &str) -> Signal<Option<T>>
+where
+    T: Serialize + FromJs + Clone + Debug + 'static,
+{
+    let mut value = use_signal(|| None::<T>);
+    let name = use_hook(|| name.to_string());
+    // The version, writer and JSON of the last write applied on this side
+    let mut synced = use_hook(|| CopyValue::new((0u64, String::new(), None::<serde_json::Value>)));
+
+    let mut apply = move |write: SignalWrite| {
+        let newer = {
+            let synced = synced.peek();
+            (write.version, &write.writer) > (synced.0, &synced.1)
+        };
+        if !newer {
+            return;
+        }
+        let parsed = match write
+            .value
+            .clone()
+            .map(serde_json::from_value::<T>)
+            .transpose()
+        {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Failed to parse synced signal: {}", e);
+                return;
+            }
+        };
+        synced.set((write.version, write.writer, write.value));
+        value.set(parsed);
+    };
+
+    let listener = use_js_listener::<SignalWrite>(apply);
+    let writer = listener.callback_id();
+
+    let name_for_load = name.clone();
+    let writer_for_load = writer.clone();
+    use_effect(move || {
+        bootstrap::inject_once("signals", JS_SIGNALS);
+        let js_code = format!(
+            r#"
+            const writer = {writer};
+            window.__dioxus_signal_subs[writer] = window.dxSignals.subscribe({name}, (write) => {{
+                if (write.writer !== writer && {callback}) {{
+                    {callback}(write);
+                }}
+            }});
+            return window.dxSignals.state({name});
+            "#,
+            writer = js_string(&writer_for_load),
+            name = js_string(&name_for_load),
+            callback = listener.js_callback(),
+        );
+        spawn(async move {
+            match eval_as::<SignalWrite>(&js_code).await {
+                Ok(state) if state.version > 0 => apply(state),
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to read synced signal: {}", e),
+            }
+        });
+    });
+
+    let name_for_write = name.clone();
+    let writer_for_write = writer.clone();
+    use_effect(move || {
+        let json = match value.read().as_ref().map(serde_json::to_value).transpose() {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("Failed to serialize synced signal: {}", e);
+                return;
+            }
+        };
+        if synced.peek().2 == json {
+            return;
+        }
+        let version = synced.peek().0 + 1;
+        synced.set((version, writer_for_write.clone(), json.clone()));
+
+        let js_code = format!(
+            "window.dxSignals.write({}, {}, {}, {});",
+            js_string(&name_for_write),
+            json.map(|json| json.to_string())
+                .unwrap_or_else(|| "null".to_string()),
+            version,
+            js_string(&writer_for_write)
+        );
+        eval_detached(&js_code);
+    });
+
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const unsubscribe = window.__dioxus_signal_subs && window.__dioxus_signal_subs[{writer}];
+            if (unsubscribe) {{
+                unsubscribe();
+                delete window.__dioxus_signal_subs[{writer}];
+            }}
+            "#,
+            writer = js_string(&writer)
+        ));
+    });
+
+    value
+}
@@ -0,0 +1,824 @@
+use crate::FromJs;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use serde_json::{Error, Map, Value};
+
+/// How enums are represented in the JSON crossing a bridge; see
+/// [`BridgeOptions::enum_tagging`](crate::BridgeOptions::enum_tagging).
+///
+/// It applies to every enum in a message, nested ones included, in both directions, so
+/// types don't need serde attributes to get shapes that are easy to `switch` on in JS.
+/// Enums with their own serde tagging attributes already serialize as objects and are
+/// left as they are.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum EnumTagging {
+    /// Serde's default: `"Reset"` for unit variants and `{ "Move": content }` otherwise.
+    #[default]
+    External,
+    /// `{ "type": "Move", "dx": 1, "dy": 0 }`, with the given tag key. Only unit variants
+    /// and variants holding fields can be represented; others fail to encode.
+    Internal { tag: String },
+    /// `{ "type": "Move", "data": content }`, with the given tag and content keys. Unit
+    /// variants have no content key.
+    Adjacent { tag: String, content: String },
+}
+
+impl EnumTagging {
+    pub fn internal(tag: impl Into<String>) -> Self {
+        Self::Internal { tag: tag.into() }
+    }
+
+    pub fn adjacent(tag: impl Into<String>, content: impl Into<String>) -> Self {
+        Self::Adjacent {
+            tag: tag.into(),
+            content: content.into(),
+        }
+    }
+
+    /// Serializes `value` to JSON with its enums in this representation.
+    pub fn to_value<S: Serialize + ?Sized>(&self, value: &S) -> Result<Value, String> {
+        value
+            .serialize(Tagged(self))
+            .map_err(|e| format!("Serialization error: {e}"))
+    }
+
+    /// Deserializes JSON with its enums in this representation. Strings are parsed as JSON
+    /// when the value itself doesn't match `T`, as with [`JsonCodec`](crate::JsonCodec).
+    pub fn from_value<T: FromJs>(&self, value: Value) -> Result<T, String> {
+        let text = value.as_str().map(str::to_string);
+        let parsed = T::deserialize(TaggedValue {
+            value,
+            tagging: self,
+        });
+        let parsed = match (parsed, text) {
+            (Err(e), Some(text)) => serde_json::from_str(&text)
+                .map_err(|_| e)
+                .and_then(|value| {
+                    T::deserialize(TaggedValue {
+                        value,
+                        tagging: self,
+                    })
+                }),
+            (parsed, _) => parsed,
+        };
+        parsed.map_err(|e| format!("Deserialization error: {e}"))
+    }
+
+    /// The variant name of a message in this representation, used as its topic.
+    pub(crate) fn variant_name<'v>(&self, message: &'v Value) -> Option<&'v str> {
+        match (self, message) {
+            (_, Value::String(variant)) => Some(variant),
+            (Self::External, Value::Object(object)) if object.len() == 1 => {
+                object.keys().next().map(String::as_str)
+            }
+            (Self::Internal { tag } | Self::Adjacent { tag, .. }, Value::Object(object)) => {
+                object.get(tag).and_then(Value::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    /// Describes the representation for the page helper, as `null` or
+    /// `{ "tag": ..., "content": ... }`.
+    fn describe(&self) -> Value {
+        match self {
+            Self::External => Value::Null,
+            Self::Internal { tag } => serde_json::json!({ "tag": tag }),
+            Self::Adjacent { tag, content } => {
+                serde_json::json!({ "tag": tag, "content": content })
+            }
+        }
+    }
+
+    /// A script telling `window.dxEnums` how the bridge `callback_id` represents enums.
+    pub(crate) fn install_script(&self, callback_id: &str) -> String {
+        format!(
+            "window.dxEnums.tagging[{}] = {};",
+            crate::js_string(callback_id),
+            self.describe()
+        )
+    }
+
+    /// Builds a variant from its name and content, if it has one.
+    fn variant(&self, name: &str, content: Option<Value>) -> Result<Value, Error> {
+        let mut object = Map::new();
+        match (self, content) {
+            (Self::External, None) => return Ok(Value::String(name.to_string())),
+            (Self::External, Some(content)) => {
+                object.insert(name.to_string(), content);
+            }
+            (Self::Internal { tag } | Self::Adjacent { tag, .. }, None) => {
+                object.insert(tag.clone(), Value::String(name.to_string()));
+            }
+            (Self::Internal { tag }, Some(Value::Object(fields))) => {
+                if fields.contains_key(tag) {
+                    return Err(ser::Error::custom(format!(
+                        "Variant {name} has a field named like the tag \"{tag}\""
+                    )));
+                }
+                object.insert(tag.clone(), Value::String(name.to_string()));
+                object.extend(fields);
+            }
+            (Self::Internal { .. }, Some(_)) => {
+                return Err(ser::Error::custom(format!(
+                    "Variant {name} can't be internally tagged: it doesn't hold fields"
+                )))
+            }
+            (Self::Adjacent { tag, content: key }, Some(content)) => {
+                object.insert(tag.clone(), Value::String(name.to_string()));
+                object.insert(key.clone(), content);
+            }
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+/// Defines `window.dxEnums`, which builds and reads enum variants in the representation of
+/// a given bridge:
+///
+/// - `dxEnums.variant(callbackId, "Move", { dx: 1, dy: 0 })` builds a variant to send;
+///   leave out the content for unit variants.
+/// - `dxEnums.match(callbackId, message)` returns `[name, content]` for a received one.
+///
+/// Bridges without [`BridgeOptions::enum_tagging`](crate::BridgeOptions::enum_tagging)
+/// use serde's default, `"Reset"` or `{ "Move": content }`.
+pub(crate) const JS_ENUMS: &str = r#"
+window.dxEnums = window.dxEnums || {
+    // callbackId -> null (external), { tag } (internal) or { tag, content } (adjacent)
+    tagging: {},
+    variant(callbackId, name, content) {
+        const tagging = this.tagging[callbackId];
+        if (!tagging) return content === undefined ? name : { [name]: content };
+        if (content === undefined) return { [tagging.tag]: name };
+        if (tagging.content === undefined) return { [tagging.tag]: name, ...content };
+        return { [tagging.tag]: name, [tagging.content]: content };
+    },
+    match(callbackId, message) {
+        if (typeof message === "string") return [message, undefined];
+        const tagging = this.tagging[callbackId];
+        if (!tagging) {
+            const [name] = Object.keys(message);
+            return [name, message[name]];
+        }
+        const { [tagging.tag]: name, ...rest } = message;
+        if (tagging.content === undefined) return [name, Object.keys(rest).length ? rest : undefined];
+        return [name, message[tagging.content]];
+    },
+};
+"#;
+
+/// Serializes to a JSON value like `serde_json::to_value`, with enums represented by the
+/// given tagging.
+#[derive(Clone, Copy)]
+struct Tagged<'a>(&'a EnumTagging);
+
+impl<'a> ser::Serializer for Tagged<'a> {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = Seq<'a>;
+    type SerializeTuple = Seq<'a>;
+    type SerializeTupleStruct = Seq<'a>;
+    type SerializeTupleVariant = Seq<'a>;
+    type SerializeMap = Fields<'a>;
+    type SerializeStruct = Fields<'a>;
+    type SerializeStructVariant = Fields<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        serde_json::to_value(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        serde_json::to_value(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        serde_json::to_value(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        serde_json::to_value(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Array(v.iter().map(|&byte| byte.into()).collect()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        self.0.variant(variant, None)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        let content = value.serialize(self)?;
+        self.0.variant(variant, Some(content))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Seq<'a>, Error> {
+        Ok(Seq {
+            tagging: self.0,
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Seq<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Seq<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Seq<'a>, Error> {
+        Ok(Seq {
+            tagging: self.0,
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Fields<'a>, Error> {
+        Ok(Fields {
+            tagging: self.0,
+            variant: None,
+            fields: Map::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Fields<'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Fields<'a>, Error> {
+        Ok(Fields {
+            tagging: self.0,
+            variant: Some(variant),
+            fields: Map::new(),
+            key: None,
+        })
+    }
+}
+
+/// A sequence, tuple or tuple variant being serialized.
+struct Seq<'a> {
+    tagging: &'a EnumTagging,
+    variant: Option<&'static str>,
+    items: Vec<Value>,
+}
+
+impl Seq<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(Tagged(self.tagging))?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        let items = Value::Array(self.items);
+        match self.variant {
+            Some(variant) => self.tagging.variant(variant, Some(items)),
+            None => Ok(items),
+        }
+    }
+}
+
+impl ser::SerializeSeq for Seq<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Seq<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Seq<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Seq<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+/// A map, struct or struct variant being serialized.
+struct Fields<'a> {
+    tagging: &'a EnumTagging,
+    variant: Option<&'static str>,
+    fields: Map<String, Value>,
+    key: Option<String>,
+}
+
+impl Fields<'_> {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        self.fields
+            .insert(key, value.serialize(Tagged(self.tagging))?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        let fields = Value::Object(self.fields);
+        match self.variant {
+            Some(variant) => self.tagging.variant(variant, Some(fields)),
+            None => Ok(fields),
+        }
+    }
+}
+
+impl ser::SerializeMap for Fields<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    /// Keys are written as serde_json writes them, so enum keys stay variant names.
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(match serde_json::to_value(key)? {
+            Value::String(key) => key,
+            key @ (Value::Number(_) | Value::Bool(_)) => key.to_string(),
+            _ => return Err(ser::Error::custom("Map keys must be strings or numbers")),
+        });
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| <Error as ser::Error>::custom("Map value without a key"))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Fields<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Fields<'_> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+/// Deserializes from a JSON value like `serde_json::from_value`, reading enums in the
+/// given tagging.
+struct TaggedValue<'a> {
+    value: Value,
+    tagging: &'a EnumTagging,
+}
+
+impl<'de> de::Deserializer<'de> for TaggedValue<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let tagging = self.tagging;
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Number(number) => {
+                if let Some(v) = number.as_u64() {
+                    visitor.visit_u64(v)
+                } else if let Some(v) = number.as_i64() {
+                    visitor.visit_i64(v)
+                } else {
+                    visitor.visit_f64(number.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(v) => visitor.visit_string(v),
+            Value::Array(items) => visitor.visit_seq(Items {
+                items: items.into_iter(),
+                tagging,
+            }),
+            Value::Object(fields) => visitor.visit_map(Entries {
+                entries: fields.into_iter(),
+                value: None,
+                tagging,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let (variant, content) = match (self.tagging, self.value) {
+            (_, Value::String(variant)) => (variant, None),
+            (EnumTagging::External, Value::Object(object)) if object.len() == 1 => {
+                let (variant, content) = object.into_iter().next().unwrap_or_default();
+                (variant, Some(content))
+            }
+            (EnumTagging::Internal { tag }, Value::Object(mut fields)) => {
+                (take_tag(&mut fields, tag)?, Some(Value::Object(fields)))
+            }
+            (EnumTagging::Adjacent { tag, content }, Value::Object(mut fields)) => {
+                (take_tag(&mut fields, tag)?, fields.remove(content))
+            }
+            (_, other) => {
+                return Err(de::Error::invalid_type(
+                    unexpected(&other),
+                    &"an enum variant",
+                ))
+            }
+        };
+        visitor.visit_enum(Variant {
+            variant,
+            content,
+            tagging: self.tagging,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn take_tag(fields: &mut Map<String, Value>, tag: &str) -> Result<String, Error> {
+    match fields.remove(tag) {
+        Some(Value::String(variant)) => Ok(variant),
+        _ => Err(de::Error::custom(format!(
+            "Expected a variant name in \"{tag}\""
+        ))),
+    }
+}
+
+fn unexpected(value: &Value) -> de::Unexpected<'_> {
+    match value {
+        Value::Null => de::Unexpected::Unit,
+        Value::Bool(v) => de::Unexpected::Bool(*v),
+        Value::Number(_) => de::Unexpected::Other("number"),
+        Value::String(v) => de::Unexpected::Str(v),
+        Value::Array(_) => de::Unexpected::Seq,
+        Value::Object(_) => de::Unexpected::Map,
+    }
+}
+
+struct Items<'a> {
+    items: std::vec::IntoIter<Value>,
+    tagging: &'a EnumTagging,
+}
+
+impl<'de> de::SeqAccess<'de> for Items<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.items
+            .next()
+            .map(|value| {
+                seed.deserialize(TaggedValue {
+                    value,
+                    tagging: self.tagging,
+                })
+            })
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+struct Entries<'a> {
+    entries: serde_json::map::IntoIter,
+    value: Option<Value>,
+    tagging: &'a EnumTagging,
+}
+
+impl<'de> de::MapAccess<'de> for Entries<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(Key(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| <Error as de::Error>::custom("Map value without a key"))?;
+        seed.deserialize(TaggedValue {
+            value,
+            tagging: self.tagging,
+        })
+    }
+}
+
+/// A map key. Keys of numeric maps are parsed from their strings, as serde_json does.
+struct Key(String);
+
+macro_rules! parse_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse() {
+                    Ok(key) => visitor.$visit(key),
+                    Err(_) => visitor.visit_string(self.0),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Key {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    parse_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+/// A variant being deserialized, with its content if it has any.
+struct Variant<'a> {
+    variant: String,
+    content: Option<Value>,
+    tagging: &'a EnumTagging,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = Error;
+    type Variant = Content<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Content<'a>), Error> {
+        let variant =
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+        Ok((
+            variant,
+            Content {
+                content: self.content,
+                tagging: self.tagging,
+            },
+        ))
+    }
+}
+
+struct Content<'a> {
+    content: Option<Value>,
+    tagging: &'a EnumTagging,
+}
+
+impl<'de> de::VariantAccess<'de> for Content<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(TaggedValue {
+            value: self.content.unwrap_or(Value::Null),
+            tagging: self.tagging,
+        })
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        let tagging = self.tagging;
+        match self.content {
+            Some(Value::Array(items)) => visitor.visit_seq(Items {
+                items: items.into_iter(),
+                tagging,
+            }),
+            other => Err(de::Error::invalid_type(
+                unexpected(&other.unwrap_or(Value::Null)),
+                &"a tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let tagging = self.tagging;
+        match self.content {
+            Some(Value::Object(fields)) => visitor.visit_map(Entries {
+                entries: fields.into_iter(),
+                value: None,
+                tagging,
+            }),
+            other => Err(de::Error::invalid_type(
+                unexpected(&other.unwrap_or(Value::Null)),
+                &"a struct variant",
+            )),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Canvas `measureText` results for a string, in CSS pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextMetrics {
+    /// The advance width.
+    pub width: f64,
+    pub actual_bounding_box_left: f64,
+    pub actual_bounding_box_right: f64,
+    pub actual_bounding_box_ascent: f64,
+    pub actual_bounding_box_descent: f64,
+    /// The ascent and descent of the font itself, the same for every string.
+    pub font_bounding_box_ascent: f64,
+    pub font_bounding_box_descent: f64,
+}
+
+impl TextMetrics {
+    /// The height of the font's line box, from its bounding box.
+    pub fn line_height(&self) -> f64 {
+        self.font_bounding_box_ascent + self.font_bounding_box_descent
+    }
+}
+
+/// Measures `text` as a 2D canvas would draw it in `font`, a CSS font shorthand such as
+/// `"bold 16px Inter"`. Fonts that haven't loaded yet are measured with their fallback;
+/// see [`use_fonts_ready`].
+pub async fn measure_text(text: &str, font: &str) -> Result<TextMetrics, String> {
+    eval_as(&format!(
+        r#"
+        window.__dxMeasureContext =
+            window.__dxMeasureContext || document.createElement("canvas").getContext("2d");
+        const context = window.__dxMeasureContext;
+        context.font = {font};
+        const m = context.measureText({text});
+        return {{
+            width: m.width,
+            actual_bounding_box_left: m.actualBoundingBoxLeft,
+            actual_bounding_box_right: m.actualBoundingBoxRight,
+            actual_bounding_box_ascent: m.actualBoundingBoxAscent,
+            actual_bounding_box_descent: m.actualBoundingBoxDescent,
+            font_bounding_box_ascent: m.fontBoundingBoxAscent ?? m.actualBoundingBoxAscent,
+            font_bounding_box_descent: m.fontBoundingBoxDescent ?? m.actualBoundingBoxDescent,
+        }};
+        "#,
+        font = js_string(font),
+        text = js_string(text),
+    ))
+    .await
+}
+
+/// Whether the page's web fonts have finished loading, created by [`use_fonts_ready`].
+#[derive(Clone)]
+pub struct FontsReady {
+    pub ready: Signal<bool>,
+    pub bridge: JsBridge<bool>,
+}
+
+impl FontsReady {
+    pub fn is_ready(&self) -> bool {
+        *self.ready.read()
+    }
+}
+
+/// Tracks `document.fonts` until the component unmounts. `ready` turns true once
+/// `document.fonts.ready` resolves, and false again while more fonts load, so text
+/// measured with [`measure_text`] can be measured again when it changes.
+pub fn use_fonts_ready() -> FontsReady {
+    let mut ready = use_signal(|| false);
+    let bridge = use_js_listener::<bool>(move |update| {
+        if *ready.peek() != update {
+            ready.set(update);
+        }
+    });
+
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            window.__dioxus_fonts = window.__dioxus_fonts || {{}};
+            const id = {id};
+            if (!window.__dioxus_fonts[id]) {{
+                const report = (value) => {{
+                    if ({callback}) {{
+                        {callback}(value);
+                    }}
+                }};
+                const check = () => {{
+                    if (document.fonts.status === "loading") {{
+                        report(false);
+                    }}
+                    document.fonts.ready.then(() => {{
+                        if (window.__dioxus_fonts[id] && document.fonts.status === "loaded") {{
+                            report(true);
+                        }}
+                    }});
+                }};
+                document.fonts.addEventListener("loading", check);
+                document.fonts.addEventListener("loadingdone", check);
+                document.fonts.addEventListener("loadingerror", check);
+                window.__dioxus_fonts[id] = check;
+                check();
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const check = window.__dioxus_fonts && window.__dioxus_fonts[{id}];
+            if (check) {{
+                for (const name of ["loading", "loadingdone", "loadingerror"]) {{
+                    document.fonts.removeEventListener(name, check);
+                }}
+                delete window.__dioxus_fonts[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    FontsReady { ready, bridge }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+/// Roughly one frame at 60 Hz, for platforms without `requestAnimationFrame` here.
+const FRAME: Duration = Duration::from_millis(16);
+
+/// How often received values are written to a bridge's `data` signal; see
+/// [`BridgeOptions::update_policy`](crate::BridgeOptions::update_policy).
+///
+/// Throttled bridges keep only the latest value between writes, so a stream of updates
+/// doesn't re-render on every message. Listeners and [`JsBridge::stream`](crate::JsBridge::stream)
+/// still get every value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpdatePolicy {
+    /// Writes every value as it arrives.
+    #[default]
+    Immediate,
+    /// Writes at most once per animation frame on web, and every 16 ms elsewhere.
+    PerFrame,
+    /// Writes at most once per interval.
+    Every(Duration),
+}
+
+/// Waits until a throttled bridge may write its latest value.
+pub(crate) async fn wait(policy: UpdatePolicy) {
+    match policy {
+        UpdatePolicy::Immediate => {}
+        UpdatePolicy::PerFrame => animation_frame().await,
+        UpdatePolicy::Every(interval) => crate::watchdog::sleep(interval).await,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn animation_frame() {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let (tx, rx) = futures_channel::oneshot::channel();
+    let callback = Closure::once_into_js(move || {
+        let _ = tx.send(());
+    });
+    let requested = web_sys::window()
+        .map(|window| window.request_animation_frame(callback.unchecked_ref()).is_ok())
+        .unwrap_or(false);
+    if !requested || rx.await.is_err() {
+        crate::watchdog::sleep(FRAME).await;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn animation_frame() {
+    crate::watchdog::sleep(FRAME).await
+}
@@ -0,0 +1,57 @@
+use std::future::Future;
+
+/// A step of a message's path through the bridge, traced as a span of the same name with
+/// `callback_id` and `message_id` fields when the `tracing` feature is enabled.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Stage {
+    Serialize,
+    Send,
+    Receive,
+    Deserialize,
+}
+
+#[cfg(feature = "tracing")]
+fn span(stage: Stage, callback_id: &str, message_id: Option<u64>) -> tracing::Span {
+    match stage {
+        Stage::Serialize => tracing::debug_span!("serialize", callback_id, message_id),
+        Stage::Send => tracing::debug_span!("send", callback_id, message_id),
+        Stage::Receive => tracing::debug_span!("receive", callback_id, message_id),
+        Stage::Deserialize => tracing::debug_span!("deserialize", callback_id, message_id),
+    }
+}
+
+/// Runs `f` inside the span for `stage`.
+#[allow(unused_variables)]
+pub(crate) fn in_span<R>(
+    stage: Stage,
+    callback_id: &str,
+    message_id: Option<u64>,
+    f: impl FnOnce() -> R,
+) -> R {
+    #[cfg(feature = "tracing")]
+    let _entered = span(stage, callback_id, message_id).entered();
+    f()
+}
+
+/// Awaits `future` inside the span for `stage`.
+#[allow(unused_variables)]
+pub(crate) async fn instrument<F: Future>(
+    stage: Stage,
+    callback_id: &str,
+    message_id: Option<u64>,
+    future: F,
+) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument;
+        future
+            .instrument(span(stage, callback_id, message_id))
+            .await
+    }
+    #[cfg(not(feature = "tracing"))]
+    future.await
+}
+
+/// Makes `window.dxOpen` log the ID of every message it opens, to match the Rust spans.
+#[cfg(feature = "tracing")]
+pub(crate) const JS_TRACE: &str = "window.__dioxus_trace = true;";
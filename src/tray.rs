@@ -0,0 +1,263 @@
+use crate::{use_js_bridge, JsBridge};
+use dioxus::prelude::*;
+use dioxus_desktop::trayicon::menu::{
+    CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu,
+};
+use dioxus_desktop::trayicon::{
+    Icon, MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent,
+};
+use dioxus_desktop::{use_tray_icon_event_handler, use_tray_menu_event_handler};
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// An entry of the tray icon's menu.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrayMenuItem {
+    /// Reported as [`TrayEvent::Menu`] with its `id` when chosen.
+    Item {
+        id: String,
+        label: String,
+        enabled: bool,
+    },
+    /// A checkbox, toggled by the OS before [`TrayEvent::Menu`] is reported.
+    Check {
+        id: String,
+        label: String,
+        checked: bool,
+    },
+    Separator,
+    Submenu {
+        label: String,
+        items: Vec<TrayMenuItem>,
+    },
+}
+
+impl TrayMenuItem {
+    pub fn item(id: &str, label: &str) -> Self {
+        TrayMenuItem::Item {
+            id: id.to_string(),
+            label: label.to_string(),
+            enabled: true,
+        }
+    }
+
+    pub fn check(id: &str, label: &str, checked: bool) -> Self {
+        TrayMenuItem::Check {
+            id: id.to_string(),
+            label: label.to_string(),
+            checked,
+        }
+    }
+
+    pub fn submenu(label: &str, items: Vec<TrayMenuItem>) -> Self {
+        TrayMenuItem::Submenu {
+            label: label.to_string(),
+            items,
+        }
+    }
+
+    /// Adds the IDs of this entry and the entries below it to `ids`.
+    fn collect_ids(&self, ids: &mut HashSet<String>) {
+        match self {
+            TrayMenuItem::Item { id, .. } | TrayMenuItem::Check { id, .. } => {
+                ids.insert(id.clone());
+            }
+            TrayMenuItem::Separator => {}
+            TrayMenuItem::Submenu { items, .. } => {
+                items.iter().for_each(|item| item.collect_ids(ids));
+            }
+        }
+    }
+
+    fn build(&self) -> Box<dyn IsMenuItem> {
+        match self {
+            TrayMenuItem::Item { id, label, enabled } => {
+                Box::new(MenuItem::with_id(id.as_str(), label, *enabled, None))
+            }
+            TrayMenuItem::Check { id, label, checked } => Box::new(CheckMenuItem::with_id(
+                id.as_str(),
+                label,
+                true,
+                *checked,
+                None,
+            )),
+            TrayMenuItem::Separator => Box::new(PredefinedMenuItem::separator()),
+            TrayMenuItem::Submenu { label, items } => {
+                let children: Vec<_> = items.iter().map(TrayMenuItem::build).collect();
+                let children: Vec<&dyn IsMenuItem> = children.iter().map(|item| &**item).collect();
+                Box::new(
+                    Submenu::with_items(label, true, &children)
+                        .unwrap_or_else(|_| Submenu::new(label, true)),
+                )
+            }
+        }
+    }
+}
+
+/// An RGBA image for the tray icon, 4 bytes per pixel.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrayImage {
+    #[serde(with = "crate::binary::base64_bytes")]
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// What [`use_tray`] shows.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrayOptions {
+    /// Some Linux desktops show nothing without an icon.
+    pub icon: Option<TrayImage>,
+    pub tooltip: Option<String>,
+    pub menu: Vec<TrayMenuItem>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Something the user did with the tray icon.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TrayEvent {
+    /// A menu entry was chosen.
+    Menu { id: String },
+    /// The icon was clicked. A left click also shows and focuses the app's windows.
+    Click { button: TrayButton },
+    /// Windows only.
+    DoubleClick { button: TrayButton },
+}
+
+fn tray_button(button: MouseButton) -> TrayButton {
+    match button {
+        MouseButton::Left => TrayButton::Left,
+        MouseButton::Right => TrayButton::Right,
+        MouseButton::Middle => TrayButton::Middle,
+    }
+}
+
+/// The tray icon created by [`use_tray`]. Its events are delivered like messages from JS:
+/// the latest in `bridge.data`, and every one through [`Tray::events`].
+#[derive(Clone)]
+pub struct Tray {
+    pub bridge: JsBridge<TrayEvent>,
+    icon: Option<Rc<dioxus_desktop::trayicon::TrayIcon>>,
+}
+
+impl Tray {
+    /// Returns a stream of every tray event received after this call.
+    pub fn events(&self) -> UnboundedReceiver<TrayEvent> {
+        self.bridge.stream()
+    }
+
+    pub fn last_event(&self) -> Option<TrayEvent> {
+        self.bridge.get_data()
+    }
+
+    pub fn set_tooltip(&self, tooltip: Option<&str>) -> Result<(), String> {
+        match &self.icon {
+            Some(icon) => icon
+                .set_tooltip(tooltip)
+                .map_err(|e| format!("Failed to set the tray tooltip: {}", e)),
+            None => Err("The tray icon wasn't created".to_string()),
+        }
+    }
+
+    pub fn set_visible(&self, visible: bool) -> Result<(), String> {
+        match &self.icon {
+            Some(icon) => icon
+                .set_visible(visible)
+                .map_err(|e| format!("Failed to show or hide the tray icon: {}", e)),
+            None => Err("The tray icon wasn't created".to_string()),
+        }
+    }
+}
+
+/// Shows an icon with a menu in the system tray while the component is mounted. Desktop
+/// only. The options are read on the first render.
+pub fn use_tray(options: TrayOptions) -> Tray {
+    let bridge = use_js_bridge::<TrayEvent>();
+    let tray_id = use_hook(|| bridge.callback_id());
+    let icon = use_hook(|| {
+        let mut builder = TrayIconBuilder::new()
+            .with_id(tray_id.as_str())
+            .with_menu_on_left_click(false);
+        if let Some(image) = &options.icon {
+            match Icon::from_rgba(image.rgba.clone(), image.width, image.height) {
+                Ok(icon) => builder = builder.with_icon(icon),
+                Err(e) => bridge
+                    .clone()
+                    .set_error(Some(format!("Invalid tray icon: {}", e))),
+            }
+        }
+        if let Some(tooltip) = &options.tooltip {
+            builder = builder.with_tooltip(tooltip);
+        }
+        if !options.menu.is_empty() {
+            let items: Vec<_> = options.menu.iter().map(TrayMenuItem::build).collect();
+            let items: Vec<&dyn IsMenuItem> = items.iter().map(|item| &**item).collect();
+            match Menu::with_items(&items) {
+                Ok(menu) => builder = builder.with_menu(Box::new(menu)),
+                Err(e) => bridge
+                    .clone()
+                    .set_error(Some(format!("Invalid tray menu: {}", e))),
+            }
+        }
+        match builder.build() {
+            Ok(icon) => Some(Rc::new(icon)),
+            Err(e) => {
+                bridge
+                    .clone()
+                    .set_error(Some(format!("Failed to create the tray icon: {}", e)));
+                None
+            }
+        }
+    });
+    let menu_ids = use_hook(|| {
+        let mut ids = HashSet::new();
+        options
+            .menu
+            .iter()
+            .for_each(|item| item.collect_ids(&mut ids));
+        Rc::new(ids)
+    });
+
+    let mut menu_bridge = bridge.clone();
+    use_tray_menu_event_handler(move |event| {
+        if menu_ids.contains(&event.id.0) {
+            menu_bridge.receive(TrayEvent::Menu {
+                id: event.id.0.clone(),
+            });
+        }
+    });
+
+    let mut icon_bridge = bridge.clone();
+    use_tray_icon_event_handler(move |event| {
+        let tray_event = match event {
+            TrayIconEvent::Click {
+                id,
+                button,
+                button_state: MouseButtonState::Up,
+                ..
+            } if id.0 == tray_id => TrayEvent::Click {
+                button: tray_button(*button),
+            },
+            TrayIconEvent::DoubleClick { id, button, .. } if id.0 == tray_id => {
+                TrayEvent::DoubleClick {
+                    button: tray_button(*button),
+                }
+            }
+            _ => return,
+        };
+        icon_bridge.receive(tray_event);
+    });
+
+    Tray { bridge, icon }
+}
@@ -0,0 +1,49 @@
+/// Whether the device can vibrate, for feature detection before calling [`vibrate`].
+///
+/// Checks `navigator.vibrate` on web and desktop, and `Vibrator.hasVibrator` on Android,
+/// where `RustBridge.hasVibrator({})` returns `"true"` or `"false"`.
+pub async fn can_vibrate() -> bool {
+    #[cfg(target_os = "android")]
+    {
+        matches!(
+            crate::android_bridge::call_native("hasVibrator", &serde_json::json!({})),
+            Ok(Some(answer)) if answer == "true"
+        )
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        crate::eval_as::<bool>(r#"return typeof navigator.vibrate === "function";"#)
+            .await
+            .unwrap_or(false)
+    }
+}
+
+/// Vibrates following `pattern`: alternating on and off durations in milliseconds,
+/// starting with on. An empty pattern cancels any ongoing vibration.
+///
+/// Returns whether the platform accepted the pattern. Uses `navigator.vibrate` on web and
+/// desktop, and `Vibrator` on Android, where `RustBridge.vibrate({ pattern })` returns
+/// `"true"` when it accepted the pattern.
+pub async fn vibrate(pattern: &[u32]) -> Result<bool, String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::json!({ "pattern": pattern });
+        let accepted = crate::android_bridge::call_native("vibrate", &args)?;
+        Ok(accepted.as_deref() == Some("true"))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let js_code = format!(
+            r#"
+            if (typeof navigator.vibrate !== "function") {{
+                return false;
+            }}
+            return navigator.vibrate({});
+            "#,
+            serde_json::to_string(pattern).map_err(|e| format!("Serialization error: {}", e))?
+        );
+        crate::eval_as::<bool>(&js_code).await
+    }
+}
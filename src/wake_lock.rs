@@ -0,0 +1,73 @@
+/// Keeps the screen on until [`release_wake_lock`] is called.
+///
+/// Uses the Screen Wake Lock API on web and desktop, re-acquiring the lock whenever the
+/// page becomes visible again since browsers release it on hide. On Android this sets
+/// `FLAG_KEEP_SCREEN_ON` with `RustBridge.setKeepScreenOn({ enabled: true })`, and
+/// [`release_wake_lock`] passes `false`.
+pub async fn acquire_wake_lock() -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::json!({ "enabled": true });
+        crate::android_bridge::call_native("setKeepScreenOn", &args).map(|_| ())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        crate::eval_as::<serde_json::Value>(
+            r#"
+            if (!("wakeLock" in navigator)) {
+                throw new Error("Screen Wake Lock is not supported");
+            }
+            let lock = window.__dioxus_wake_lock;
+            if (!lock) {
+                lock = { sentinel: null, wanted: false };
+                lock.acquire = async () => {
+                    if (lock.wanted && !lock.sentinel && document.visibilityState === "visible") {
+                        lock.sentinel = await navigator.wakeLock.request("screen");
+                        lock.sentinel.addEventListener("release", () => {
+                            lock.sentinel = null;
+                        });
+                    }
+                };
+                lock.onVisibility = () => lock.acquire().catch(() => {});
+                window.__dioxus_wake_lock = lock;
+            }
+            if (!lock.wanted) {
+                lock.wanted = true;
+                document.addEventListener("visibilitychange", lock.onVisibility);
+            }
+            await lock.acquire();
+            "#,
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Releases the lock taken by [`acquire_wake_lock`].
+pub async fn release_wake_lock() -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        let args = serde_json::json!({ "enabled": false });
+        crate::android_bridge::call_native("setKeepScreenOn", &args).map(|_| ())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        crate::eval_as::<serde_json::Value>(
+            r#"
+            const lock = window.__dioxus_wake_lock;
+            if (lock && lock.wanted) {
+                lock.wanted = false;
+                document.removeEventListener("visibilitychange", lock.onVisibility);
+                if (lock.sentinel) {
+                    await lock.sentinel.release();
+                    lock.sentinel = null;
+                }
+            }
+            "#,
+        )
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long [`RawBridge::ping`](crate::RawBridge::ping) waits when no watchdog sets a timeout.
+pub(crate) const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Periodically pings JS and tracks whether it still answers; see
+/// [`BridgeOptions::watchdog`](crate::BridgeOptions::watchdog).
+///
+/// A ping fails when the WebView doesn't respond in time, or when the bridge's callback is
+/// gone from the page, as after Android kills the renderer process and reloads it.
+#[derive(Clone)]
+pub struct Watchdog {
+    pub(crate) interval: Duration,
+    pub(crate) timeout: Duration,
+    on_disconnected: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl Watchdog {
+    /// Pings every `interval`, waiting up to `interval` for each answer.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            timeout: interval,
+            on_disconnected: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Called once each time a bridge that was alive stops answering.
+    pub fn on_disconnected(mut self, handler: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_disconnected = Some(Arc::new(handler));
+        self
+    }
+
+    pub(crate) fn disconnected(&self) {
+        if let Some(handler) = &self.on_disconnected {
+            handler();
+        }
+    }
+}
+
+impl fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Watchdog {
+    fn eq(&self, other: &Self) -> bool {
+        let same_handler = match (&self.on_disconnected, &other.on_disconnected) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        self.interval == other.interval && self.timeout == other.timeout && same_handler
+    }
+}
+
+/// Waits for `future`, failing once `timeout` has passed.
+pub(crate) async fn with_timeout<F: Future>(
+    future: F,
+    timeout: Duration,
+) -> Result<F::Output, String> {
+    use futures_util::future::{select, Either};
+    let future = std::pin::pin!(future);
+    match select(future, futures_timer::Delay::new(timeout)).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(format!("JS didn't answer within {:?}", timeout)),
+    }
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    futures_timer::Delay::new(duration).await
+}
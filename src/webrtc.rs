@@ -0,0 +1,293 @@
+use crate::binary::{self, JS_BASE64_HELPERS};
+use crate::{eval_as, eval_detached, js_string, use_js_listener, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+
+/// An SDP offer or answer, exchanged through the app's own signaling channel.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionDescription {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub sdp: String,
+}
+
+/// An ICE candidate, exchanged through the app's own signaling channel.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IceCandidate {
+    pub candidate: String,
+    #[serde(rename = "sdpMid")]
+    pub sdp_mid: Option<String>,
+    #[serde(rename = "sdpMLineIndex")]
+    pub sdp_m_line_index: Option<u16>,
+}
+
+/// Configuration of the `RTCPeerConnection`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RtcConfig {
+    /// STUN/TURN server URLs.
+    pub ice_servers: Vec<String>,
+    /// Label of the data channel created by the offering side.
+    pub channel_label: String,
+    /// Whether the data channel guarantees in-order delivery.
+    pub ordered: bool,
+}
+
+impl Default for RtcConfig {
+    fn default() -> Self {
+        Self {
+            ice_servers: vec!["stun:stun.l.google.com:19302".to_string()],
+            channel_label: "data".to_string(),
+            ordered: true,
+        }
+    }
+}
+
+/// An event from the peer connection or its data channel.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RtcEvent {
+    /// A local candidate to forward to the remote peer.
+    IceCandidate {
+        candidate: IceCandidate,
+    },
+    IceGatheringComplete,
+    /// `RTCPeerConnection.connectionState` changed.
+    ConnectionState {
+        state: String,
+    },
+    ChannelOpen,
+    ChannelClose,
+    Text {
+        data: String,
+    },
+    Binary {
+        #[serde(with = "crate::binary::base64_bytes")]
+        data: Vec<u8>,
+    },
+}
+
+/// A WebRTC peer connection living in the page, created by [`use_webrtc_peer`].
+#[derive(Clone)]
+pub struct RtcPeer {
+    pub connection_state: Signal<String>,
+    pub channel_open: Signal<bool>,
+    pub bridge: JsBridge<RtcEvent>,
+}
+
+impl RtcPeer {
+    /// Returns a stream of every peer event received after this call.
+    pub fn events(&self) -> UnboundedReceiver<RtcEvent> {
+        self.bridge.stream()
+    }
+
+    pub fn is_channel_open(&self) -> bool {
+        *self.channel_open.read()
+    }
+
+    /// Creates the data channel and an offer, and sets it as the local description.
+    pub async fn create_offer(&self) -> Result<SessionDescription, String> {
+        self.run(
+            r#"
+            attachChannel(peer.pc.createDataChannel(peer.config.channel_label, {
+                ordered: peer.config.ordered,
+            }));
+            const offer = await peer.pc.createOffer();
+            await peer.pc.setLocalDescription(offer);
+            return { type: offer.type, sdp: offer.sdp };
+            "#,
+        )
+        .await
+    }
+
+    /// Applies a remote offer and returns the answer to send back.
+    pub async fn accept_offer(
+        &self,
+        offer: &SessionDescription,
+    ) -> Result<SessionDescription, String> {
+        self.run(&format!(
+            r#"
+            await peer.pc.setRemoteDescription({});
+            const answer = await peer.pc.createAnswer();
+            await peer.pc.setLocalDescription(answer);
+            return {{ type: answer.type, sdp: answer.sdp }};
+            "#,
+            to_json(offer)?
+        ))
+        .await
+    }
+
+    /// Applies the remote answer to an offer created by [`RtcPeer::create_offer`].
+    pub async fn accept_answer(&self, answer: &SessionDescription) -> Result<(), String> {
+        self.run::<serde_json::Value>(&format!(
+            "await peer.pc.setRemoteDescription({});",
+            to_json(answer)?
+        ))
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn add_ice_candidate(&self, candidate: &IceCandidate) -> Result<(), String> {
+        self.run::<serde_json::Value>(&format!(
+            "await peer.pc.addIceCandidate({});",
+            to_json(candidate)?
+        ))
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn send_text(&self, text: &str) -> Result<(), String> {
+        self.run::<serde_json::Value>(&format!("openChannel().send({});", js_string(text)))
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn send_bytes(&self, bytes: &[u8]) -> Result<(), String> {
+        self.run::<serde_json::Value>(&format!(
+            "openChannel().send(__dxFromBase64({}));",
+            js_string(&binary::encode(bytes))
+        ))
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn close(&self) -> Result<(), String> {
+        eval_as::<serde_json::Value>(&close_script(&self.bridge.callback_id()))
+            .await
+            .map(|_| ())
+    }
+
+    /// Runs `body` with `peer`, `attachChannel` and `openChannel` in scope.
+    async fn run<R: crate::FromJs>(&self, body: &str) -> Result<R, String> {
+        let js_code = format!(
+            r#"
+            {JS_BASE64_HELPERS}
+            const peer = window.__dioxus_rtc && window.__dioxus_rtc[{id}];
+            if (!peer) {{
+                throw new Error("Peer connection is closed");
+            }}
+            const attachChannel = peer.attachChannel;
+            const openChannel = () => {{
+                if (!peer.channel || peer.channel.readyState !== "open") {{
+                    throw new Error("Data channel is not open");
+                }}
+                return peer.channel;
+            }};
+            {body}
+            "#,
+            id = js_string(&self.bridge.callback_id()),
+        );
+        eval_as(&js_code).await
+    }
+}
+
+fn to_json<S: Serialize>(value: &S) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Serialization error: {}", e))
+}
+
+fn close_script(id: &str) -> String {
+    format!(
+        r#"
+        const peer = window.__dioxus_rtc && window.__dioxus_rtc[{id}];
+        if (peer) {{
+            if (peer.channel) peer.channel.close();
+            peer.pc.close();
+            delete window.__dioxus_rtc[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Creates an `RTCPeerConnection` in the page. Signaling (exchanging offers, answers and
+/// ICE candidates) is left to the app; data channel traffic arrives as [`RtcEvent`]s.
+///
+/// The connection is closed when the component unmounts.
+pub fn use_webrtc_peer(config: RtcConfig) -> RtcPeer {
+    let mut connection_state = use_signal(|| "new".to_string());
+    let mut channel_open = use_signal(|| false);
+    let bridge = use_js_listener::<RtcEvent>(move |event| match event {
+        RtcEvent::ConnectionState { state } => connection_state.set(state),
+        RtcEvent::ChannelOpen => channel_open.set(true),
+        RtcEvent::ChannelClose => channel_open.set(false),
+        _ => {}
+    });
+
+    let config = use_hook(move || config);
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            {JS_BASE64_HELPERS}
+            window.__dioxus_rtc = window.__dioxus_rtc || {{}};
+            const id = {id};
+            if (!window.__dioxus_rtc[id]) {{
+                const config = {config};
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const pc = new RTCPeerConnection({{
+                    iceServers: config.ice_servers.map((urls) => ({{ urls }})),
+                }});
+                const peer = {{ pc, config, channel: null }};
+                peer.attachChannel = (channel) => {{
+                    peer.channel = channel;
+                    channel.binaryType = "arraybuffer";
+                    channel.onopen = () => emit({{ event: "channel_open" }});
+                    channel.onclose = () => emit({{ event: "channel_close" }});
+                    channel.onmessage = (e) => {{
+                        if (typeof e.data === "string") {{
+                            emit({{ event: "text", data: e.data }});
+                        }} else {{
+                            emit({{ event: "binary", data: __dxToBase64(new Uint8Array(e.data)) }});
+                        }}
+                    }};
+                }};
+                pc.ondatachannel = (e) => peer.attachChannel(e.channel);
+                pc.onicecandidate = (e) => {{
+                    if (e.candidate) {{
+                        emit({{
+                            event: "ice_candidate",
+                            candidate: {{
+                                candidate: e.candidate.candidate,
+                                sdpMid: e.candidate.sdpMid,
+                                sdpMLineIndex: e.candidate.sdpMLineIndex,
+                            }},
+                        }});
+                    }} else {{
+                        emit({{ event: "ice_gathering_complete" }});
+                    }}
+                }};
+                pc.onconnectionstatechange = () => emit({{
+                    event: "connection_state",
+                    state: pc.connectionState,
+                }});
+                window.__dioxus_rtc[id] = peer;
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+            config = serde_json::to_string(&config).unwrap_or_else(|_| "{}".to_string()),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&close_script(&callback_id));
+    });
+
+    RtcPeer {
+        connection_state,
+        channel_open,
+        bridge,
+    }
+}
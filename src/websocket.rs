@@ -0,0 +1,271 @@
+use crate::binary::JS_BASE64_HELPERS;
+use crate::{eval_as, eval_detached, js_string, use_js_listener, FromJs, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Connection state of a [`JsWebSocket`], mirroring `WebSocket.readyState`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebSocketState {
+    Connecting,
+    Open,
+    Closed,
+}
+
+/// An event forwarded from the JS-side `WebSocket`.
+///
+/// Text frames arrive as `Message`: parsed as JSON when possible and passed through as
+/// strings otherwise by [`use_js_websocket`], and verbatim by [`use_js_websocket_text`].
+/// Binary frames arrive as `Binary`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebSocketEvent<T> {
+    Open,
+    Message {
+        data: T,
+    },
+    Binary {
+        #[serde(with = "crate::binary::base64_bytes")]
+        data: Vec<u8>,
+    },
+    Close {
+        code: u16,
+        reason: String,
+        was_clean: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// A WebSocket owned by the page, created by [`use_js_websocket`].
+#[derive(Clone)]
+pub struct JsWebSocket<T: FromJs + Clone> {
+    pub state: Signal<WebSocketState>,
+    pub bridge: JsBridge<WebSocketEvent<T>>,
+}
+
+impl<T: FromJs + Clone> JsWebSocket<T> {
+    pub fn get_state(&self) -> WebSocketState {
+        *self.state.read()
+    }
+
+    /// The most recent message received on the socket.
+    pub fn last_message(&self) -> Option<T> {
+        match self.bridge.get_data() {
+            Some(WebSocketEvent::Message { data }) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns a stream of every socket event received after this call.
+    pub fn events(&self) -> UnboundedReceiver<WebSocketEvent<T>> {
+        self.bridge.stream()
+    }
+
+    /// Sends `data` serialized as a JSON text frame.
+    pub async fn send<S: Serialize>(&self, data: &S) -> Result<(), String> {
+        let json =
+            serde_json::to_string(data).map_err(|e| format!("Serialization error: {}", e))?;
+        self.send_text(&json).await
+    }
+
+    /// Sends a raw text frame.
+    pub async fn send_text(&self, text: &str) -> Result<(), String> {
+        self.send_frame(&js_string(text)).await
+    }
+
+    /// Sends a binary frame.
+    pub async fn send_binary(&self, bytes: &[u8]) -> Result<(), String> {
+        let frame = format!(
+            "__dxFromBase64({})",
+            js_string(&crate::binary::encode(bytes))
+        );
+        self.send_frame(&frame).await
+    }
+
+    /// Sends the value of the JS expression `frame` on the socket.
+    async fn send_frame(&self, frame: &str) -> Result<(), String> {
+        let js_code = format!(
+            r#"
+            {JS_BASE64_HELPERS}
+            const socket = window.__dioxus_ws && window.__dioxus_ws[{id}];
+            if (!socket || socket.readyState !== WebSocket.OPEN) {{
+                throw new Error("WebSocket is not open");
+            }}
+            socket.send({frame});
+            "#,
+            id = js_string(&self.bridge.callback_id()),
+        );
+        eval_as::<serde_json::Value>(&js_code).await.map(|_| ())
+    }
+
+    pub async fn close(&self) -> Result<(), String> {
+        eval_as::<serde_json::Value>(&close_script(&self.bridge.callback_id()))
+            .await
+            .map(|_| ())
+    }
+}
+
+fn close_script(id: &str) -> String {
+    format!(
+        r#"
+        const socket = window.__dioxus_ws && window.__dioxus_ws[{id}];
+        if (socket) {{
+            socket.close();
+            delete window.__dioxus_ws[{id}];
+        }}
+        "#,
+        id = js_string(id)
+    )
+}
+
+/// Opens a WebSocket to `url` in the page and forwards its events to Rust.
+///
+/// The socket lives in JS, so it uses the browser's WebSocket stack and the page's cookies.
+/// It is closed when the component unmounts. Text frames are parsed as JSON, so a frame
+/// such as `"123"` arrives as a number; use [`use_js_websocket_text`] for text protocols.
+pub fn use_js_websocket<T>(url: &str) -> JsWebSocket<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    use_websocket(url, false)
+}
+
+/// Like [`use_js_websocket`], but delivers text frames as they arrived, without parsing
+/// them as JSON.
+pub fn use_js_websocket_text(url: &str) -> JsWebSocket<String> {
+    use_websocket(url, true)
+}
+
+fn use_websocket<T>(url: &str, raw_text: bool) -> JsWebSocket<T>
+where
+    T: FromJs + Clone + Debug + 'static,
+{
+    let mut state = use_signal(|| WebSocketState::Connecting);
+    let bridge = use_js_listener::<WebSocketEvent<T>>(move |event| match event {
+        WebSocketEvent::Open => state.set(WebSocketState::Open),
+        WebSocketEvent::Close { .. } => state.set(WebSocketState::Closed),
+        WebSocketEvent::Message { .. }
+        | WebSocketEvent::Binary { .. }
+        | WebSocketEvent::Error { .. } => {}
+    });
+
+    let url = url.to_string();
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            {JS_BASE64_HELPERS}
+            window.__dioxus_ws = window.__dioxus_ws || {{}};
+            const id = {id};
+            if (!window.__dioxus_ws[id]) {{
+                const emit = (event) => {{
+                    if ({callback}) {{
+                        {callback}(event);
+                    }}
+                }};
+                const socket = new WebSocket({url});
+                socket.binaryType = "arraybuffer";
+                window.__dioxus_ws[id] = socket;
+                socket.onopen = () => emit({{ event: "open" }});
+                socket.onmessage = (e) => {{
+                    if (e.data instanceof ArrayBuffer) {{
+                        emit({{ event: "binary", data: __dxToBase64(new Uint8Array(e.data)) }});
+                        return;
+                    }}
+                    let data = e.data;
+                    if (!{raw_text}) {{
+                        try {{
+                            data = JSON.parse(e.data);
+                        }} catch (_) {{}}
+                    }}
+                    emit({{ event: "message", data }});
+                }};
+                socket.onerror = () => emit({{ event: "error", message: "WebSocket error" }});
+                socket.onclose = (e) => emit({{
+                    event: "close",
+                    code: e.code,
+                    reason: e.reason,
+                    was_clean: e.wasClean,
+                }});
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+            url = js_string(&url),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&close_script(&callback_id));
+    });
+
+    JsWebSocket { state, bridge }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn text_frames_decode_into_the_message_type() {
+        let event: WebSocketEvent<serde_json::Value> =
+            serde_json::from_value(json!({ "event": "message", "data": { "n": 1 } })).unwrap();
+        assert_eq!(
+            event,
+            WebSocketEvent::Message {
+                data: json!({ "n": 1 })
+            }
+        );
+        let event: WebSocketEvent<String> =
+            serde_json::from_value(json!({ "event": "message", "data": "{\"n\":1}" })).unwrap();
+        assert_eq!(
+            event,
+            WebSocketEvent::Message {
+                data: "{\"n\":1}".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn binary_frames_decode_to_bytes() {
+        let event: WebSocketEvent<String> =
+            serde_json::from_value(json!({ "event": "binary", "data": "AAEC/w==" })).unwrap();
+        assert_eq!(
+            event,
+            WebSocketEvent::Binary {
+                data: vec![0, 1, 2, 255]
+            }
+        );
+    }
+
+    #[test]
+    fn close_events_keep_their_reason() {
+        let event: WebSocketEvent<String> = serde_json::from_value(json!({
+            "event": "close",
+            "code": 1001,
+            "reason": "going away",
+            "was_clean": true,
+        }))
+        .unwrap();
+        assert_eq!(
+            event,
+            WebSocketEvent::Close {
+                code: 1001,
+                reason: "going away".to_string(),
+                was_clean: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::binary::{self, JS_BASE64_HELPERS};
+use crate::{eval_as, eval_detached, js_string, use_js_listener, FromJs, JsBridge};
+use dioxus::core::use_drop;
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use serde::Serialize;
+
+/// Runs inside every worker before the user script. Defines the built-in tasks,
+/// `register(name, fn)` for adding tasks and `emit(data)` for pushing events to Rust.
+const WORKER_PRELUDE: &str = r#"
+const __tasks = {
+    parse_json: (text) => JSON.parse(text),
+    gzip: async (bytes) => new Uint8Array(await new Response(
+        new Blob([bytes]).stream().pipeThrough(new CompressionStream("gzip"))
+    ).arrayBuffer()),
+    gunzip: async (bytes) => new Uint8Array(await new Response(
+        new Blob([bytes]).stream().pipeThrough(new DecompressionStream("gzip"))
+    ).arrayBuffer()),
+};
+const register = (name, fn) => { __tasks[name] = fn; };
+const emit = (data) => self.postMessage({ event: data });
+self.onmessage = async (e) => {
+    const { id, task, payload } = e.data;
+    try {
+        const fn = __tasks[task];
+        if (!fn) throw new Error("Unknown worker task: " + task);
+        const result = await fn(payload);
+        const transfer = result instanceof ArrayBuffer ? [result]
+            : ArrayBuffer.isView(result) ? [result.buffer] : [];
+        self.postMessage({ id, ok: result }, transfer);
+    } catch (err) {
+        self.postMessage({ id, err: String((err && err.message) || err) });
+    }
+};
+"#;
+
+/// A dedicated Web Worker for heavy JS-side work, created by [`use_js_worker`].
+///
+/// Tasks are JS functions in the worker: the built-in `parse_json`, `gzip` and `gunzip`,
+/// plus any the worker script adds with `register(name, async (payload) => result)`.
+/// Byte payloads and results are moved between threads as transferred `ArrayBuffer`s.
+/// Worker scripts can push events to Rust with `emit(data)`; they arrive through
+/// [`JsWorker::events`].
+#[derive(Clone)]
+pub struct JsWorker {
+    pub bridge: JsBridge<serde_json::Value>,
+}
+
+impl JsWorker {
+    /// Returns a stream of every event emitted by the worker after this call.
+    pub fn events(&self) -> UnboundedReceiver<serde_json::Value> {
+        self.bridge.stream()
+    }
+
+    /// Runs `task` in the worker with a JSON payload and returns its result.
+    pub async fn run<P: Serialize, R: FromJs>(&self, task: &str, payload: &P) -> Result<R, String> {
+        let payload =
+            serde_json::to_string(payload).map_err(|e| format!("Serialization error: {}", e))?;
+        self.call(&format!(
+            "return await worker.call({}, {}, []);",
+            js_string(task),
+            payload
+        ))
+        .await
+    }
+
+    /// Runs `task` with `bytes` as a `Uint8Array` and returns the bytes it resolves with.
+    pub async fn run_bytes(&self, task: &str, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let encoded = self
+            .call::<String>(&format!(
+                r#"
+                {JS_BASE64_HELPERS}
+                const bytes = __dxFromBase64({bytes});
+                const result = await worker.call({task}, bytes, [bytes.buffer]);
+                if (!(result instanceof ArrayBuffer) && !ArrayBuffer.isView(result)) {{
+                    throw new Error("Worker task did not return bytes");
+                }}
+                return __dxToBase64(result instanceof ArrayBuffer
+                    ? new Uint8Array(result)
+                    : new Uint8Array(result.buffer, result.byteOffset, result.byteLength));
+                "#,
+                bytes = js_string(&binary::encode(bytes)),
+                task = js_string(task),
+            ))
+            .await?;
+        binary::decode(&encoded)
+    }
+
+    /// Runs `body` with `worker` in scope.
+    async fn call<R: FromJs>(&self, body: &str) -> Result<R, String> {
+        let js_code = format!(
+            r#"
+            const worker = window.__dioxus_workers && window.__dioxus_workers[{id}];
+            if (!worker) {{
+                throw new Error("Worker is not running");
+            }}
+            {body}
+            "#,
+            id = js_string(&self.bridge.callback_id()),
+        );
+        eval_as(&js_code).await
+    }
+}
+
+/// Starts a dedicated worker running `script` after the built-in prelude. The worker is
+/// terminated when the component unmounts.
+pub fn use_js_worker(script: &str) -> JsWorker {
+    let bridge = use_js_listener::<serde_json::Value>(|_| {});
+
+    let script = use_hook(|| script.to_string());
+    let bridge_for_effect = bridge.clone();
+    use_effect(move || {
+        let js_code = format!(
+            r#"
+            window.__dioxus_workers = window.__dioxus_workers || {{}};
+            const id = {id};
+            if (!window.__dioxus_workers[id]) {{
+                const source = {prelude} + "\n" + {script};
+                const url = URL.createObjectURL(new Blob([source], {{ type: "text/javascript" }}));
+                const w = new Worker(url);
+                URL.revokeObjectURL(url);
+                const pending = new Map();
+                let nextId = 0;
+                w.onmessage = (e) => {{
+                    const data = e.data;
+                    if ("event" in data) {{
+                        if ({callback}) {{
+                            {callback}(data.event);
+                        }}
+                        return;
+                    }}
+                    const call = pending.get(data.id);
+                    if (!call) return;
+                    pending.delete(data.id);
+                    if ("err" in data) call.reject(new Error(data.err));
+                    else call.resolve(data.ok);
+                }};
+                w.onerror = (e) => {{
+                    for (const call of pending.values()) {{
+                        call.reject(new Error(e.message || "Worker error"));
+                    }}
+                    pending.clear();
+                }};
+                window.__dioxus_workers[id] = {{
+                    worker: w,
+                    pending,
+                    call: (task, payload, transfer) => new Promise((resolve, reject) => {{
+                        const callId = nextId++;
+                        pending.set(callId, {{ resolve, reject }});
+                        w.postMessage({{ id: callId, task, payload }}, transfer);
+                    }}),
+                }};
+            }}
+            "#,
+            id = js_string(&bridge_for_effect.callback_id()),
+            callback = bridge_for_effect.js_callback(),
+            prelude = js_string(WORKER_PRELUDE),
+            script = js_string(&script),
+        );
+        let mut bridge = bridge_for_effect.clone();
+        spawn(async move {
+            if let Err(e) = eval_as::<serde_json::Value>(&js_code).await {
+                bridge.set_error(Some(e));
+            }
+        });
+    });
+
+    let callback_id = bridge.callback_id();
+    use_drop(move || {
+        eval_detached(&format!(
+            r#"
+            const worker = window.__dioxus_workers && window.__dioxus_workers[{id}];
+            if (worker) {{
+                worker.worker.terminate();
+                for (const call of worker.pending.values()) {{
+                    call.reject(new Error("Worker terminated"));
+                }}
+                delete window.__dioxus_workers[{id}];
+            }}
+            "#,
+            id = js_string(&callback_id)
+        ));
+    });
+
+    JsWorker { bridge }
+}
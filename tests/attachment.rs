@@ -0,0 +1,40 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+// Attachments hand files to the page by URL; temporary ones must not outlive their handles.
+
+use dx_use_js_bridge::{Attachment, ATTACHMENT_PROTOCOL};
+
+#[test]
+fn temporary_files_are_removed_with_the_last_clone() {
+    let attachment = Attachment::from_bytes(b"frame data", "clip one.mp4", "video/mp4").unwrap();
+    let path = attachment.path().to_path_buf();
+    assert_eq!(attachment.read().unwrap(), b"frame data");
+    assert_eq!(attachment.info().size, 10);
+    assert!(attachment.url().starts_with(&format!("/{ATTACHMENT_PROTOCOL}/")));
+
+    let clone = attachment.clone();
+    drop(attachment);
+    assert!(path.exists());
+    drop(clone);
+    assert!(!path.exists());
+}
+
+#[test]
+fn existing_files_are_kept() {
+    let path = std::env::temp_dir().join("dx_attachment_test.png");
+    std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+    let attachment = Attachment::from_path(&path, None).unwrap();
+    assert_eq!(attachment.info().mime_type, "image/png");
+    assert_eq!(attachment.info().name, "dx_attachment_test.png");
+
+    let value = serde_json::to_value(&attachment).unwrap();
+    assert_eq!(value["url"], attachment.url());
+    drop(attachment);
+    assert!(path.exists());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn missing_files_are_an_error() {
+    assert!(Attachment::from_path("/nonexistent/video.mp4", None).is_err());
+}
@@ -0,0 +1,60 @@
+// Opens a hidden Dioxus desktop window and checks round trips through the real webview.
+// Opt in with `cargo test --features desktop-it --test desktop`; it needs a display, e.g.
+// `xvfb-run` on CI.
+use dioxus::desktop::{Config, WindowBuilder};
+use dioxus::prelude::*;
+use dx_use_js_bridge::{eval_as, use_js_bridge};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+fn main() {
+    std::thread::spawn(|| {
+        std::thread::sleep(TIMEOUT);
+        eprintln!("desktop-it: timed out after {:?}", TIMEOUT);
+        std::process::exit(1);
+    });
+    let window = WindowBuilder::new()
+        .with_title("dx_use_js_bridge desktop-it")
+        .with_visible(false);
+    dioxus::desktop::launch::launch_virtual_dom(
+        VirtualDom::new(app),
+        Config::new().with_window(window),
+    );
+}
+
+fn check(name: &str, passed: bool) -> bool {
+    eprintln!("test {name} ... {}", if passed { "ok" } else { "FAILED" });
+    passed
+}
+
+fn app() -> Element {
+    let bridge = use_js_bridge::<String>();
+    // Runs after the bridge's own effect, which registers the callback
+    use_effect(move || {
+        let mut bridge = bridge.clone();
+        spawn(async move {
+            let mut received = bridge.stream();
+            let mut passed = true;
+
+            passed &= check("eval", eval_as::<u32>("return 40 + 2;").await == Ok(42));
+
+            let script = format!("{}(\"from js ✓\");", bridge.js_callback());
+            let sent = bridge.eval(&script).await.is_ok();
+            let echoed = received.next().await;
+            passed &= check("js_to_rust", sent && echoed.as_deref() == Some("from js ✓"));
+
+            // Values sent to the bridge's own JS callback come straight back
+            let large = "🦀".repeat(100_000);
+            for value in ["from rust ✓".to_string(), large] {
+                let sent = bridge.send_to_js(&value).await.is_ok();
+                let echoed = received.next().await;
+                passed &= check("rust_to_js_to_rust", sent && echoed == Some(value));
+            }
+
+            std::process::exit(if passed { 0 } else { 1 });
+        });
+    });
+    rsx! {}
+}
@@ -0,0 +1,209 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+// Property tests: values of representative types must survive the JSON codec exactly,
+// and strings must survive being embedded in an eval script.
+
+use dx_use_js_bridge::{Codec, JsonCodec, PreparedMessage};
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect { w: f64, h: f64 },
+    Path(Vec<(f32, f32)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    name: String,
+    letter: char,
+    // Sorted, so encoding the decoded value gives the same bytes
+    tags: BTreeMap<String, i64>,
+    by_id: BTreeMap<u32, String>,
+    shapes: Vec<Shape>,
+    ratio: f64,
+    small: f32,
+    big: u64,
+    negative: i64,
+    bytes: Vec<u8>,
+    note: Option<String>,
+}
+
+/// Finite floats of every class; JSON has no NaN or infinities.
+fn finite_f64() -> impl Strategy<Value = f64> {
+    prop::num::f64::NORMAL | prop::num::f64::SUBNORMAL | prop::num::f64::ZERO
+}
+
+fn finite_f32() -> impl Strategy<Value = f32> {
+    prop::num::f32::NORMAL | prop::num::f32::SUBNORMAL | prop::num::f32::ZERO
+}
+
+/// Keys that are empty, unicode, quoted, escaped or look like numbers.
+fn weird_key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        Just("\"".to_string()),
+        Just("\\".to_string()),
+        Just("__proto__".to_string()),
+        Just("\u{2028}\u{2029}".to_string()),
+        "-?[0-9]{1,20}",
+        any::<String>(),
+    ]
+}
+
+fn shape() -> impl Strategy<Value = Shape> {
+    prop_oneof![
+        Just(Shape::Empty),
+        finite_f64().prop_map(Shape::Circle),
+        (finite_f64(), finite_f64()).prop_map(|(w, h)| Shape::Rect { w, h }),
+        prop::collection::vec((finite_f32(), finite_f32()), 0..8).prop_map(Shape::Path),
+    ]
+}
+
+fn record() -> impl Strategy<Value = Record> {
+    (
+        (any::<String>(), any::<char>()),
+        prop::collection::btree_map(weird_key(), any::<i64>(), 0..8),
+        prop::collection::btree_map(any::<u32>(), any::<String>(), 0..8),
+        prop::collection::vec(shape(), 0..8),
+        (finite_f64(), finite_f32(), any::<u64>(), any::<i64>()),
+        (
+            prop::collection::vec(any::<u8>(), 0..64),
+            any::<Option<String>>(),
+        ),
+    )
+        .prop_map(
+            |(
+                (name, letter),
+                tags,
+                by_id,
+                shapes,
+                (ratio, small, big, negative),
+                (bytes, note),
+            )| {
+                Record {
+                    name,
+                    letter,
+                    tags,
+                    by_id,
+                    shapes,
+                    ratio,
+                    small,
+                    big,
+                    negative,
+                    bytes,
+                    note,
+                }
+            },
+        )
+}
+
+/// Arbitrary JSON, nested up to eight levels.
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        any::<u64>().prop_map(Value::from),
+        finite_f64().prop_map(Value::from),
+        any::<String>().prop_map(Value::from),
+    ];
+    leaf.prop_recursive(8, 256, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+            prop::collection::btree_map(weird_key(), inner, 0..8)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+/// Encodes `value`, decodes it back and encodes it again, requiring the same value and
+/// the same bytes.
+fn assert_round_trip<T>(value: &T) -> Result<(), TestCaseError>
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug + 'static,
+{
+    let encoded = JsonCodec::encode(value).map_err(TestCaseError::fail)?;
+    let wire: Value =
+        serde_json::from_str(&encoded).map_err(|e| TestCaseError::fail(e.to_string()))?;
+    let decoded: T = JsonCodec::decode(wire).map_err(TestCaseError::fail)?;
+    prop_assert_eq!(&decoded, value);
+    prop_assert_eq!(
+        JsonCodec::encode(&decoded).map_err(TestCaseError::fail)?,
+        encoded
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn records_round_trip(record in record()) {
+        assert_round_trip(&record)?;
+    }
+
+    #[test]
+    fn json_values_round_trip(value in json_value()) {
+        assert_round_trip(&value)?;
+    }
+
+    #[test]
+    fn stringified_records_decode(record in record()) {
+        // JS may send `JSON.stringify` output instead of an object
+        let encoded = JsonCodec::encode(&record).map_err(TestCaseError::fail)?;
+        let decoded: Record = JsonCodec::decode(Value::String(encoded)).map_err(TestCaseError::fail)?;
+        prop_assert_eq!(decoded, record);
+    }
+
+    #[test]
+    fn prepared_messages_match_the_codec(value in json_value()) {
+        let prepared = PreparedMessage::new(&value).map_err(TestCaseError::fail)?;
+        prop_assert_eq!(prepared.as_json(), JsonCodec::encode(&value).map_err(TestCaseError::fail)?);
+    }
+
+    #[test]
+    fn deep_nesting_round_trips(depth in 1usize..120, leaf in any::<String>()) {
+        // serde_json stops at 128 levels
+        let value = (0..depth).fold(Value::from(leaf), |inner, level| {
+            if level % 2 == 0 {
+                Value::Array(vec![inner])
+            } else {
+                serde_json::json!({ "\u{1F980}": inner })
+            }
+        });
+        assert_round_trip(&value)?;
+    }
+}
+
+#[cfg(feature = "fuzzing")]
+proptest! {
+    #[test]
+    fn embedded_strings_round_trip(text in any::<String>()) {
+        let literal = dx_use_js_bridge::__fuzz::js_string(&text);
+        // A JSON string literal is a JS string literal; control characters must be escaped
+        // so the script stays on its lines.
+        prop_assert!(!literal.chars().any(|c| c.is_control() && (c as u32) < 0x20));
+        let parsed: String = serde_json::from_str(&literal).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(parsed, text);
+    }
+}
+
+#[cfg(all(feature = "fuzzing", feature = "compression"))]
+#[test]
+fn gzipped_messages_stop_inflating_at_the_cap() {
+    use base64::Engine;
+    use std::io::Write;
+    let inflate = |json: &str| {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(json.as_bytes()).unwrap();
+        let data = base64::engine::general_purpose::STANDARD.encode(encoder.finish().unwrap());
+        dx_use_js_bridge::__fuzz::envelope(serde_json::json!({ "z": "gzip", "data": data }))
+    };
+    assert_eq!(inflate("[1]"), Ok(serde_json::json!([1])));
+    // A few kilobytes that would inflate to more than the default cap
+    let bomb = format!("{}0", " ".repeat(dx_use_js_bridge::MAX_INFLATED_SIZE));
+    assert!(inflate(&bomb).unwrap_err().contains("inflates past"));
+}
@@ -0,0 +1,63 @@
+#![cfg(all(not(target_arch = "wasm32"), feature = "security", feature = "fuzzing"))]
+
+// Protected messages must not verify once their envelope's ID or version is changed, and
+// must not be accepted twice.
+
+use dx_use_js_bridge::{__fuzz, __fuzz::Sealed, Security};
+use serde_json::json;
+
+const MODES: [Security; 2] = [Security::Sign, Security::Encrypt];
+
+#[test]
+fn sealed_messages_open_unchanged() {
+    for mode in MODES {
+        let opened = __fuzz::reseal(mode, r#"{"a":1}"#, (Some(2), Some(7)), (Some(2), Some(7)));
+        assert_eq!(opened, Ok(json!({ "a": 1 })), "{mode:?}");
+        let opened = __fuzz::reseal(mode, "[]", (None, None), (None, None));
+        assert_eq!(opened, Ok(json!([])), "{mode:?}");
+    }
+}
+
+#[test]
+fn tampered_ids_are_rejected() {
+    for mode in MODES {
+        assert!(__fuzz::reseal(mode, "1", (Some(2), Some(7)), (Some(2), Some(8))).is_err());
+        assert!(__fuzz::reseal(mode, "1", (Some(2), Some(7)), (Some(2), None)).is_err());
+        assert!(__fuzz::reseal(mode, "1", (Some(2), None), (Some(2), Some(7))).is_err());
+    }
+}
+
+#[test]
+fn tampered_versions_are_rejected() {
+    for mode in MODES {
+        assert!(__fuzz::reseal(mode, "1", (Some(2), Some(7)), (Some(3), Some(7))).is_err());
+        assert!(__fuzz::reseal(mode, "1", (None, Some(7)), (Some(2), Some(7))).is_err());
+    }
+}
+
+#[test]
+fn replayed_messages_are_rejected() {
+    for mode in MODES {
+        let mut bridge = Sealed::new(mode).unwrap();
+        let first = bridge.seal(r#"{"a":1}"#, Some(1)).unwrap();
+        assert_eq!(
+            bridge.receive(first.clone()),
+            Ok(json!({ "a": 1 })),
+            "{mode:?}"
+        );
+        assert!(bridge.receive(first).is_err(), "{mode:?}");
+        let older = bridge.seal("2", Some(1)).unwrap();
+        assert!(bridge.receive(older).is_err(), "{mode:?}");
+        let newer = bridge.seal("3", Some(2)).unwrap();
+        assert_eq!(bridge.receive(newer), Ok(json!(3)), "{mode:?}");
+    }
+}
+
+#[test]
+fn messages_without_ids_are_rejected() {
+    for mode in MODES {
+        let mut bridge = Sealed::new(mode).unwrap();
+        let unstamped = bridge.seal("1", None).unwrap();
+        assert!(bridge.receive(unstamped).is_err(), "{mode:?}");
+    }
+}
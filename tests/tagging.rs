@@ -0,0 +1,136 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+// Enum representations: values must survive each tagging, and encode to the shapes JS
+// expects.
+
+use dx_use_js_bridge::EnumTagging;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum Command {
+    Reset,
+    Move { dx: i32, dy: i32 },
+    Spawn(Unit),
+    Path(Vec<(f32, f32)>, bool),
+    Rename(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Unit {
+    kind: Kind,
+    level: Option<u8>,
+    slots: BTreeMap<u32, Kind>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, PartialOrd, Ord, Eq)]
+enum Kind {
+    Archer,
+    Knight,
+}
+
+fn unit() -> Unit {
+    Unit {
+        kind: Kind::Knight,
+        level: None,
+        slots: BTreeMap::from([(1, Kind::Archer)]),
+    }
+}
+
+fn taggings() -> [EnumTagging; 3] {
+    [
+        EnumTagging::External,
+        EnumTagging::internal("type"),
+        EnumTagging::adjacent("type", "data"),
+    ]
+}
+
+#[test]
+fn variants_round_trip() {
+    let commands = [
+        Command::Reset,
+        Command::Move { dx: 1, dy: -2 },
+        Command::Spawn(unit()),
+    ];
+    for tagging in taggings() {
+        for command in &commands {
+            let value = tagging.to_value(command).unwrap();
+            assert_eq!(
+                tagging.from_value::<Command>(value).unwrap(),
+                *command,
+                "{tagging:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn external_matches_serde_json() {
+    let command = Command::Path(vec![(0.5, 1.0)], true);
+    let value = EnumTagging::External.to_value(&command).unwrap();
+    assert_eq!(value, serde_json::to_value(&command).unwrap());
+}
+
+#[test]
+fn internal_shape() {
+    let tagging = EnumTagging::internal("type");
+    let value = tagging.to_value(&Command::Move { dx: 1, dy: 0 }).unwrap();
+    assert_eq!(value, json!({ "type": "Move", "dx": 1, "dy": 0 }));
+    let value = tagging.to_value(&Command::Spawn(unit())).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "Spawn",
+            "kind": { "type": "Knight" },
+            "level": null,
+            "slots": { "1": { "type": "Archer" } },
+        })
+    );
+}
+
+#[test]
+fn internal_rejects_variants_without_fields() {
+    let tagging = EnumTagging::internal("type");
+    assert!(tagging.to_value(&Command::Rename("a".into())).is_err());
+    assert!(tagging.to_value(&Command::Path(Vec::new(), false)).is_err());
+}
+
+#[test]
+fn adjacent_shape() {
+    let tagging = EnumTagging::adjacent("type", "data");
+    assert_eq!(
+        tagging.to_value(&Command::Reset).unwrap(),
+        json!({ "type": "Reset" })
+    );
+    let command = Command::Path(vec![(0.5, 1.0)], true);
+    let value = tagging.to_value(&command).unwrap();
+    assert_eq!(
+        value,
+        json!({ "type": "Path", "data": [[[0.5, 1.0]], true] })
+    );
+    assert_eq!(tagging.from_value::<Command>(value).unwrap(), command);
+    let value = tagging.to_value(&Command::Rename("a".into())).unwrap();
+    assert_eq!(value, json!({ "type": "Rename", "data": "a" }));
+}
+
+#[test]
+fn unit_variants_accept_bare_strings() {
+    let tagging = EnumTagging::internal("type");
+    let parsed: Command = tagging.from_value(Value::String("Reset".into())).unwrap();
+    assert_eq!(parsed, Command::Reset);
+}
+
+#[test]
+fn json_strings_are_parsed() {
+    let tagging = EnumTagging::internal("type");
+    let text = Value::String(r#"{"type":"Move","dx":3,"dy":4}"#.into());
+    let parsed: Command = tagging.from_value(text).unwrap();
+    assert_eq!(parsed, Command::Move { dx: 3, dy: 4 });
+}
+
+#[test]
+fn missing_tag_is_an_error() {
+    let tagging = EnumTagging::adjacent("type", "data");
+    assert!(tagging.from_value::<Command>(json!({ "data": 1 })).is_err());
+}
@@ -0,0 +1,127 @@
+#![cfg(target_arch = "wasm32")]
+
+use dioxus::prelude::*;
+use dx_use_js_bridge::{use_js_bridge, FromJs, JsBridge};
+use futures_util::{FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+type Slot<T> = Rc<RefCell<Option<JsBridge<T>>>>;
+
+fn app<T: FromJs + Clone + Debug>() -> Element {
+    let bridge = use_js_bridge::<T>();
+    use_hook(|| consume_context::<Slot<T>>().replace(Some(bridge.clone())));
+    rsx! {}
+}
+
+/// Mounts a component with a bridge and runs its effects, which register the callback.
+fn mount<T: FromJs + Clone + Debug>() -> (VirtualDom, JsBridge<T>) {
+    let slot: Slot<T> = Rc::default();
+    let mut dom = VirtualDom::new(app::<T>).with_root_context(slot.clone());
+    dom.rebuild_in_place();
+    settle(&mut dom);
+    let bridge = slot
+        .borrow()
+        .clone()
+        .expect("the component stores its bridge");
+    (dom, bridge)
+}
+
+/// Runs the tasks and renders that received messages woke up.
+fn settle(dom: &mut VirtualDom) {
+    for _ in 0..4 {
+        dom.process_events();
+        dom.render_immediate_to_vec();
+    }
+}
+
+/// Calls `window.dxBridge.send(id, JSON.parse(json))` like page JS would.
+fn send_from_js(callback_id: &str, json: &str) -> bool {
+    let window = web_sys::window().unwrap();
+    let registry = js_sys::Reflect::get(&window, &"dxBridge".into()).unwrap();
+    let send: js_sys::Function = js_sys::Reflect::get(&registry, &"send".into())
+        .unwrap()
+        .dyn_into()
+        .unwrap();
+    let value = js_sys::JSON::parse(json).unwrap();
+    send.call2(&registry, &callback_id.into(), &value)
+        .unwrap()
+        .as_bool()
+        .unwrap()
+}
+
+/// Sends `value` from JS, and from Rust to the bridge's JS callback, checking that both
+/// arrive intact.
+fn round_trip<T>(value: T)
+where
+    T: FromJs + Serialize + Clone + Debug + PartialEq,
+{
+    let (mut dom, bridge) = mount::<T>();
+    let mut received = dom.in_runtime(|| bridge.stream());
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(send_from_js(&bridge.callback_id(), &json));
+    settle(&mut dom);
+    assert_eq!(received.try_next().unwrap(), Some(value.clone()));
+    assert_eq!(dom.in_runtime(|| bridge.get_data()), Some(value.clone()));
+
+    let sent = dom.in_runtime(|| bridge.clone().send_to_js(&value).now_or_never());
+    assert_eq!(sent, Some(Ok(())));
+    settle(&mut dom);
+    assert_eq!(received.try_next().unwrap(), Some(value));
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Circle { radius: f64 },
+    Group(Vec<Shape>),
+    Empty,
+}
+
+#[wasm_bindgen_test]
+fn unicode_strings() {
+    round_trip("héllo, 世界 👋 \"quoted\" \n newline".to_string());
+}
+
+#[wasm_bindgen_test]
+fn nested_enums() {
+    round_trip(Shape::Group(vec![
+        Shape::Circle { radius: 1.5 },
+        Shape::Group(vec![Shape::Empty]),
+    ]));
+}
+
+#[wasm_bindgen_test]
+fn large_arrays() {
+    round_trip((0..100_000u32).collect::<Vec<_>>());
+}
+
+#[wasm_bindgen_test]
+fn messages_sent_before_registration_are_replayed() {
+    // Defines the registry if no bridge did yet, as the snippet in the page's head would
+    js_sys::eval(dx_use_js_bridge::INBOUND_QUEUE_JS).unwrap();
+    assert!(!send_from_js("early", "\"queued\""));
+    let raw = dx_use_js_bridge::RawBridge::with_id("early");
+    let mut messages = raw.listen();
+    let first = messages.next().now_or_never().flatten();
+    assert_eq!(first, Some(Ok(serde_json::json!("queued"))));
+    raw.close();
+}
+
+#[wasm_bindgen_test]
+fn undecodable_messages_set_the_error() {
+    let (mut dom, bridge) = mount::<u32>();
+    assert!(send_from_js(
+        &bridge.callback_id(),
+        "{\"not\": \"a number\"}"
+    ));
+    settle(&mut dom);
+    assert_eq!(dom.in_runtime(|| bridge.get_data()), None);
+    assert!(dom.in_runtime(|| bridge.get_error()).is_some());
+}